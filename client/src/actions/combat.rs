@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use common::{protocol::HeroId, replay::LoggedAction};
+
+use crate::{
+    core_gameplay::{components::Owner, resources::Players},
+    map::resources::HexGrid,
+    player::components::{AttackPoints, DefensePoints, Health, HeroUnits},
+};
+
+use super::Action;
+
+/// Attack of a hero without an army.
+pub const HERO_ATTACK: i32 = 10;
+
+/// A hero attacks an enemy hero next to it. The damage is the attacker's
+/// strength less the defender's defense, and a hero without health left is
+/// removed along with its army.
+pub struct AttackAction {
+    pub attacker: Entity,
+    pub target: Entity,
+}
+
+impl Action for AttackAction {
+    fn execute(&self, world: &mut World) -> bool {
+        if !self.are_enemies(world) {
+            return false;
+        }
+
+        let (Some(from), Some(to)) = (
+            world.get::<Transform>(self.attacker),
+            world.get::<Transform>(self.target),
+        ) else {
+            return false;
+        };
+        let grid = world.resource::<HexGrid>();
+        let from = grid.world_pos_to_hex(Vec2::new(from.translation.x, from.translation.z));
+        let to = grid.world_pos_to_hex(Vec2::new(to.translation.x, to.translation.z));
+        if grid.shape.distance(from, to) != 1 {
+            return false;
+        }
+
+        let attack = HERO_ATTACK + army_total::<AttackPoints>(world, self.attacker, |a| a.0);
+        let defense = army_total::<DefensePoints>(world, self.target, |d| d.0);
+        let damage = (attack - defense).max(1) as u32;
+
+        let Some(mut health) = world.get_mut::<Health>(self.target) else {
+            return true;
+        };
+        health.current = health.current.saturating_sub(damage);
+        if health.current == 0 {
+            despawn_hero(world, self.target);
+        }
+
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "attack"
+    }
+
+    fn cost(&self) -> u32 {
+        1
+    }
+
+    fn logged(&self, hero_index: &dyn Fn(Entity) -> Option<HeroId>) -> Option<LoggedAction> {
+        Some(LoggedAction::Attack {
+            attacker: hero_index(self.attacker)?,
+            target: hero_index(self.target)?,
+        })
+    }
+}
+
+impl AttackAction {
+    /// Whether the heroes belong to players on different teams.
+    fn are_enemies(&self, world: &World) -> bool {
+        let (Some(attacker), Some(target)) = (
+            world.get::<Owner>(self.attacker),
+            world.get::<Owner>(self.target),
+        ) else {
+            return false;
+        };
+        let players = world.resource::<Players>();
+        let team = |owner: &Owner| players.get(owner.0).map(|player| player.team);
+
+        team(attacker) != team(target)
+    }
+}
+
+/// Sum of a stat over the units in a hero's army.
+fn army_total<T: Component>(world: &World, hero: Entity, value: impl Fn(&T) -> i32) -> i32 {
+    world.get::<HeroUnits>(hero).map_or(0, |units| {
+        units
+            .0
+            .iter()
+            .flatten()
+            .filter_map(|&unit| world.get::<T>(unit))
+            .map(&value)
+            .sum()
+    })
+}
+
+fn despawn_hero(world: &mut World, hero: Entity) {
+    let units: Vec<Entity> = world
+        .get::<HeroUnits>(hero)
+        .map(|units| units.0.iter().flatten().copied().collect())
+        .unwrap_or_default();
+
+    for unit in units {
+        despawn_with_children_recursive(world, unit);
+    }
+    despawn_with_children_recursive(world, hero);
+}
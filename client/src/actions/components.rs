@@ -1 +1,6 @@
 use bevy::prelude::*;
+
+/// Text telling the player why their last action failed. It clears itself
+/// when the timer runs out.
+#[derive(Component)]
+pub struct ActionMessage(pub Timer);
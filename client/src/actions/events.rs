@@ -9,5 +9,16 @@ pub struct NextActorEvent;
 #[derive(Event)]
 pub struct ActionsCompleteEvent;
 
+/// An action of an actor belonging to a player could not be carried out.
 #[derive(Event)]
-pub struct InvalidPlayerActionEvent;
+pub struct InvalidPlayerActionEvent {
+    pub actor: Entity,
+    /// What the actor tried to do, see `Action::name`.
+    pub action: &'static str,
+}
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryCommand {
+    Undo,
+    Redo,
+}
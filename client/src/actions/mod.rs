@@ -1,16 +1,116 @@
 use bevy::prelude::*;
+use common::{protocol::HeroId, replay::LoggedAction};
+use events::{
+    ActionsCompleteEvent, HistoryCommand, InvalidPlayerActionEvent, NextActorEvent, TickEvent,
+};
+use hexx::Hex;
+use resources::{ActionHistory, ActorQueue};
+use systems::{
+    clear_action_history, fade_action_message, handle_history_commands, handle_history_keys,
+    process_action_queue, queue_actions, show_invalid_actions,
+};
+use ui::setup_action_message;
 
+use crate::{
+    core_gameplay::states::AppState, editor::states::EditorState,
+    network::resources::ServerConnection,
+};
+
+pub mod combat;
 pub mod components;
 pub mod events;
+pub mod moves;
 pub mod resources;
 pub mod systems;
+pub mod turns;
+mod ui;
+
+/// Works through the planned actions. Systems that plan actions come before,
+/// so what else they change is in place when the actions are carried out.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionQueueSet;
 
+/// Carries out what heroes and players do. Planning an action for an `Actor`
+/// puts it into the `ActorQueue`, and the queue is worked through one action
+/// at a time, starting with a `TickEvent` and going on with each
+/// `NextActorEvent` until `ActionsCompleteEvent`. An actor carries on with
+/// its plan until it runs out of action points or has to wait, the rest
+/// waits for its owner's next turn. Ctrl+Z takes back what the current player did this
+/// turn and Ctrl+Y carries it out again.
 pub struct ActionsPlugin;
 
 impl Plugin for ActionsPlugin {
-    fn build(&self, app: &mut App) {}
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActorQueue>()
+            .init_resource::<ActionHistory>()
+            .add_event::<TickEvent>()
+            .add_event::<NextActorEvent>()
+            .add_event::<ActionsCompleteEvent>()
+            .add_event::<InvalidPlayerActionEvent>()
+            .add_event::<HistoryCommand>()
+            .add_systems(OnEnter(AppState::InGame), setup_action_message)
+            .add_systems(
+                Update,
+                (
+                    queue_actions,
+                    process_action_queue
+                        .run_if(on_event::<TickEvent>().or_else(on_event::<NextActorEvent>())),
+                    show_invalid_actions,
+                    fade_action_message,
+                    clear_action_history,
+                )
+                    .chain()
+                    .in_set(ActionQueueSet)
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(
+                Update,
+                // the editor has its own undo, and online the server keeps
+                // track of heroes
+                (handle_history_keys, handle_history_commands)
+                    .chain()
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(EditorState::Closed))
+                    .run_if(not(resource_exists::<ServerConnection>)),
+            );
+    }
 }
 
 pub trait Action: Send + Sync {
     fn execute(&self, world: &mut World) -> bool;
+
+    /// What the action does, as in "could not move".
+    fn name(&self) -> &'static str;
+
+    /// Action points it takes. An actor short of them waits for its next
+    /// turn before carrying it out.
+    fn cost(&self) -> u32 {
+        0
+    }
+
+    /// Whether the actor has to wait for its next turn before carrying the
+    /// action out, for another reason than being short of action points.
+    fn waits(&self, _world: &World) -> bool {
+        false
+    }
+
+    /// An action taking back what this one is about to do, made before it is
+    /// carried out. Actions that reveal something the player didn't know,
+    /// like how a fight goes, have none and can't be taken back.
+    fn inverse(&self, _world: &World) -> Option<Box<dyn Action>> {
+        None
+    }
+
+    /// Hex the action takes its actor to, if it moves it. Simultaneous orders
+    /// for the same hex conflict.
+    fn target(&self) -> Option<Hex> {
+        None
+    }
+
+    /// The action as it goes into the command log, with its heroes numbered
+    /// by `hero_index`. Actions that only ever follow from another one, like
+    /// taking a move back, aren't logged themselves.
+    fn logged(&self, _hero_index: &dyn Fn(Entity) -> Option<HeroId>) -> Option<LoggedAction> {
+        None
+    }
 }
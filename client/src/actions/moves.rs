@@ -0,0 +1,234 @@
+use bevy::{prelude::*, utils::HashMap};
+use common::{
+    map::{components::Tile, pathfinding::find_path, rivers::Rivers},
+    protocol::{ClientMessage, HeroId},
+    replay::LoggedAction,
+};
+use hexx::Hex;
+
+use crate::{
+    map::resources::HexGrid,
+    network::{components::ServerHero, resources::ServerConnection, utils::send},
+    player::components::{
+        HasCalculatedFieldOfMovement, HasCalculatedPath, HasMoved, Hero, Journey, MovementPoints,
+    },
+};
+
+use super::Action;
+
+/// Moves a hero onto a hex its movement points reach, unless another hero got
+/// there first. A hero that moved this turn waits for the next one. The
+/// points the path takes are spent, and a hero on a journey gets that far
+/// along it. Online the move is sent to the server instead, which moves the
+/// hero if it agrees.
+pub struct MoveAction {
+    pub hero: Entity,
+    pub to: Hex,
+}
+
+impl Action for MoveAction {
+    fn execute(&self, world: &mut World) -> bool {
+        if world.get::<HasMoved>(self.hero).is_some() {
+            return false;
+        }
+
+        let mut heroes = world.query_filtered::<(Entity, &Transform), With<Hero>>();
+        let grid = world.resource::<HexGrid>();
+        let occupied = heroes.iter(world).any(|(entity, transform)| {
+            let position = Vec2::new(transform.translation.x, transform.translation.z);
+            entity != self.hero && grid.world_pos_to_hex(position) == self.to
+        });
+        if occupied {
+            return false;
+        }
+        let position = grid.layout.hex_to_world_pos(self.to);
+
+        let (Some(transform), Some(points)) = (
+            world.get::<Transform>(self.hero),
+            world.get::<MovementPoints>(self.hero),
+        ) else {
+            return false;
+        };
+        let start =
+            grid.world_pos_to_hex(Vec2::new(transform.translation.x, transform.translation.z));
+        let rivers = world.get_resource::<Rivers>().cloned().unwrap_or_default();
+        let cost = match find_path(&tiles(world), &grid.shape, &rivers, start, self.to) {
+            Some((path, cost)) if !path.is_empty() && cost <= points.0 => cost,
+            _ => return false,
+        };
+
+        if let Some(&ServerHero { id }) = world.get::<ServerHero>(self.hero) {
+            let Some(mut connection) = world.get_resource_mut::<ServerConnection>() else {
+                return false;
+            };
+            let sent = send(
+                &mut connection,
+                &ClientMessage::MoveHero {
+                    hero: id,
+                    to: self.to,
+                },
+            );
+            if sent {
+                walk_journey(world, self.hero, self.to);
+            }
+            return sent;
+        }
+
+        let mut hero = world.entity_mut(self.hero);
+        if let Some(mut transform) = hero.get_mut::<Transform>() {
+            transform.translation = Vec3::new(position.x, transform.translation.y, position.y);
+        }
+        if let Some(mut points) = hero.get_mut::<MovementPoints>() {
+            points.0 -= cost;
+        }
+        hero.insert(HasMoved);
+        walk_journey(world, self.hero, self.to);
+
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "move"
+    }
+
+    fn cost(&self) -> u32 {
+        1
+    }
+
+    fn waits(&self, world: &World) -> bool {
+        world.get::<HasMoved>(self.hero).is_some()
+    }
+
+    fn inverse(&self, world: &World) -> Option<Box<dyn Action>> {
+        TakeBackAction::of(world, self.hero)
+    }
+
+    fn target(&self) -> Option<Hex> {
+        Some(self.to)
+    }
+
+    fn logged(&self, hero_index: &dyn Fn(Entity) -> Option<HeroId>) -> Option<LoggedAction> {
+        Some(LoggedAction::Move {
+            hero: hero_index(self.hero)?,
+            to: self.to,
+        })
+    }
+}
+
+/// A move a hero on a journey makes by itself as its owner's turn starts. It
+/// follows from the journey order, so unlike an ordered move it isn't logged.
+pub struct JourneyMoveAction(pub MoveAction);
+
+impl Action for JourneyMoveAction {
+    fn execute(&self, world: &mut World) -> bool {
+        self.0.execute(world)
+    }
+
+    fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    fn cost(&self) -> u32 {
+        self.0.cost()
+    }
+
+    fn waits(&self, world: &World) -> bool {
+        self.0.waits(world)
+    }
+
+    fn inverse(&self, world: &World) -> Option<Box<dyn Action>> {
+        self.0.inverse(world)
+    }
+
+    fn target(&self) -> Option<Hex> {
+        self.0.target()
+    }
+}
+
+/// Puts a hero back where it stood with the movement points and journey it
+/// had, and lets it move again unless it had moved already.
+pub struct TakeBackAction {
+    pub hero: Entity,
+    pub hex: Hex,
+    pub movement_points: Option<u32>,
+    pub journey: Option<Journey>,
+    pub moved: bool,
+}
+
+impl TakeBackAction {
+    /// Takes the hero back to where and how it stands now.
+    pub fn of(world: &World, hero: Entity) -> Option<Box<dyn Action>> {
+        let transform = world.get::<Transform>(hero)?;
+        let grid = world.resource::<HexGrid>();
+        let hex =
+            grid.world_pos_to_hex(Vec2::new(transform.translation.x, transform.translation.z));
+        let movement_points = world.get::<MovementPoints>(hero).map(|points| points.0);
+        let journey = world.get::<Journey>(hero).cloned();
+        let moved = world.get::<HasMoved>(hero).is_some();
+
+        Some(Box::new(TakeBackAction {
+            hero,
+            hex,
+            movement_points,
+            journey,
+            moved,
+        }))
+    }
+}
+
+impl Action for TakeBackAction {
+    fn execute(&self, world: &mut World) -> bool {
+        let position = world
+            .resource::<HexGrid>()
+            .layout
+            .hex_to_world_pos(self.hex);
+        let Some(mut hero) = world.get_entity_mut(self.hero) else {
+            return false;
+        };
+
+        if let Some(mut transform) = hero.get_mut::<Transform>() {
+            transform.translation = Vec3::new(position.x, transform.translation.y, position.y);
+        }
+        if let (Some(mut points), Some(movement_points)) =
+            (hero.get_mut::<MovementPoints>(), self.movement_points)
+        {
+            points.0 = movement_points;
+        }
+        match &self.journey {
+            Some(journey) => hero.insert(journey.clone()),
+            None => hero.remove::<Journey>(),
+        };
+        if !self.moved {
+            hero.remove::<(HasMoved, HasCalculatedFieldOfMovement, HasCalculatedPath)>();
+        }
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "take back the move"
+    }
+}
+
+/// Takes the hexes up to `to` off the hero's journey, and ends the journey
+/// once nothing is left of it.
+fn walk_journey(world: &mut World, hero: Entity, to: Hex) {
+    let Some(mut journey) = world.get_mut::<Journey>(hero) else {
+        return;
+    };
+    if let Some(index) = journey.path.iter().position(|&hex| hex == to) {
+        journey.path.drain(..=index);
+    }
+    if journey.path.is_empty() {
+        world.entity_mut(hero).remove::<Journey>();
+    }
+}
+
+/// Current tiles of the grid keyed by hex.
+fn tiles(world: &World) -> HashMap<Hex, Tile> {
+    world
+        .resource::<HexGrid>()
+        .entities
+        .iter()
+        .filter_map(|(&hex, &entity)| Some((hex, world.get::<Tile>(entity)?.clone())))
+        .collect()
+}
@@ -2,5 +2,71 @@ use std::collections::VecDeque;
 
 use bevy::prelude::*;
 
+use super::Action;
+
 #[derive(Default, Resource)]
 pub struct ActorQueue(pub VecDeque<Entity>);
+
+/// An action the current player carried out, and the one taking it back.
+pub struct HistoryStep {
+    pub actor: Entity,
+    pub action: Box<dyn Action>,
+    pub inverse: Box<dyn Action>,
+    /// Action points it took, given back when it is taken back.
+    pub spent: u32,
+}
+
+/// Undo and redo stacks of the current player's actions. Cleared when their
+/// turn ends.
+#[derive(Default, Resource)]
+pub struct ActionHistory {
+    undo: Vec<HistoryStep>,
+    redo: Vec<HistoryStep>,
+}
+
+impl ActionHistory {
+    /// Adds an action that was carried out. Without an inverse neither it nor
+    /// anything before it can be taken back.
+    pub fn record(
+        &mut self,
+        actor: Entity,
+        action: Box<dyn Action>,
+        inverse: Option<Box<dyn Action>>,
+        spent: u32,
+    ) {
+        self.redo.clear();
+
+        match inverse {
+            Some(inverse) => self.undo.push(HistoryStep {
+                actor,
+                action,
+                inverse,
+                spent,
+            }),
+            None => self.undo.clear(),
+        }
+    }
+
+    /// The step to take back next. Hand it to `undone` once it is.
+    pub fn take_undo(&mut self) -> Option<HistoryStep> {
+        self.undo.pop()
+    }
+
+    pub fn undone(&mut self, step: HistoryStep) {
+        self.redo.push(step);
+    }
+
+    /// The step to carry out again next. Hand it to `redone` once it is.
+    pub fn take_redo(&mut self) -> Option<HistoryStep> {
+        self.redo.pop()
+    }
+
+    pub fn redone(&mut self, step: HistoryStep) {
+        self.undo.push(step);
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
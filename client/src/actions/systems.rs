@@ -1,12 +1,53 @@
 use bevy::prelude::*;
+use common::{replay::LoggedAction, setup::TurnMode};
 
-use crate::core_gameplay::components::{Actor, Player};
+use crate::{
+    core_gameplay::{
+        components::{ActionPoints, Actor, Owner},
+        events::TurnEndEvent,
+        resources::TurnManager,
+        states::GameplayState,
+    },
+    player::components::{Hero, HeroIndex},
+    replay::resources::CommandRecorder,
+};
 
 use super::{
-    events::{ActionsCompleteEvent, InvalidPlayerActionEvent, NextActorEvent},
-    resources::ActorQueue,
+    components::ActionMessage,
+    events::{
+        ActionsCompleteEvent, HistoryCommand, InvalidPlayerActionEvent, NextActorEvent, TickEvent,
+    },
+    resources::{ActionHistory, ActorQueue},
 };
 
+/// Puts actors that were just given an action into the queue and starts
+/// carrying out their plans. With simultaneous turns heroes keep their orders until
+/// the turn ends instead, see `simultaneous`.
+pub fn queue_actions(
+    mut queue: ResMut<ActorQueue>,
+    turn_manager: Res<TurnManager>,
+    actors: Query<(Entity, &Actor, Has<Hero>), Changed<Actor>>,
+    mut ev_tick: EventWriter<TickEvent>,
+) {
+    let simultaneous = turn_manager.mode == TurnMode::Simultaneous;
+    let mut queued = false;
+
+    for (entity, actor, is_hero) in actors.iter() {
+        if actor.0.is_empty() || (simultaneous && is_hero) || queue.0.contains(&entity) {
+            continue;
+        }
+
+        queue.0.push_back(entity);
+        queued = true;
+    }
+
+    if queued {
+        ev_tick.send(TickEvent);
+    }
+}
+
+/// Carries out the next planned action of the actor at the front of the
+/// queue. An actor with more to do that it can still afford stays in front.
 pub fn process_action_queue(world: &mut World) {
     let Some(mut queue) = world.get_resource_mut::<ActorQueue>() else {
         return;
@@ -16,14 +57,184 @@ pub fn process_action_queue(world: &mut World) {
         return;
     };
 
-    let Some(mut actor) = world.get_mut::<Actor>(entity) else {
+    // actors that left, have nothing to do or can't carry out what's next
+    // this turn are skipped, their plans wait for the next turn
+    let points = action_points(world, entity);
+    let ready = world
+        .get::<Actor>(entity)
+        .and_then(|actor| actor.0.front())
+        .is_some_and(|next| {
+            if points.is_some_and(|points| points < next.cost()) {
+                return false;
+            }
+            !next.waits(world)
+        });
+    let action = ready
+        .then(|| world.get_mut::<Actor>(entity)?.0.pop_front())
+        .flatten();
+    let Some(action) = action else {
+        world.send_event(NextActorEvent);
         return;
     };
-    let Some(action) = actor.0.take() else { return };
 
-    if !action.execute(world) && world.get::<Player>(entity).is_some() {
-        world.send_event(InvalidPlayerActionEvent);
-        return;
+    let inverse = action.inverse(world);
+    // logged first, a hero it involves may not survive it
+    let logged = action.logged(&|hero| world.get::<HeroIndex>(hero).map(|index| index.0));
+    let done = action.execute(world);
+    let owner = world.get::<Owner>(entity).map(|owner| owner.0);
+    let by_player = owner.is_some();
+
+    if done {
+        if let Some(mut left) = world.get_mut::<ActionPoints>(entity) {
+            left.0 = left.0.saturating_sub(action.cost());
+        }
+    }
+    let spent = points
+        .unwrap_or(0)
+        .saturating_sub(action_points(world, entity).unwrap_or(0));
+    let planned = world.get_mut::<Actor>(entity).map(|mut actor| {
+        // the rest of the plan counted on this action
+        if !done {
+            actor.0.clear();
+        }
+        !actor.0.is_empty()
+    });
+    if planned == Some(true) {
+        world.resource_mut::<ActorQueue>().0.push_front(entity);
+    }
+
+    // players hear about their failed actions, the queue moves on either way
+    if !done && by_player {
+        world.send_event(InvalidPlayerActionEvent {
+            actor: entity,
+            action: action.name(),
+        });
+    }
+    // only what players do during their own turn is logged and can be taken
+    // back, what happens between turns follows from it
+    let players_turn = world
+        .resource::<State<GameplayState>>()
+        .get()
+        .player()
+        .is_some();
+    if done && by_player && players_turn {
+        if let (Some(player), Some(logged)) = (owner, logged) {
+            let turn = world.resource::<TurnManager>().current_turn;
+            world
+                .resource_mut::<CommandRecorder>()
+                .record(turn, player, logged);
+        }
+        world
+            .resource_mut::<ActionHistory>()
+            .record(entity, action, inverse, spent);
     }
     world.send_event(NextActorEvent);
 }
+
+pub fn handle_history_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut ev_history_command: EventWriter<HistoryCommand>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if (keys.just_pressed(KeyCode::KeyZ) && shift) || keys.just_pressed(KeyCode::KeyY) {
+        ev_history_command.send(HistoryCommand::Redo);
+    } else if keys.just_pressed(KeyCode::KeyZ) {
+        ev_history_command.send(HistoryCommand::Undo);
+    }
+}
+
+/// Takes back the current player's last action, or carries out again the
+/// last one taken back. If that fails the history is dropped.
+pub fn handle_history_commands(world: &mut World) {
+    let commands: Vec<HistoryCommand> = world
+        .resource_mut::<Events<HistoryCommand>>()
+        .drain()
+        .collect();
+
+    for command in commands {
+        let mut history = world.resource_mut::<ActionHistory>();
+        let step = match command {
+            HistoryCommand::Undo => history.take_undo(),
+            HistoryCommand::Redo => history.take_redo(),
+        };
+        let Some(step) = step else {
+            continue;
+        };
+
+        // action points go back with the action, and have to be there again
+        // to carry it out again
+        let points = action_points(world, step.actor);
+        let done = match command {
+            HistoryCommand::Undo => step.inverse.execute(world),
+            HistoryCommand::Redo if points.is_some_and(|points| points < step.spent) => false,
+            HistoryCommand::Redo => step.action.execute(world),
+        };
+        if let (true, Some(mut points)) = (done, world.get_mut::<ActionPoints>(step.actor)) {
+            match command {
+                HistoryCommand::Undo => points.0 += step.spent,
+                HistoryCommand::Redo => points.0 = points.0.saturating_sub(step.spent),
+            }
+        }
+        let mut history = world.resource_mut::<ActionHistory>();
+        match (done, command) {
+            (true, HistoryCommand::Undo) => history.undone(step),
+            (true, HistoryCommand::Redo) => history.redone(step),
+            (false, _) => history.clear(),
+        }
+
+        let turn_manager = world.resource::<TurnManager>();
+        let turn = turn_manager.current_turn;
+        if let (true, Some(player)) = (done, turn_manager.current_state.player()) {
+            let logged = match command {
+                HistoryCommand::Undo => LoggedAction::Undo,
+                HistoryCommand::Redo => LoggedAction::Redo,
+            };
+            world
+                .resource_mut::<CommandRecorder>()
+                .record(turn, player, logged);
+        }
+    }
+}
+
+pub fn clear_action_history(
+    mut ev_turn_end: EventReader<TurnEndEvent>,
+    mut history: ResMut<ActionHistory>,
+) {
+    if ev_turn_end.read().count() > 0 {
+        history.clear();
+    }
+}
+
+/// Tells the player which of their actions failed.
+pub fn show_invalid_actions(
+    mut ev_invalid_action: EventReader<InvalidPlayerActionEvent>,
+    names: Query<&Name>,
+    mut messages: Query<(&mut Text, &mut ActionMessage)>,
+) {
+    for event in ev_invalid_action.read() {
+        let actor = names
+            .get(event.actor)
+            .map_or("Someone", |name| name.as_str());
+
+        for (mut text, mut message) in messages.iter_mut() {
+            text.sections[0].value = format!("{actor} could not {}", event.action);
+            message.0.reset();
+        }
+    }
+}
+
+pub fn fade_action_message(time: Res<Time>, mut messages: Query<(&mut Text, &mut ActionMessage)>) {
+    for (mut text, mut message) in messages.iter_mut() {
+        if message.0.tick(time.delta()).just_finished() {
+            text.sections[0].value.clear();
+        }
+    }
+}
+
+fn action_points(world: &World, actor: Entity) -> Option<u32> {
+    world.get::<ActionPoints>(actor).map(|points| points.0)
+}
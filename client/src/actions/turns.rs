@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+use common::{
+    protocol::{HeroId, PlayerId},
+    replay::LoggedAction,
+};
+
+use crate::{
+    core_gameplay::{components::ActionPoints, events::TurnEndEvent, resources::TurnManager},
+    player::components::HasMoved,
+};
+
+use super::{moves::TakeBackAction, Action};
+
+/// Lets a hero sit out the rest of the turn, using up its action points.
+pub struct WaitAction {
+    pub hero: Entity,
+}
+
+impl Action for WaitAction {
+    fn execute(&self, world: &mut World) -> bool {
+        let Some(mut hero) = world.get_entity_mut(self.hero) else {
+            return false;
+        };
+
+        hero.insert(HasMoved);
+        if let Some(mut points) = hero.get_mut::<ActionPoints>() {
+            points.0 = 0;
+        }
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "wait"
+    }
+
+    fn inverse(&self, world: &World) -> Option<Box<dyn Action>> {
+        TakeBackAction::of(world, self.hero)
+    }
+
+    fn logged(&self, hero_index: &dyn Fn(Entity) -> Option<HeroId>) -> Option<LoggedAction> {
+        Some(LoggedAction::Wait {
+            hero: hero_index(self.hero)?,
+        })
+    }
+}
+
+/// Ends a player's turn, after everything queued before it was carried out.
+pub struct EndTurnAction {
+    pub player: PlayerId,
+}
+
+impl Action for EndTurnAction {
+    fn execute(&self, world: &mut World) -> bool {
+        let turn_manager = world.resource::<TurnManager>();
+        if turn_manager.current_state.player() != Some(self.player) {
+            return false;
+        }
+
+        world.send_event(TurnEndEvent {
+            player_id: self.player,
+        });
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "end the turn"
+    }
+
+    fn logged(&self, _hero_index: &dyn Fn(Entity) -> Option<HeroId>) -> Option<LoggedAction> {
+        Some(LoggedAction::EndTurn)
+    }
+}
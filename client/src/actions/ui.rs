@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use super::components::ActionMessage;
+
+pub fn setup_action_message(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::top(Val::Px(16.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            Pickable::IGNORE,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::rgb(1.0, 0.8, 0.3),
+                        ..default()
+                    },
+                ),
+                ActionMessage(Timer::from_seconds(3.0, TimerMode::Once)),
+            ));
+        });
+}
@@ -1,9 +1,41 @@
+use std::collections::VecDeque;
+
 use bevy::prelude::*;
+use common::protocol::PlayerId;
 
 use crate::actions::Action;
 
+/// The actor of a player, for what they do besides commanding heroes.
 #[derive(Component)]
 pub struct Player;
 
+/// The player an entity belongs to.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Owner(pub PlayerId);
+
+#[derive(Component)]
+pub struct EndTurnButton;
+
+/// What an entity is going to do, in order. Whatever it has no action points
+/// left for waits for its owner's next turn.
 #[derive(Component, Default)]
-pub struct Actor(pub Option<Box<dyn Action>>);
+pub struct Actor(pub VecDeque<Box<dyn Action>>);
+
+impl Actor {
+    /// Plans `action` after everything planned so far, or instead of it.
+    pub fn plan(&mut self, action: Box<dyn Action>, append: bool) {
+        if !append {
+            self.0.clear();
+        }
+        self.0.push_back(action);
+    }
+}
+
+/// Action points an actor has left this turn. Actors without any are not
+/// limited.
+#[derive(Component, Debug)]
+pub struct ActionPoints(pub u32);
+
+/// Action points an actor gets back at the start of its owner's turn.
+#[derive(Component, Debug)]
+pub struct MaxActionPoints(pub u32);
@@ -1,11 +1,12 @@
 use bevy::prelude::*;
+use common::protocol::PlayerId;
 
 #[derive(Event)]
 pub struct TurnStartEvent {
-    pub player_id: u32, // player that started their turn
+    pub player_id: PlayerId, // player that started their turn
 }
 
 #[derive(Event)]
 pub struct TurnEndEvent {
-    pub player_id: u32, // player that ended their turn
+    pub player_id: PlayerId, // player that ended their turn
 }
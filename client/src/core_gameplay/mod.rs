@@ -1,16 +1,21 @@
 use bevy::prelude::*;
 use events::{TurnEndEvent, TurnStartEvent};
-use resources::TurnManager;
-use states::GameplayState;
-use systems::turn_end_system;
+use resources::{Players, TurnManager};
+use states::{AppState, GameplayState};
+use systems::{
+    continue_journeys_system, end_turn_input_system, refresh_units_system, resume_plans_system,
+    spawn_player_actors, start_first_turn_system, turn_end_system,
+};
 use ui::setup_ui;
 
+use crate::network::resources::ServerConnection;
+
 pub mod components;
 pub mod events;
-mod resources;
+pub mod resources;
 pub mod states;
 mod systems;
-mod utils;
+pub mod utils;
 mod ui;
 
 pub struct CoreGameplayPlugin;
@@ -18,10 +23,32 @@ pub struct CoreGameplayPlugin;
 impl Plugin for CoreGameplayPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<TurnManager>()
+            .init_resource::<Players>()
+            .init_state::<AppState>()
             .init_state::<GameplayState>()
             .add_event::<TurnStartEvent>()
             .add_event::<TurnEndEvent>()
-            .add_systems(Startup, setup_ui)
-            .add_systems(Update, (turn_end_system,));
+            .add_systems(
+                OnEnter(AppState::InGame),
+                (setup_ui, start_first_turn_system),
+            )
+            .add_systems(
+                Update,
+                spawn_player_actors.run_if(resource_changed::<Players>),
+            )
+            .add_systems(
+                Update,
+                (
+                    end_turn_input_system,
+                    // online the network plugin sends the turn end to the server
+                    turn_end_system.run_if(not(resource_exists::<ServerConnection>)),
+                    refresh_units_system,
+                    // online the moves go to the server from the action queue
+                    continue_journeys_system,
+                    resume_plans_system,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::InGame)),
+            );
     }
 }
@@ -1,4 +1,8 @@
 use bevy::prelude::*;
+use common::{
+    protocol::PlayerId,
+    setup::{default_color, default_name, MaxTurns, SlotKind, TurnMode},
+};
 
 use super::states::GameplayState;
 
@@ -6,37 +10,151 @@ use super::states::GameplayState;
 pub struct TurnManager {
     pub current_turn: u32,
     pub max_turns: u32,
+    pub mode: TurnMode,
     pub current_state: GameplayState,
+    /// Players in the order they take their turns.
+    pub turn_order: Vec<PlayerId>,
 }
 
-pub enum MaxTurns {
-    Quick,
-    Normal,
-    Long,
-    LongLongLong,
-    Marathon,
-    Infinite,
+impl Default for TurnManager {
+    fn default() -> Self {
+        TurnManager {
+            current_turn: 1,
+            max_turns: MaxTurns::Normal.get_max_turns(),
+            mode: TurnMode::default(),
+            current_state: GameplayState::default(),
+            turn_order: vec![PlayerId(1)],
+        }
+    }
 }
 
-impl MaxTurns {
-    pub fn get_max_turns(&self) -> u32 {
-        match self {
-            MaxTurns::Quick => 50,
-            MaxTurns::Normal => 100,
-            MaxTurns::Long => 150,
-            MaxTurns::LongLongLong => 175,
-            MaxTurns::Marathon => 200,
-            MaxTurns::Infinite => u32::MAX,
+impl TurnManager {
+    /// The live player who moves after `player`, and whether a new turn
+    /// starts on the way. `None` once nobody is left to play.
+    pub fn next_player(
+        &self,
+        player: PlayerId,
+        is_live: impl Fn(PlayerId) -> bool,
+    ) -> Option<(PlayerId, bool)> {
+        let count = self.turn_order.len();
+        let index = self
+            .turn_order
+            .iter()
+            .position(|&other| other == player)
+            .unwrap_or(count.saturating_sub(1));
+
+        (1..=count)
+            .map(|step| index + step)
+            .find(|&next| is_live(self.turn_order[next % count]))
+            .map(|next| (self.turn_order[next % count], next >= count))
+    }
+
+    /// Ends `player`'s turn and moves to the state that follows it. With
+    /// simultaneous turns the orders are carried out in between turns.
+    pub fn end_turn(
+        &mut self,
+        player: PlayerId,
+        is_live: impl Fn(PlayerId) -> bool,
+    ) -> GameplayState {
+        let state = match self.next_player(player, is_live) {
+            Some((_, true)) if self.mode == TurnMode::Simultaneous => GameplayState::TurnTransition,
+            Some((next, new_turn)) => self.begin(next, new_turn),
+            None => GameplayState::GameOver,
+        };
+
+        self.current_state = state.clone();
+        state
+    }
+
+    /// Starts the next turn once the simultaneous orders are carried out.
+    pub fn end_transition(&mut self, is_live: impl Fn(PlayerId) -> bool) -> GameplayState {
+        let last = self.turn_order.last().copied().unwrap_or(PlayerId(1));
+        let state = match self.next_player(last, is_live) {
+            Some((next, _)) => self.begin(next, true),
+            None => GameplayState::GameOver,
+        };
+
+        self.current_state = state.clone();
+        state
+    }
+
+    fn begin(&mut self, player: PlayerId, new_turn: bool) -> GameplayState {
+        if new_turn {
+            self.current_turn += 1;
+        }
+
+        if self.current_turn > self.max_turns {
+            GameplayState::GameOver
+        } else {
+            GameplayState::PlayerTurn(player)
         }
     }
 }
 
-impl Default for TurnManager {
+#[derive(Debug, Clone)]
+pub struct PlayerInfo {
+    pub id: PlayerId,
+    pub name: String,
+    pub color: Color,
+    /// Players on the same team are allies.
+    pub team: u32,
+    pub kind: SlotKind,
+}
+
+/// Everyone taking part in the game.
+#[derive(Debug, Resource)]
+pub struct Players(pub Vec<PlayerInfo>);
+
+impl Default for Players {
     fn default() -> Self {
-        TurnManager {
-            current_turn: 1,
-            max_turns: MaxTurns::Normal.get_max_turns(),
-            current_state: GameplayState::Player1Turn,
-        }
+        Players::from_slots(&[SlotKind::Human])
+    }
+}
+
+impl Players {
+    /// Players for the slots of a setup, each on their own team.
+    pub fn from_slots(slots: &[SlotKind]) -> Self {
+        Players(
+            slots
+                .iter()
+                .enumerate()
+                .map(|(index, &kind)| {
+                    let id = PlayerId(index as u32 + 1);
+                    let [r, g, b] = default_color(id);
+                    PlayerInfo {
+                        id,
+                        name: default_name(id),
+                        color: Color::rgb(r, g, b),
+                        team: id.0,
+                        kind,
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, id: PlayerId) -> Option<&PlayerInfo> {
+        self.0.iter().find(|player| player.id == id)
+    }
+
+    pub fn is_human(&self, id: PlayerId) -> bool {
+        self.get(id)
+            .is_some_and(|player| player.kind == SlotKind::Human)
+    }
+
+    pub fn color(&self, id: PlayerId) -> Color {
+        self.get(id).map_or(Color::GRAY, |player| player.color)
+    }
+
+    pub fn humans(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|player| player.kind == SlotKind::Human)
+            .count()
+    }
+
+    /// Ids in turn order.
+    pub fn ids(&self) -> Vec<PlayerId> {
+        self.0.iter().map(|player| player.id).collect()
     }
 }
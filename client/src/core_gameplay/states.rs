@@ -1,21 +1,33 @@
 use bevy::prelude::*;
+use common::protocol::PlayerId;
+
+/// Whether the game is still being set up in the lobby or being played.
+#[derive(States, Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum AppState {
+    #[default]
+    Lobby,
+    InGame,
+}
 
 #[derive(States, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum GameplayState {
-    Player1Turn,
-    Player2Turn,
-    Player3Turn,
-    Player4Turn,
-    Player5Turn,
-    Player6Turn,
-    Player7Turn,
-    Player8Turn,
+    PlayerTurn(PlayerId),
     TurnTransition,
     GameOver,
 }
 
 impl Default for GameplayState {
     fn default() -> Self {
-        GameplayState::Player1Turn
+        GameplayState::PlayerTurn(PlayerId(1))
+    }
+}
+
+impl GameplayState {
+    /// The player whose turn it is, if it is anyone's.
+    pub fn player(&self) -> Option<PlayerId> {
+        match self {
+            GameplayState::PlayerTurn(player) => Some(*player),
+            GameplayState::TurnTransition | GameplayState::GameOver => None,
+        }
     }
 }
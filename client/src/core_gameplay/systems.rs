@@ -1,55 +1,256 @@
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use common::{
+    map::{components::Tile, pathfinding::find_path, rivers::Rivers},
+    setup::TurnMode,
+};
+use hexx::Hex;
 
-use crate::player::components::{HasMoved, Hero, Player1Marker};
+use crate::{
+    actions::{
+        events::TickEvent,
+        moves::{JourneyMoveAction, MoveAction},
+        resources::ActorQueue,
+        turns::EndTurnAction,
+    },
+    map::{resources::HexGrid, utils::collect_tiles},
+    network::resources::LocalPlayer,
+    player::{
+        components::{HasMoved, Hero, Journey, MaxMovementPoints, MovementPoints},
+        utils::next_leg,
+    },
+};
 
 use super::{
+    components::{ActionPoints, Actor, EndTurnButton, MaxActionPoints, Owner, Player},
     events::{TurnEndEvent, TurnStartEvent},
-    resources::TurnManager,
+    resources::{Players, TurnManager},
     states::GameplayState,
+    utils::live_players,
 };
 
+/// Gives every player an actor of their own, for the actions that belong to
+/// the player rather than one of their heroes.
+pub fn spawn_player_actors(
+    mut commands: Commands,
+    players: Res<Players>,
+    actors: Query<Entity, With<Player>>,
+) {
+    actors.iter().for_each(|actor| {
+        commands.entity(actor).despawn_recursive();
+    });
+
+    for player in players.0.iter() {
+        commands.spawn((
+            Name::new(player.name.clone()),
+            Player,
+            Owner(player.id),
+            Actor::default(),
+        ));
+    }
+}
+
+/// Ends the turn of whoever's turn it is when the End Turn button or Enter is
+/// pressed. Online only the local player's turn can be ended.
+pub fn end_turn_input_system(
+    interactions: Query<&Interaction, (Changed<Interaction>, With<EndTurnButton>)>,
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameplayState>>,
+    local_player: Option<Res<LocalPlayer>>,
+    mut actors: Query<(&Owner, &mut Actor), With<Player>>,
+) {
+    let Some(player) = state.get().player() else {
+        return;
+    };
+    if local_player.is_some_and(|local| local.0 != player) {
+        return;
+    }
+
+    let pressed = interactions
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    if !pressed && !keys.just_pressed(KeyCode::Enter) {
+        return;
+    }
+
+    if let Some((_, mut actor)) = actors.iter_mut().find(|(owner, _)| owner.0 == player) {
+        actor.plan(Box::new(EndTurnAction { player }), false);
+    }
+}
+
+/// Starts the turn of the player who moves first once the game begins.
+pub fn start_first_turn_system(
+    turn_manager: Res<TurnManager>,
+    mut ev_turn_start: EventWriter<TurnStartEvent>,
+) {
+    if let Some(player) = turn_manager.current_state.player() {
+        ev_turn_start.send(TurnStartEvent { player_id: player });
+    }
+}
+
+/// Hands the turn to the next live player, starting a new turn after the
+/// last one.
 pub fn turn_end_system(
     mut ev_turn_ends: EventReader<TurnEndEvent>,
+    mut ev_turn_start: EventWriter<TurnStartEvent>,
     mut turn_manager: ResMut<TurnManager>,
+    players: Res<Players>,
+    heroes: Query<&Owner, With<Hero>>,
     mut next_state: ResMut<NextState<GameplayState>>,
 ) {
-    ev_turn_ends
-        .read()
-        .for_each(|_| match turn_manager.current_state {
-            GameplayState::Player1Turn => next_state.set(GameplayState::Player2Turn),
-            GameplayState::Player2Turn => next_state.set(GameplayState::Player3Turn),
-            GameplayState::Player3Turn => next_state.set(GameplayState::Player4Turn),
-            GameplayState::Player4Turn => next_state.set(GameplayState::Player5Turn),
-            GameplayState::Player5Turn => next_state.set(GameplayState::Player6Turn),
-            GameplayState::Player6Turn => next_state.set(GameplayState::Player7Turn),
-            GameplayState::Player7Turn => next_state.set(GameplayState::Player8Turn),
-            GameplayState::Player8Turn => {
-                turn_manager.current_turn += 1;
-                next_state.set(GameplayState::Player1Turn);
-
-                if turn_manager.current_turn > turn_manager.max_turns {
-                    next_state.set(GameplayState::GameOver);
-                }
+    let live = live_players(&players, heroes.iter());
+
+    for event in ev_turn_ends.read() {
+        if turn_manager.current_state.player() != Some(event.player_id) {
+            continue;
+        }
+
+        let state = turn_manager.end_turn(event.player_id, |player| live.contains(&player));
+        if let Some(player) = state.player() {
+            ev_turn_start.send(TurnStartEvent { player_id: player });
+        }
+        next_state.set(state);
+    }
+}
+
+/// A unit with the points it gets back at the start of its owner's turn.
+type RefreshedUnit = (
+    Entity,
+    &'static Owner,
+    Option<(&'static mut MovementPoints, &'static MaxMovementPoints)>,
+    Option<(&'static mut ActionPoints, &'static MaxActionPoints)>,
+);
+
+/// Gets the units of the player whose turn starts ready to move and act
+/// again.
+pub fn refresh_units_system(
+    mut commands: Commands,
+    mut units: Query<RefreshedUnit>,
+    mut ev_turn_start: EventReader<TurnStartEvent>,
+) {
+    for event in ev_turn_start.read() {
+        for (entity, owner, movement_points, action_points) in units.iter_mut() {
+            if owner.0 != event.player_id {
+                continue;
             }
-            _ => (),
-        });
+
+            commands.entity(entity).remove::<HasMoved>();
+            if let Some((mut points, max)) = movement_points {
+                points.0 = max.0;
+            }
+            if let Some((mut points, max)) = action_points {
+                points.0 = max.0;
+            }
+        }
+    }
 }
 
-pub fn handle_player1_turn_system(
+/// Sends the heroes of the player whose turn starts on along the paths they
+/// were ordered down in earlier turns. A path that got blocked is found anew,
+/// a hero with no way left stays where it is. The moves follow from the
+/// journey order and aren't logged, a replay makes them the same way.
+pub fn continue_journeys_system(
     mut commands: Commands,
-    state: Res<State<GameplayState>>,
-    hero_query: Query<(Entity, Option<&HasMoved>), (With<Hero>, With<Player1Marker>)>,
     mut ev_turn_start: EventReader<TurnStartEvent>,
+    grid: Res<HexGrid>,
+    rivers: Res<Rivers>,
+    tiles: Query<&Tile>,
+    heroes: Query<(Entity, &Transform), With<Hero>>,
+    mut travellers: Query<(
+        Entity,
+        &Owner,
+        &MovementPoints,
+        &MaxMovementPoints,
+        &mut Journey,
+        &mut Actor,
+    )>,
 ) {
-    if state.get() != &GameplayState::Player1Turn {
-        return;
+    for event in ev_turn_start.read() {
+        let hero_hexes: HashMap<Entity, Hex> = heroes
+            .iter()
+            .map(|(hero, transform)| {
+                let position = Vec2::new(transform.translation.x, transform.translation.z);
+                (hero, grid.world_pos_to_hex(position))
+            })
+            .collect();
+        let mut collected = None;
+
+        for (hero, owner, movement_points, max_movement_points, mut journey, mut actor) in
+            travellers.iter_mut()
+        {
+            if owner.0 != event.player_id {
+                continue;
+            }
+            let Some(&start) = hero_hexes.get(&hero) else {
+                continue;
+            };
+            let map = collected.get_or_insert_with(|| collect_tiles(&grid, &tiles));
+            let occupied: HashSet<Hex> = hero_hexes
+                .iter()
+                .filter(|(&other, _)| other != hero)
+                .map(|(_, &hex)| hex)
+                .collect();
+
+            let on_course = journey
+                .path
+                .first()
+                .is_some_and(|&next| grid.shape.distance(start, next) == 1);
+            if !on_course || journey.path.iter().any(|hex| occupied.contains(hex)) {
+                let mut open = map.clone();
+                open.retain(|hex, _| !occupied.contains(hex));
+                match find_path(&open, &grid.shape, &rivers, start, journey.to) {
+                    Some((path, _)) => journey.path = path,
+                    None => journey.path.clear(),
+                }
+            }
+
+            let (to, way) = next_leg(
+                map,
+                &grid,
+                &rivers,
+                start,
+                &journey.path,
+                movement_points.0,
+                max_movement_points.0,
+            );
+            if let Some(to) = to {
+                actor.plan(Box::new(JourneyMoveAction(MoveAction { hero, to })), true);
+            }
+            // the move walks the hero on, a hero with no way left gives up
+            if way.is_empty() {
+                commands.entity(hero).remove::<Journey>();
+            }
+            journey.path = way;
+        }
     }
+}
 
-    for _ in ev_turn_start.read() {
-        for (entity, has_moved) in hero_query.iter() {
-            if let Some(_) = has_moved {
-                commands.entity(entity).remove::<HasMoved>();
+/// Carries on with what the units of the player whose turn starts planned in
+/// earlier turns. With simultaneous turns that waits for the orders to be
+/// carried out.
+pub fn resume_plans_system(
+    mut ev_turn_start: EventReader<TurnStartEvent>,
+    turn_manager: Res<TurnManager>,
+    mut queue: ResMut<ActorQueue>,
+    actors: Query<(Entity, &Owner, &Actor)>,
+    mut ev_tick: EventWriter<TickEvent>,
+) {
+    for event in ev_turn_start.read() {
+        if turn_manager.mode == TurnMode::Simultaneous {
+            continue;
+        }
+
+        let mut resumed = false;
+        for (entity, owner, actor) in actors.iter() {
+            if owner.0 == event.player_id && !actor.0.is_empty() && !queue.0.contains(&entity) {
+                queue.0.push_back(entity);
+                resumed = true;
             }
         }
+        if resumed {
+            ev_tick.send(TickEvent);
+        }
     }
 }
@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
 
+use super::components::EndTurnButton;
+
 pub fn setup_ui(mut commands: Commands) {
     let root = commands.spawn(NodeBundle {
         style: Style {
@@ -12,7 +14,7 @@ pub fn setup_ui(mut commands: Commands) {
         },
         ..default()
     }).with_children(|parent| {
-        parent.spawn(ButtonBundle {
+        parent.spawn((ButtonBundle {
             style: Style {
                 width: Val::Px(100.0),
                 height: Val::Px(50.0),
@@ -22,7 +24,7 @@ pub fn setup_ui(mut commands: Commands) {
                 ..default()
             },
             ..default()
-        }).with_children(|parent| {
+        }, EndTurnButton)).with_children(|parent| {
             parent.spawn(TextBundle::from_section("End Turn", TextStyle {
                 ..default()
             }));
@@ -0,0 +1,53 @@
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashSet};
+use common::{protocol::PlayerId, setup::TurnMode};
+
+use crate::player::components::Hero;
+
+use super::{
+    components::Owner,
+    events::TurnStartEvent,
+    resources::{Players, TurnManager},
+    states::GameplayState,
+};
+
+/// Players still taking turns: humans with at least one hero left. The AI has
+/// nothing to decide yet, so its turns pass straight away.
+pub fn live_players<'a>(
+    players: &Players,
+    heroes: impl Iterator<Item = &'a Owner>,
+) -> HashSet<PlayerId> {
+    heroes
+        .map(|owner| owner.0)
+        .filter(|&player| players.is_human(player))
+        .collect()
+}
+
+/// Run condition for systems that only run while everyone plays at once.
+pub fn simultaneous_turns(turn_manager: Res<TurnManager>) -> bool {
+    turn_manager.mode == TurnMode::Simultaneous
+}
+
+/// What moving on to another turn takes.
+#[derive(SystemParam)]
+pub struct TurnPasser<'w, 's> {
+    pub turn_manager: ResMut<'w, TurnManager>,
+    players: Res<'w, Players>,
+    heroes: Query<'w, 's, &'static Owner, With<Hero>>,
+    ev_turn_start: EventWriter<'w, TurnStartEvent>,
+    next_state: ResMut<'w, NextState<GameplayState>>,
+}
+
+impl TurnPasser<'_, '_> {
+    pub fn live_players(&self) -> HashSet<PlayerId> {
+        live_players(&self.players, self.heroes.iter())
+    }
+
+    /// Moves on to `state`, starting the turn of its player if it has one.
+    pub fn enter(&mut self, state: GameplayState) {
+        if let Some(player) = state.player() {
+            self.ev_turn_start
+                .send(TurnStartEvent { player_id: player });
+        }
+        self.next_state.set(state);
+    }
+}
@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorCommand {
+    Undo,
+    Redo,
+    /// Writes the map to `MapSettings::export_path` in the map file format.
+    SaveMap,
+}
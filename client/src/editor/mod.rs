@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use bevy_egui::EguiPlugin;
+use events::EditorCommand;
+use resources::{Brush, EditHistory};
+use states::EditorState;
+use systems::{
+    clear_history, draw_editor_panel, handle_editor_commands, handle_editor_keys, paint_tiles,
+    toggle_editor, update_tile_materials,
+};
+
+use crate::core_gameplay::states::AppState;
+
+pub mod events;
+pub mod resources;
+pub mod states;
+mod systems;
+
+/// Map editor, toggled with F2. Paints tiles with a brush while open.
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin);
+        }
+
+        app.init_resource::<Brush>()
+            .init_resource::<EditHistory>()
+            .init_state::<EditorState>()
+            .add_event::<EditorCommand>()
+            .add_systems(OnEnter(EditorState::Open), clear_history)
+            .add_systems(Update, toggle_editor.run_if(in_state(AppState::InGame)))
+            .add_systems(
+                Update,
+                (
+                    draw_editor_panel,
+                    handle_editor_keys,
+                    paint_tiles,
+                    handle_editor_commands,
+                    update_tile_materials,
+                )
+                    .chain()
+                    .run_if(in_state(EditorState::Open)),
+            );
+    }
+}
@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+use common::map::components::{Biome, Tile, TileAttributes, TileResource};
+use hexx::Hex;
+
+/// What painting a resource slot does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceBrush {
+    Keep,
+    Clear,
+    Set(TileResource),
+}
+
+impl ResourceBrush {
+    fn paint(&self, resource: Option<TileResource>) -> Option<TileResource> {
+        match self {
+            ResourceBrush::Keep => resource,
+            ResourceBrush::Clear => None,
+            ResourceBrush::Set(resource) => Some(*resource),
+        }
+    }
+}
+
+/// Changes painted onto every tile under the brush. Parts set to `None` or
+/// `ResourceBrush::Keep` leave the tile as it is.
+#[derive(Debug, Resource)]
+pub struct Brush {
+    /// Rings around the clicked hex that are painted too.
+    pub radius: u32,
+    pub biome: Option<Biome>,
+    pub strategic_resource: ResourceBrush,
+    pub trade_resource: ResourceBrush,
+    pub attributes: Option<TileAttributes>,
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Self {
+            radius: 0,
+            biome: Some(Biome::Plains),
+            strategic_resource: ResourceBrush::Keep,
+            trade_resource: ResourceBrush::Keep,
+            attributes: None,
+        }
+    }
+}
+
+impl Brush {
+    pub fn paint(&self, tile: &Tile) -> Tile {
+        Tile {
+            biome: self.biome.unwrap_or(tile.biome),
+            attributes: self
+                .attributes
+                .clone()
+                .unwrap_or_else(|| tile.attributes.clone()),
+            strategic_resource: self.strategic_resource.paint(tile.strategic_resource),
+            trade_resource: self.trade_resource.paint(tile.trade_resource),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TileEdit {
+    pub hex: Hex,
+    pub before: Tile,
+    pub after: Tile,
+}
+
+/// Undo and redo stacks of brush strokes. A stroke is everything painted
+/// while the mouse button was held. Cleared whenever the editor opens, as
+/// the map may have been regenerated or loaded in between.
+#[derive(Debug, Default, Resource)]
+pub struct EditHistory {
+    undo: Vec<Vec<TileEdit>>,
+    redo: Vec<Vec<TileEdit>>,
+    stroke: Vec<TileEdit>,
+}
+
+impl EditHistory {
+    /// Adds an edit to the current stroke. Painting a hex twice in one stroke
+    /// keeps its state from before the stroke.
+    pub fn record(&mut self, edit: TileEdit) {
+        match self.stroke.iter_mut().find(|stroke| stroke.hex == edit.hex) {
+            Some(existing) => existing.after = edit.after,
+            None => self.stroke.push(edit),
+        }
+    }
+
+    pub fn finish_stroke(&mut self) {
+        if self.stroke.is_empty() {
+            return;
+        }
+
+        self.undo.push(std::mem::take(&mut self.stroke));
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty() || !self.stroke.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Tiles to put back to undo the last stroke.
+    pub fn undo(&mut self) -> Vec<(Hex, Tile)> {
+        self.finish_stroke();
+        let Some(stroke) = self.undo.pop() else {
+            return vec![];
+        };
+
+        let tiles = stroke
+            .iter()
+            .map(|edit| (edit.hex, edit.before.clone()))
+            .collect();
+        self.redo.push(stroke);
+        tiles
+    }
+
+    /// Tiles to put back to redo the last undone stroke.
+    pub fn redo(&mut self) -> Vec<(Hex, Tile)> {
+        let Some(stroke) = self.redo.pop() else {
+            return vec![];
+        };
+
+        let tiles = stroke
+            .iter()
+            .map(|edit| (edit.hex, edit.after.clone()))
+            .collect();
+        self.undo.push(stroke);
+        tiles
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.stroke.clear();
+    }
+}
@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+
+#[derive(States, Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum EditorState {
+    #[default]
+    Closed,
+    Open,
+}
@@ -0,0 +1,260 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_egui::{egui, EguiContexts};
+use common::map::{
+    components::{Biome, ResourceCategory, Tile, TileResource, MAX_ATTRIBUTE},
+    map_file::MapFile,
+};
+
+use crate::map::{
+    resources::{HexGrid, MapSettings},
+    utils::{collect_tiles, get_color_from_biome, HexCursor},
+};
+
+use super::{
+    events::EditorCommand,
+    resources::{Brush, EditHistory, ResourceBrush, TileEdit},
+    states::EditorState,
+};
+
+const MAX_BRUSH_RADIUS: u32 = 8;
+
+pub fn toggle_editor(
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<State<EditorState>>,
+    mut next_state: ResMut<NextState<EditorState>>,
+) {
+    if !keys.just_pressed(KeyCode::F2) {
+        return;
+    }
+
+    match state.get() {
+        EditorState::Closed => next_state.set(EditorState::Open),
+        EditorState::Open => next_state.set(EditorState::Closed),
+    }
+}
+
+pub fn clear_history(mut history: ResMut<EditHistory>) {
+    history.clear();
+}
+
+pub fn draw_editor_panel(
+    mut contexts: EguiContexts,
+    mut brush: ResMut<Brush>,
+    history: Res<EditHistory>,
+    settings: Res<MapSettings>,
+    mut ev_editor_command: EventWriter<EditorCommand>,
+) {
+    egui::Window::new("Map Editor").show(contexts.ctx_mut(), |ui| {
+        ui.add(egui::Slider::new(&mut brush.radius, 0..=MAX_BRUSH_RADIUS).text("Brush radius"));
+        ui.separator();
+
+        let mut paint_biome = brush.biome.is_some();
+        ui.checkbox(&mut paint_biome, "Paint biome");
+        let mut biome = brush.biome.unwrap_or(Biome::Plains);
+        ui.add_enabled_ui(paint_biome, |ui| {
+            egui::ComboBox::from_label("Biome")
+                .selected_text(biome.name())
+                .show_ui(ui, |ui| {
+                    for option in Biome::ALL {
+                        ui.selectable_value(&mut biome, option, option.name());
+                    }
+                });
+        });
+        brush.biome = paint_biome.then_some(biome);
+        ui.separator();
+
+        resource_picker(
+            ui,
+            "Strategic resource",
+            &mut brush.strategic_resource,
+            |resource| resource.is_strategic(),
+        );
+        resource_picker(
+            ui,
+            "Trade resource",
+            &mut brush.trade_resource,
+            |resource| resource.category() != ResourceCategory::Strategic,
+        );
+        ui.separator();
+
+        let mut paint_attributes = brush.attributes.is_some();
+        ui.checkbox(&mut paint_attributes, "Set attributes");
+        let mut attributes = brush.attributes.clone().unwrap_or_default();
+        ui.add_enabled_ui(paint_attributes, |ui| {
+            for (value, label) in [
+                (&mut attributes.production, "Production"),
+                (&mut attributes.science, "Science"),
+                (&mut attributes.attractiveness, "Attractiveness"),
+            ] {
+                ui.add(egui::Slider::new(value, 0..=MAX_ATTRIBUTE).text(label));
+            }
+        });
+        brush.attributes = paint_attributes.then_some(attributes);
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(history.can_undo(), egui::Button::new("Undo"))
+                .clicked()
+            {
+                ev_editor_command.send(EditorCommand::Undo);
+            }
+            if ui
+                .add_enabled(history.can_redo(), egui::Button::new("Redo"))
+                .clicked()
+            {
+                ev_editor_command.send(EditorCommand::Redo);
+            }
+            if ui.button("Save map").clicked() {
+                ev_editor_command.send(EditorCommand::SaveMap);
+            }
+        });
+        ui.label(format!("Saves to {}", settings.export_path.display()));
+    });
+}
+
+fn resource_picker(
+    ui: &mut egui::Ui,
+    label: &str,
+    brush: &mut ResourceBrush,
+    allowed: impl Fn(&TileResource) -> bool,
+) {
+    let name = |brush: &ResourceBrush| match brush {
+        ResourceBrush::Keep => "Keep",
+        ResourceBrush::Clear => "Clear",
+        ResourceBrush::Set(resource) => resource.name(),
+    };
+
+    egui::ComboBox::from_label(label)
+        .selected_text(name(brush))
+        .show_ui(ui, |ui| {
+            let options = [ResourceBrush::Keep, ResourceBrush::Clear]
+                .into_iter()
+                .chain(
+                    TileResource::ALL
+                        .into_iter()
+                        .filter(|resource| allowed(resource))
+                        .map(ResourceBrush::Set),
+                );
+            for option in options {
+                ui.selectable_value(brush, option, name(&option));
+            }
+        });
+}
+
+pub fn handle_editor_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut ev_editor_command: EventWriter<EditorCommand>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if (keys.just_pressed(KeyCode::KeyZ) && shift) || keys.just_pressed(KeyCode::KeyY) {
+        ev_editor_command.send(EditorCommand::Redo);
+    } else if keys.just_pressed(KeyCode::KeyZ) {
+        ev_editor_command.send(EditorCommand::Undo);
+    } else if keys.just_pressed(KeyCode::KeyS) {
+        ev_editor_command.send(EditorCommand::SaveMap);
+    }
+}
+
+pub fn paint_tiles(
+    mut contexts: EguiContexts,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    cursor: HexCursor,
+    brush: Res<Brush>,
+    mut tiles: Query<&mut Tile>,
+    mut history: ResMut<EditHistory>,
+) {
+    if mouse_button_input.just_released(MouseButton::Left) {
+        history.finish_stroke();
+    }
+
+    if !mouse_button_input.pressed(MouseButton::Left) {
+        return;
+    }
+
+    let ctx = contexts.ctx_mut();
+    if ctx.wants_pointer_input() || ctx.is_pointer_over_area() {
+        return;
+    }
+
+    let Some(center) = cursor.hex() else {
+        return;
+    };
+    let grid = &cursor.grid;
+
+    for hex in center.spiral_range(0..=brush.radius) {
+        let Some(hex) = grid.shape.normalize(hex) else {
+            continue;
+        };
+        let Some(mut tile) = grid.get(hex).and_then(|entity| tiles.get_mut(entity).ok()) else {
+            continue;
+        };
+
+        let painted = brush.paint(&tile);
+        if painted == *tile {
+            continue;
+        }
+
+        history.record(TileEdit {
+            hex,
+            before: tile.clone(),
+            after: painted.clone(),
+        });
+        *tile = painted;
+    }
+}
+
+pub fn handle_editor_commands(
+    mut ev_editor_command: EventReader<EditorCommand>,
+    grid: Res<HexGrid>,
+    settings: Res<MapSettings>,
+    mut tiles: Query<&mut Tile>,
+    mut history: ResMut<EditHistory>,
+) {
+    for command in ev_editor_command.read() {
+        let changes = match command {
+            EditorCommand::Undo => history.undo(),
+            EditorCommand::Redo => history.redo(),
+            EditorCommand::SaveMap => {
+                history.finish_stroke();
+                let map = MapFile::new(grid.shape, &collect_tiles(&grid, &tiles.to_readonly()));
+                match map.write(&settings.export_path) {
+                    Ok(()) => info!("Saved map to {}", settings.export_path.display()),
+                    Err(error) => error!(
+                        "Saving map to {} failed: {error}",
+                        settings.export_path.display()
+                    ),
+                }
+                continue;
+            }
+        };
+
+        for (hex, changed) in changes {
+            if let Some(mut tile) = grid.get(hex).and_then(|entity| tiles.get_mut(entity).ok()) {
+                *tile = changed;
+            }
+        }
+    }
+}
+
+/// Recolors tiles whose biome was painted over.
+pub fn update_tile_materials(
+    mut tiles: Query<(Ref<Tile>, &mut Handle<StandardMaterial>), Changed<Tile>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut biome_materials: Local<HashMap<Biome, Handle<StandardMaterial>>>,
+) {
+    for (tile, mut material) in tiles.iter_mut() {
+        if tile.is_added() {
+            continue;
+        }
+
+        *material = biome_materials
+            .entry(tile.biome)
+            .or_insert_with(|| materials.add(get_color_from_biome(&tile.biome)))
+            .clone();
+    }
+}
@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+
+/// Covers the map while the device is passed to the next player.
+#[derive(Component)]
+pub struct PassDeviceScreen;
+
+#[derive(Component)]
+pub struct PassDeviceButton;
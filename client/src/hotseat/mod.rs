@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+use resources::HotseatViews;
+use systems::{begin_hotseat_turn, finish_pass_device};
+
+use crate::{core_gameplay::states::AppState, network::resources::ServerConnection};
+
+pub mod components;
+pub mod resources;
+mod systems;
+pub mod utils;
+
+/// Local games shared on one device. Between turns a screen hides the map
+/// until the next player takes over, and each player gets their own camera.
+pub struct HotseatPlugin;
+
+impl Plugin for HotseatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HotseatViews>().add_systems(
+            Update,
+            (begin_hotseat_turn, finish_pass_device)
+                .chain()
+                .run_if(in_state(AppState::InGame))
+                .run_if(not(resource_exists::<ServerConnection>)),
+        );
+    }
+}
@@ -0,0 +1,10 @@
+use bevy::{prelude::*, utils::HashMap};
+use common::protocol::PlayerId;
+
+#[derive(Debug, Default, Resource)]
+pub struct HotseatViews {
+    /// Where each player left the camera.
+    pub cameras: HashMap<PlayerId, Vec3>,
+    /// The player currently looking at the map.
+    pub current: Option<PlayerId>,
+}
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+
+use crate::{
+    camera::components::GameCamera,
+    core_gameplay::{components::Owner, resources::Players, states::GameplayState},
+    player::components::Hero,
+};
+
+use super::{
+    components::{PassDeviceButton, PassDeviceScreen},
+    resources::HotseatViews,
+    utils::PassDevice,
+};
+
+/// Heroes, apart from the camera that moves to them.
+type HeroesOnly = (With<Hero>, Without<GameCamera>);
+
+/// Hides the previous player's view when the turn passes to someone else.
+pub fn begin_hotseat_turn(
+    state: Res<State<GameplayState>>,
+    players: Res<Players>,
+    mut views: ResMut<HotseatViews>,
+    cameras: Query<&Transform, With<GameCamera>>,
+    mut pass_device: PassDevice,
+) {
+    if !state.is_changed() {
+        return;
+    }
+    let Some(player) = state.get().player() else {
+        return;
+    };
+    if views.current == Some(player) {
+        return;
+    }
+
+    if let (Some(previous), Ok(camera)) = (views.current, cameras.get_single()) {
+        views.cameras.insert(previous, camera.translation);
+    }
+    views.current = Some(player);
+
+    // a single human has nobody to hide the map from
+    let next = (players.humans() > 1).then(|| {
+        players
+            .get(player)
+            .map_or("", |player| player.name.as_str())
+    });
+    pass_device.hand_over(next);
+}
+
+/// Shows the map to the player who took over the device, where they left it.
+pub fn finish_pass_device(
+    mut commands: Commands,
+    interactions: Query<&Interaction, (Changed<Interaction>, With<PassDeviceButton>)>,
+    screens: Query<Entity, With<PassDeviceScreen>>,
+    views: Res<HotseatViews>,
+    heroes: Query<(&Owner, &Transform), HeroesOnly>,
+    mut cameras: Query<&mut Transform, With<GameCamera>>,
+) {
+    if !interactions
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+
+    screens.iter().for_each(|screen| {
+        commands.entity(screen).despawn_recursive();
+    });
+
+    let Some(player) = views.current else {
+        return;
+    };
+    // the first time round the camera starts on the player's hero
+    let Some(position) = views.cameras.get(&player).copied().or_else(|| {
+        heroes
+            .iter()
+            .find(|(owner, _)| owner.0 == player)
+            .map(|(_, transform)| transform.translation)
+    }) else {
+        return;
+    };
+
+    for mut transform in cameras.iter_mut() {
+        transform.translation.x = position.x;
+        transform.translation.z = position.z;
+        if views.cameras.contains_key(&player) {
+            transform.translation.y = position.y;
+        }
+    }
+}
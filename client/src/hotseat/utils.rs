@@ -0,0 +1,91 @@
+use bevy::{ecs::system::SystemParam, prelude::*, ui::FocusPolicy};
+
+use crate::player::{components::SelectedHero, events::HeroDeselectEvent};
+
+use super::components::{PassDeviceButton, PassDeviceScreen};
+
+/// What changes hands with the device between two players.
+#[derive(SystemParam)]
+pub struct PassDevice<'w, 's> {
+    commands: Commands<'w, 's>,
+    selected: Query<'w, 's, Entity, With<SelectedHero>>,
+    screens: Query<'w, 's, Entity, With<PassDeviceScreen>>,
+    ev_hero_deselect: EventWriter<'w, HeroDeselectEvent>,
+}
+
+impl PassDevice<'_, '_> {
+    /// Drops the previous player's selection and, given the next player's
+    /// name, covers the map until they continue.
+    pub fn hand_over(&mut self, next: Option<&str>) {
+        self.ev_hero_deselect.send_batch(
+            self.selected
+                .iter()
+                .map(|hero| HeroDeselectEvent { hero, button: None }),
+        );
+
+        self.screens.iter().for_each(|screen| {
+            self.commands.entity(screen).despawn_recursive();
+        });
+        if let Some(name) = next {
+            spawn_pass_device_screen(&mut self.commands, name);
+        }
+    }
+}
+
+fn spawn_pass_device_screen(commands: &mut Commands, name: &str) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                background_color: Color::BLACK.into(),
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            PassDeviceScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                format!("{name}, it's your turn"),
+                TextStyle {
+                    font_size: 40.0,
+                    ..default()
+                },
+            ));
+            parent.spawn(TextBundle::from_section(
+                "Pass the device, then continue when nobody else is looking.",
+                TextStyle::default(),
+            ));
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(150.0),
+                            height: Val::Px(50.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    PassDeviceButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Continue",
+                        TextStyle {
+                            color: Color::BLACK,
+                            ..default()
+                        },
+                    ));
+                });
+        });
+}
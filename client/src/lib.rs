@@ -0,0 +1,13 @@
+pub mod actions;
+pub mod camera;
+pub mod core_gameplay;
+pub mod debug_gui;
+pub mod editor;
+pub mod hotseat;
+pub mod lobby;
+pub mod map;
+pub mod network;
+pub mod player;
+pub mod replay;
+pub mod save;
+pub mod simultaneous;
@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+use common::setup::GameSetup;
+
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub enum LobbyCommand {
+    ChangeSetup(GameSetup),
+    SetReady(bool),
+    /// Starts a local game, online games start when everyone is ready.
+    Start,
+}
@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+use bevy_egui::EguiPlugin;
+use events::LobbyCommand;
+use resources::Lobby;
+use systems::{draw_lobby_panel, handle_lobby_commands};
+
+use crate::{core_gameplay::states::AppState, network::resources::ServerConnection};
+
+pub mod events;
+pub mod resources;
+mod systems;
+
+/// Sets up the game before it starts. Played locally the game starts right
+/// away, online it starts once every human player is ready on the server.
+pub struct LobbyPlugin;
+
+impl Plugin for LobbyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Lobby>()
+            .add_event::<LobbyCommand>()
+            .add_systems(
+                Update,
+                // the network plugin sends the commands to the server instead
+                handle_lobby_commands
+                    .run_if(in_state(AppState::Lobby))
+                    .run_if(not(resource_exists::<ServerConnection>)),
+            );
+
+        // without a window, as in the tests, the game is set up by commands
+        if app.is_plugin_added::<WindowPlugin>() {
+            if !app.is_plugin_added::<EguiPlugin>() {
+                app.add_plugins(EguiPlugin);
+            }
+            app.add_systems(
+                Update,
+                draw_lobby_panel
+                    .before(handle_lobby_commands)
+                    .run_if(in_state(AppState::Lobby)),
+            );
+        }
+    }
+}
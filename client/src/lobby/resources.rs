@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+use common::{protocol::Seat, setup::GameSetup};
+
+#[derive(Debug, Default, Resource)]
+pub struct Lobby {
+    pub setup: GameSetup,
+    /// Who sits where, sent by the server. Empty when playing locally.
+    pub seats: Vec<Seat>,
+}
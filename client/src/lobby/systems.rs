@@ -0,0 +1,179 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use common::{
+    map::generation::MapType,
+    protocol::HOST,
+    setup::{GameSetup, MapSize, MaxTurns, SlotKind, TurnMode, MAX_PLAYERS, MIN_PLAYERS},
+};
+
+use crate::{
+    core_gameplay::{
+        resources::{Players, TurnManager},
+        states::{AppState, GameplayState},
+    },
+    map::resources::MapSettings,
+    network::resources::{LocalPlayer, ServerConnection},
+    replay::events::StartReplay,
+};
+
+use super::{events::LobbyCommand, resources::Lobby};
+
+pub fn draw_lobby_panel(
+    mut contexts: EguiContexts,
+    lobby: Res<Lobby>,
+    connection: Option<Res<ServerConnection>>,
+    local_player: Option<Res<LocalPlayer>>,
+    mut ev_lobby_command: EventWriter<LobbyCommand>,
+    mut ev_start_replay: EventWriter<StartReplay>,
+) {
+    let online = connection.is_some();
+    let local_player = local_player.map(|local| local.0);
+    // online only the host changes the setup
+    let can_edit = !online || local_player == Some(HOST);
+
+    egui::Window::new("New Game").show(contexts.ctx_mut(), |ui| {
+        let mut setup = lobby.setup.clone();
+        ui.add_enabled_ui(can_edit, |ui| setup_editor(ui, &mut setup, online));
+        if setup != lobby.setup {
+            ev_lobby_command.send(LobbyCommand::ChangeSetup(setup));
+        }
+        ui.separator();
+
+        if !online {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(lobby.setup.is_valid(), egui::Button::new("Start"))
+                    .clicked()
+                {
+                    ev_lobby_command.send(LobbyCommand::Start);
+                }
+                if ui.button("Replay").clicked() {
+                    ev_start_replay.send(StartReplay);
+                }
+            });
+            return;
+        }
+
+        for seat in lobby.seats.iter() {
+            let status = match (seat.kind, seat.taken, seat.ready) {
+                (SlotKind::Ai, _, _) => "AI",
+                (SlotKind::Human, false, _) => "Waiting for a player",
+                (SlotKind::Human, true, false) => "Not ready",
+                (SlotKind::Human, true, true) => "Ready",
+            };
+            let you = if Some(seat.player) == local_player {
+                " (you)"
+            } else {
+                ""
+            };
+            ui.label(format!("Player {}{you}: {status}", seat.player.0));
+        }
+
+        let seat = lobby
+            .seats
+            .iter()
+            .find(|seat| Some(seat.player) == local_player);
+        if let Some(seat) = seat {
+            let mut ready = seat.ready;
+            if ui.checkbox(&mut ready, "Ready").changed() {
+                ev_lobby_command.send(LobbyCommand::SetReady(ready));
+            }
+        }
+    });
+}
+
+fn setup_editor(ui: &mut egui::Ui, setup: &mut GameSetup, online: bool) {
+    egui::ComboBox::from_label("Map size")
+        .selected_text(setup.map_size.name())
+        .show_ui(ui, |ui| {
+            for option in MapSize::ALL {
+                ui.selectable_value(&mut setup.map_size, option, option.name());
+            }
+        });
+    egui::ComboBox::from_label("Map type")
+        .selected_text(setup.map_type.name())
+        .show_ui(ui, |ui| {
+            for option in MapType::ALL {
+                ui.selectable_value(&mut setup.map_type, option, option.name());
+            }
+        });
+    ui.horizontal(|ui| {
+        ui.label("Seed");
+        ui.add(egui::DragValue::new(&mut setup.seed));
+        if ui.button("Random").clicked() {
+            setup.seed = rand::random();
+        }
+    });
+    egui::ComboBox::from_label("Turn limit")
+        .selected_text(setup.max_turns.name())
+        .show_ui(ui, |ui| {
+            for option in MaxTurns::ALL {
+                ui.selectable_value(&mut setup.max_turns, option, option.name());
+            }
+        });
+    // the server only plays turns one after another
+    ui.add_enabled_ui(!online, |ui| {
+        egui::ComboBox::from_label("Turns")
+            .selected_text(setup.turn_mode.name())
+            .show_ui(ui, |ui| {
+                for option in TurnMode::ALL {
+                    ui.selectable_value(&mut setup.turn_mode, option, option.name());
+                }
+            });
+    });
+    ui.separator();
+
+    let mut players = setup.slots.len();
+    ui.add(egui::Slider::new(&mut players, MIN_PLAYERS..=MAX_PLAYERS).text("Players"));
+    setup.set_player_count(players);
+
+    for (index, slot) in setup.slots.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(format!("Player {}", index + 1));
+            // the first slot belongs to the host
+            ui.add_enabled_ui(index > 0, |ui| {
+                for option in SlotKind::ALL {
+                    ui.selectable_value(slot, option, option.name());
+                }
+            });
+        });
+    }
+}
+
+/// Applies the lobby commands of a local game.
+pub fn handle_lobby_commands(
+    mut ev_lobby_command: EventReader<LobbyCommand>,
+    mut lobby: ResMut<Lobby>,
+    mut map_settings: ResMut<MapSettings>,
+    mut turn_manager: ResMut<TurnManager>,
+    mut players: ResMut<Players>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut next_gameplay_state: ResMut<NextState<GameplayState>>,
+) {
+    for command in ev_lobby_command.read() {
+        match command {
+            LobbyCommand::ChangeSetup(setup) => lobby.setup = setup.clone(),
+            LobbyCommand::SetReady(_) => (),
+            LobbyCommand::Start => {
+                if !lobby.setup.is_valid() {
+                    continue;
+                }
+
+                map_settings.generator = lobby.setup.generator();
+                turn_manager.max_turns = lobby.setup.max_turns.get_max_turns();
+                turn_manager.mode = lobby.setup.turn_mode;
+                *players = Players::from_slots(&lobby.setup.slots);
+                turn_manager.turn_order = players.ids();
+                turn_manager.current_turn = 1;
+                turn_manager.current_state = GameplayState::PlayerTurn(turn_manager.turn_order[0]);
+                next_gameplay_state.set(turn_manager.current_state.clone());
+                next_state.set(AppState::InGame);
+                info!(
+                    "Starting a game with {} players, map seed {}",
+                    lobby.setup.slots.len(),
+                    lobby.setup.seed
+                );
+            }
+        }
+    }
+}
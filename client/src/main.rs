@@ -1,18 +1,22 @@
 use bevy::prelude::*;
 use bevy_inspector_egui::quick::{FilterQueryInspectorPlugin, WorldInspectorPlugin};
 use bevy_mod_picking::prelude::*;
-use camera::CameraPlugin;
-use core_gameplay::CoreGameplayPlugin;
-use debug_gui::DebugGuiPlugin;
-use map::{components::Tile, resources::SelectedTile, MapPlugin};
-use player::PlayerPlugin;
-
-pub mod actions;
-pub mod camera;
-pub mod core_gameplay;
-pub mod debug_gui;
-pub mod map;
-pub mod player;
+use client::{
+    actions::ActionsPlugin,
+    camera::CameraPlugin,
+    core_gameplay::CoreGameplayPlugin,
+    debug_gui::DebugGuiPlugin,
+    editor::EditorPlugin,
+    hotseat::HotseatPlugin,
+    lobby::LobbyPlugin,
+    map::{resources::SelectedTile, MapPlugin},
+    network::NetworkPlugin,
+    player::PlayerPlugin,
+    replay::ReplayPlugin,
+    save::SavePlugin,
+    simultaneous::SimultaneousPlugin,
+};
+use common::map::components::Tile;
 
 fn main() {
     App::new()
@@ -37,6 +41,14 @@ fn main() {
         // .add_plugins(DebugGuiPlugin)
         .add_plugins(PlayerPlugin)
         .add_plugins(CoreGameplayPlugin)
+        .add_plugins(LobbyPlugin)
+        .add_plugins(HotseatPlugin)
+        .add_plugins(ActionsPlugin)
+        .add_plugins(SimultaneousPlugin)
+        .add_plugins(ReplayPlugin)
+        .add_plugins(SavePlugin)
+        .add_plugins(EditorPlugin)
+        .add_plugins(NetworkPlugin)
         // .insert_resource(DebugPickingMode::Normal)
         .run();
 }
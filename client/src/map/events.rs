@@ -1,8 +1,7 @@
 use bevy::prelude::*;
+use common::map::components::Tile;
 use hexx::Hex;
 
-use super::components::Tile;
-
 #[derive(Event)]
 pub struct TileSelectEvent {
     pub entity: Entity,
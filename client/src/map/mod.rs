@@ -1,14 +1,22 @@
 use bevy::prelude::*;
-use components::Tile;
+use common::map::{components::Tile, rivers::Rivers};
 use events::{TileDeselectEvent, TileSelectEvent};
-use resources::{HexPreview, MapSettings};
-use systems::{handle_selected_tile_material, handle_tile_selection, regenerate_grid, setup_grid};
+use resources::{HexGrid, HexPreview, MapSettings};
+use systems::{
+    export_map, handle_selected_tile_material, handle_tile_selection, regenerate_grid, setup_grid,
+};
+
+use crate::{core_gameplay::states::AppState, network::resources::ServerConnection};
 
 pub mod components;
 pub mod events;
 pub mod resources;
 mod systems;
-mod utils;
+pub mod utils;
+
+/// Builds the map when the game starts. Systems that need the map come after.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MapSetupSet;
 
 pub struct MapPlugin;
 
@@ -16,17 +24,27 @@ impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<MapSettings>()
             .init_resource::<HexPreview>()
+            .init_resource::<HexGrid>()
+            .init_resource::<Rivers>()
             .register_type::<Tile>()
             .add_event::<TileSelectEvent>()
             .add_event::<TileDeselectEvent>()
-            .add_systems(Startup, setup_grid)
+            // when playing online the map comes from the server
+            .add_systems(
+                OnEnter(AppState::InGame),
+                setup_grid
+                    .in_set(MapSetupSet)
+                    .run_if(not(resource_exists::<ServerConnection>)),
+            )
             .add_systems(
                 Update,
                 (
                     regenerate_grid,
+                    export_map,
                     handle_tile_selection,
                     handle_selected_tile_material,
-                ),
+                )
+                    .run_if(in_state(AppState::InGame)),
             );
     }
 }
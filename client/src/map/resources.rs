@@ -1,17 +1,19 @@
+use std::path::PathBuf;
+
 use bevy::{
     prelude::*,
     utils::{HashMap, HashSet},
 };
 use bevy_inspector_egui::prelude::*;
+use common::map::{components::Tile, generation::GeneratorSettings, shape::MapShape};
 use hexx::*;
 
-use super::components::Tile;
-
 #[derive(Debug, Resource)]
 pub struct HexGrid {
     pub entities: HashMap<Hex, Entity>,
     pub reachable_entities: HashSet<Entity>,
     pub layout: HexLayout,
+    pub shape: MapShape,
 }
 
 impl Default for HexGrid {
@@ -20,10 +22,32 @@ impl Default for HexGrid {
             entities: HashMap::default(),
             reachable_entities: HashSet::default(),
             layout: HexLayout::default(),
+            shape: MapShape::default(),
         }
     }
 }
 
+impl HexGrid {
+    /// Tile entity at `hex`, wrapping across the seam of wrapping maps.
+    pub fn get(&self, hex: Hex) -> Option<Entity> {
+        self.shape
+            .normalize(hex)
+            .and_then(|hex| self.entities.get(&hex).copied())
+    }
+
+    pub fn neighbors(&self, hex: Hex) -> impl Iterator<Item = (Hex, Entity)> + '_ {
+        hex.all_neighbors().into_iter().filter_map(|neighbor| {
+            let neighbor = self.shape.normalize(neighbor)?;
+            Some((neighbor, *self.entities.get(&neighbor)?))
+        })
+    }
+
+    /// Hex under a world position, wrapped back onto the map.
+    pub fn world_pos_to_hex(&self, pos: Vec2) -> Hex {
+        self.shape.wrap(self.layout.world_pos_to_hex(pos))
+    }
+}
+
 #[derive(Debug, Resource)]
 pub struct HexPreview {
     pub entity: Entity,
@@ -42,18 +66,23 @@ impl Default for HexPreview {
 #[derive(Debug, Resource, Reflect, InspectorOptions)]
 pub struct MapSettings {
     pub hex_size: Vec2,
-    #[inspector(min = 1, max = 100)]
-    pub map_radius: u32,
     #[inspector(min = 1, max = 10)]
     pub budget: u32,
+    pub generator: GeneratorSettings,
+    /// Map file to load instead of generating a map, see `common::map::map_file`.
+    pub map_file: Option<PathBuf>,
+    /// Where the current map is exported to.
+    pub export_path: PathBuf,
 }
 
 impl Default for MapSettings {
     fn default() -> Self {
         Self {
             hex_size: Vec2::splat(16.0),
-            map_radius: 80,
             budget: 7,
+            generator: GeneratorSettings::default(),
+            map_file: None,
+            export_path: PathBuf::from("maps/export.ron"),
         }
     }
 }
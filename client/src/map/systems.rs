@@ -1,113 +1,170 @@
-use bevy::window::PrimaryWindow;
 use bevy::{prelude::*, utils::HashSet};
+use common::map::components::Tile;
+use common::map::generation::generate_map;
+use common::map::map_file::MapFile;
+use common::map::start_positions::StartPositions;
 use hexx::*;
 
-use crate::camera::components::GameCamera;
-use crate::map::components::Cross;
+use crate::map::components::{Cross, RiverSegment};
 use crate::player::components::{MoveTarget, SelectedHero};
 use crate::player::events::HeroDeselectEvent;
 
-use super::components::Tile;
 use super::events::{TileDeselectEvent, TileSelectEvent};
 use super::resources::HexGrid;
 use super::resources::MapSettings;
-use super::utils::generate_terrain_hex_grid;
+use super::utils::{
+    build_map, collect_tiles, spawn_rivers, spawn_tiles, HexCursor, TileSelectionEvents,
+};
 
 pub fn setup_grid(
     mut commands: Commands,
     settings: Res<MapSettings>,
-    meshes: ResMut<Assets<Mesh>>,
-    materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    if !settings.is_changed() {
-        return;
-    }
-
     let layout = HexLayout {
         hex_size: settings.hex_size,
         ..default()
     };
 
-    let entities = generate_terrain_hex_grid(
-        settings.map_radius,
+    let (map, shape) = build_map(&settings);
+    let entities = spawn_tiles(
+        &map.tiles,
         settings.hex_size,
         &mut commands,
-        meshes,
-        materials,
+        &mut meshes,
+        &mut materials,
     );
-    commands.insert_resource(HexGrid {
+    let grid = HexGrid {
         entities,
         reachable_entities: HashSet::default(),
         layout,
-    });
+        shape,
+    };
+
+    spawn_rivers(
+        &map.rivers,
+        &grid,
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+    );
+    log_start_positions(&map.start_positions);
+
+    commands.insert_resource(grid);
+    commands.insert_resource(map.rivers);
+    commands.insert_resource(map.start_positions);
 }
 
 pub fn regenerate_grid(
     mut commands: Commands,
-    meshes: ResMut<Assets<Mesh>>,
-    materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
     mut grid: ResMut<HexGrid>,
     keys: Res<ButtonInput<KeyCode>>,
-    settings: Res<MapSettings>,
+    mut settings: ResMut<MapSettings>,
+    river_segments: Query<Entity, With<RiverSegment>>,
 ) {
     if keys.just_pressed(KeyCode::KeyR) {
+        settings.generator.seed = rand::random();
+        info!("Regenerating map with seed {}", settings.generator.seed);
+
         grid.entities.iter_mut().for_each(|(_, entity)| {
             commands.entity(*entity).despawn_recursive();
         });
+        river_segments.iter().for_each(|entity| {
+            commands.entity(entity).despawn_recursive();
+        });
 
-        grid.entities = generate_terrain_hex_grid(
-            settings.map_radius,
+        let map = generate_map(&settings.generator);
+        grid.entities = spawn_tiles(
+            &map.tiles,
             settings.hex_size,
             &mut commands,
-            meshes,
-            materials,
+            &mut meshes,
+            &mut materials,
         );
+        grid.shape = settings.generator.shape;
         grid.reachable_entities.clear();
+
+        spawn_rivers(
+            &map.rivers,
+            &grid,
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+        );
+        log_start_positions(&map.start_positions);
+
+        commands.insert_resource(map.rivers);
+        commands.insert_resource(map.start_positions);
+    }
+}
+
+fn log_start_positions(starts: &StartPositions) {
+    for (player, start) in starts.positions.iter().enumerate() {
+        info!(
+            "Player {} starts at {:?} with score {:.0}",
+            player + 1,
+            start.hex,
+            start.score
+        );
+    }
+    info!("Start position fairness {:.2}", starts.fairness);
+}
+
+pub fn export_map(
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<MapSettings>,
+    grid: Res<HexGrid>,
+    tiles: Query<&Tile>,
+) {
+    if !keys.just_pressed(KeyCode::F6) {
+        return;
+    }
+
+    let tiles = collect_tiles(&grid, &tiles);
+
+    match MapFile::new(grid.shape, &tiles).write(&settings.export_path) {
+        Ok(()) => info!("Exported map to {}", settings.export_path.display()),
+        Err(error) => error!(
+            "Exporting map to {} failed: {error}",
+            settings.export_path.display()
+        ),
     }
 }
 
 pub fn handle_tile_selection(
     mut commands: Commands,
-    mut ev_tile_select: EventWriter<TileSelectEvent>,
-    mut ev_tile_desel: EventWriter<TileDeselectEvent>,
-    mut ev_hero_deselect: EventWriter<HeroDeselectEvent>,
+    mut events: TileSelectionEvents,
     tiles: Query<(Entity, &Tile)>,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    cameras: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
-    grid: ResMut<HexGrid>,
+    cursor: HexCursor,
     mut current: Local<Hex>,
-    selected_hero_query: Query<Entity, With<SelectedHero>>,
-    move_target_query: Query<Option<&MoveTarget>, With<SelectedHero>>,
+    selected_hero_query: Query<(Entity, Has<MoveTarget>), With<SelectedHero>>,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
 ) {
-    if selected_hero_query.iter().next().is_none() {
+    let Some((selected_hero_entity, has_move_target)) = selected_hero_query.iter().next() else {
         return;
-    }
-
-    let selected_hero_entity = selected_hero_query.iter().next().unwrap();
-
-    let window = windows.iter().next().unwrap();
-    let (camera, cam_transform) = cameras.iter().next().unwrap();
+    };
+    let grid = &cursor.grid;
 
     let local_hex = *current;
 
-    if mouse_button_input.just_pressed(MouseButton::Left) {
-        if move_target_query.iter().next().is_some() {
-            commands.entity(selected_hero_entity).remove::<MoveTarget>();
+    if mouse_button_input.just_pressed(MouseButton::Left) && has_move_target {
+        commands.entity(selected_hero_entity).remove::<MoveTarget>();
 
-            ev_hero_deselect.send(HeroDeselectEvent {
-                hero: selected_hero_entity,
-                button: None,
-            });
+        events.hero_deselect.send(HeroDeselectEvent {
+            hero: selected_hero_entity,
+            button: None,
+        });
 
-            for (ent, tile) in tiles.iter() {
-                if grid.entities.get(&local_hex).map(|&e| e) == Some(ent) {
-                    ev_tile_desel.send(TileDeselectEvent {
-                        tile: tile.clone(),
-                        entity: ent,
-                        hex: *current,
-                    });
-                }
+        for (ent, tile) in tiles.iter() {
+            if grid.entities.get(&local_hex).copied() == Some(ent) {
+                events.tile_deselect.send(TileDeselectEvent {
+                    tile: tile.clone(),
+                    entity: ent,
+                    hex: *current,
+                });
             }
         }
     }
@@ -116,32 +173,17 @@ pub fn handle_tile_selection(
         return;
     }
 
-    let cursor_position = match window.cursor_position() {
-        Some(pos) => pos,
-        None => return,
-    };
-
-    if let Some(ray) = camera.viewport_to_world(cam_transform, cursor_position) {
-        let Some(distance) = ray.intersect_plane(
-            Vec3::new(ray.origin.x, 0.5, ray.origin.z),
-            Plane3d::new(Vec3::Y),
-        ) else {
-            return;
-        };
-
-        let point = ray.get_point(distance);
-        let hex_pos = grid.layout.world_pos_to_hex(Vec2::new(point.x, point.z));
-
+    if let Some(hex_pos) = cursor.hex() {
         *current = hex_pos;
 
         if let Some(tile_entity) = grid.entities.get(&hex_pos) {
             for (ent, tile) in tiles.iter() {
-                if tile.cost() == None {
+                if tile.cost().is_none() {
                     continue;
                 }
 
-                if grid.entities.get(&hex_pos).map(|&e| e) == Some(ent) {
-                    ev_tile_desel.send(TileDeselectEvent {
+                if grid.entities.get(&hex_pos).copied() == Some(ent) {
+                    events.tile_deselect.send(TileDeselectEvent {
                         tile: tile.clone(),
                         entity: ent,
                         hex: *current,
@@ -153,7 +195,7 @@ pub fn handle_tile_selection(
                     .insert(MoveTarget(*current));
 
                 if commands.entity(*tile_entity).id() == commands.entity(ent).id() {
-                    ev_tile_select.send(TileSelectEvent {
+                    events.tile_select.send(TileSelectEvent {
                         tile: tile.clone(),
                         entity: ent,
                         hex: *current,
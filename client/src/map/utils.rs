@@ -1,23 +1,110 @@
-use super::components::{Biome, Tile, TileResource};
 use bevy::{
+    ecs::system::SystemParam,
     prelude::*,
     render::{
         mesh::{Indices, PrimitiveTopology},
         render_asset::RenderAssetUsages,
     },
-    utils::hashbrown::HashMap,
+    utils::HashMap,
+    window::PrimaryWindow,
+};
+use common::map::{
+    components::{Biome, Tile},
+    generation::{generate_map, GeneratedMap},
+    map_file::MapFile,
+    rivers::Rivers,
+    shape::MapShape,
 };
 use hexx::*;
-use noise::{NoiseFn, Simplex};
 
-use rand::prelude::*;
+use crate::{camera::components::GameCamera, player::events::HeroDeselectEvent};
+
+use super::{
+    components::RiverSegment,
+    events::{TileDeselectEvent, TileSelectEvent},
+    resources::{HexGrid, MapSettings},
+};
+
+/// Loads the map file `settings` points at, or generates a map if there is
+/// none or it can't be loaded.
+pub fn build_map(settings: &MapSettings) -> (GeneratedMap, MapShape) {
+    if let Some(path) = &settings.map_file {
+        let generator = &settings.generator;
+        let loaded = MapFile::read(path).and_then(|file| {
+            let map = file.to_map(generator.player_count, generator.resources.start_radius)?;
+            Ok((map, file.shape))
+        });
+
+        match loaded {
+            Ok(loaded) => {
+                info!("Loaded map from {}", path.display());
+                return loaded;
+            }
+            Err(error) => error!("Could not load map {}: {error}", path.display()),
+        }
+    }
+
+    info!("Generating map with seed {}", settings.generator.seed);
+    (generate_map(&settings.generator), settings.generator.shape)
+}
+
+/// Current tiles of the grid keyed by hex, e.g. for saving or exporting.
+pub fn collect_tiles(grid: &HexGrid, tiles: &Query<&Tile>) -> HashMap<Hex, Tile> {
+    grid.entities
+        .iter()
+        .filter_map(|(&hex, &entity)| Some((hex, tiles.get(entity).ok()?.clone())))
+        .collect()
+}
+
+/// Hex under the cursor, found by casting a ray from the camera onto the tiles.
+pub fn cursor_to_hex(
+    window: &Window,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    grid: &HexGrid,
+) -> Option<Hex> {
+    let cursor_position = window.cursor_position()?;
+    let ray = camera.viewport_to_world(camera_transform, cursor_position)?;
+    let distance = ray.intersect_plane(
+        Vec3::new(ray.origin.x, 0.5, ray.origin.z),
+        Plane3d::new(Vec3::Y),
+    )?;
 
-pub fn generate_terrain_hex_grid(
-    map_radius: u32,
+    let point = ray.get_point(distance);
+    Some(grid.world_pos_to_hex(Vec2::new(point.x, point.z)))
+}
+
+/// The window, camera and grid needed to find the hex under the cursor.
+#[derive(SystemParam)]
+pub struct HexCursor<'w, 's> {
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<GameCamera>>,
+    pub grid: Res<'w, HexGrid>,
+}
+
+impl HexCursor<'_, '_> {
+    pub fn hex(&self) -> Option<Hex> {
+        let window = self.windows.get_single().ok()?;
+        let (camera, camera_transform) = self.cameras.get_single().ok()?;
+        cursor_to_hex(window, camera, camera_transform, &self.grid)
+    }
+}
+
+/// What choosing where the selected hero goes tells the rest of the game.
+#[derive(SystemParam)]
+pub struct TileSelectionEvents<'w> {
+    pub tile_select: EventWriter<'w, TileSelectEvent>,
+    pub tile_deselect: EventWriter<'w, TileDeselectEvent>,
+    pub hero_deselect: EventWriter<'w, HeroDeselectEvent>,
+}
+
+/// Spawns an entity for every generated tile and returns them keyed by hex.
+pub fn spawn_tiles(
+    tiles: &HashMap<Hex, Tile>,
     hex_size: Vec2,
     commands: &mut Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
 ) -> HashMap<Hex, Entity> {
     let layout = HexLayout {
         hex_size,
@@ -34,19 +121,11 @@ pub fn generate_terrain_hex_grid(
     let desert_mat = materials.add(Color::ORANGE);
     let snow_mat = materials.add(Color::BEIGE);
 
-    let mut rng = rand::thread_rng();
-    let seed = rng.gen();
-    let simplex = Simplex::new(seed);
-
-    Hex::ZERO
-        .spiral_range(0..=map_radius)
-        .map(|coord| {
-            let elevation = simplex.get([coord.x as f64, coord.y as f64]);
-            // let moisture = simplex.get([coord.x as f64 + 100.0, coord.y as f64 + 100.0]);
+    tiles
+        .iter()
+        .map(|(&coord, tile)| {
             let pos = layout.hex_to_world_pos(coord);
-            // let biome = Biome::from_elevation_and_moisture(elevation, moisture);
-            let biome = Biome::simple_biome(elevation);
-            let material = match biome {
+            let material = match tile.biome {
                 Biome::Plains => plains_mat.clone(),
                 Biome::Forest => forest_mat.clone(),
                 Biome::Mountain => mountain_mat.clone(),
@@ -56,15 +135,6 @@ pub fn generate_terrain_hex_grid(
                 Biome::Snow => snow_mat.clone(),
             };
 
-            let tile = Tile::new(
-                biome,
-                rng.gen_range(0..100),
-                rng.gen_range(0..100),
-                rng.gen_range(0..100),
-                TileResource::get_from_number(rng.gen_range(0..=25)),
-                TileResource::get_from_number(rng.gen_range(0..=25)),
-            );
-
             let entity = commands
                 .spawn((
                     Name::new("HexTile".to_string()),
@@ -83,6 +153,42 @@ pub fn generate_terrain_hex_grid(
         .collect()
 }
 
+/// Spawns a strip along the border of every river edge, just above the tiles.
+pub fn spawn_rivers(
+    rivers: &Rivers,
+    grid: &HexGrid,
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    // the side of a regular hexagon is as long as its radius
+    let mesh = meshes.add(Cuboid::new(3.0, 0.2, grid.layout.hex_size.x));
+    let material = materials.add(Color::rgb(0.2, 0.5, 0.9));
+
+    for edge in rivers.edges.iter() {
+        let [a, b] = edge.hexes();
+        // draw edges crossing the seam of a wrapping map next to `a`
+        let b = grid.shape.nearest(a, b);
+
+        let a = grid.layout.hex_to_world_pos(a);
+        let b = grid.layout.hex_to_world_pos(b);
+        let middle = (a + b) / 2.0;
+        let across = b - a;
+
+        commands.spawn((
+            Name::new("RiverSegment".to_string()),
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_xyz(middle.x, 1.1, middle.y)
+                    .looking_to(Vec3::new(-across.y, 0.0, across.x), Vec3::Y),
+                ..default()
+            },
+            RiverSegment,
+        ));
+    }
+}
+
 fn hexagonal_plane(hex_layout: &HexLayout) -> Mesh {
     let mesh_info = ColumnMeshBuilder::new(hex_layout, 1.0)
         .without_bottom_face()
@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+use common::protocol::HeroId;
+
+/// Links a hero to its counterpart on the server.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ServerHero {
+    pub id: HeroId,
+}
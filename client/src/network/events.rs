@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+use common::protocol::ServerMessage;
+
+#[derive(Event)]
+pub struct ServerMessageEvent(pub ServerMessage);
@@ -0,0 +1,50 @@
+use std::env;
+
+use bevy::prelude::*;
+use events::ServerMessageEvent;
+use resources::{NetworkSettings, ServerConnection};
+use systems::{
+    apply_game_over, apply_game_started, apply_hero_moved, apply_lobby_changes, apply_turn_started,
+    connect_to_server, log_rejections, receive_server_messages, send_end_turn, send_hero_selection,
+    send_lobby_commands,
+};
+use utils::connect_address;
+
+pub mod components;
+pub mod events;
+pub mod resources;
+mod systems;
+pub mod utils;
+
+/// Plays against a server when the client is started with
+/// `--connect host:port`. The game is set up in the server's lobby, then the
+/// player's actions are sent to the server and only its answers change heroes
+/// and turns. Without the flag nothing changes.
+pub struct NetworkPlugin;
+
+impl Plugin for NetworkPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(NetworkSettings {
+            server: connect_address(env::args()),
+        })
+        .add_event::<ServerMessageEvent>()
+        .add_systems(Startup, connect_to_server)
+        .add_systems(
+            Update,
+            (
+                receive_server_messages,
+                apply_lobby_changes,
+                apply_game_started,
+                apply_hero_moved,
+                apply_turn_started,
+                apply_game_over,
+                log_rejections,
+                send_lobby_commands,
+                send_hero_selection,
+                send_end_turn,
+            )
+                .chain()
+                .distributive_run_if(resource_exists::<ServerConnection>),
+        );
+    }
+}
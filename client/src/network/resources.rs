@@ -0,0 +1,76 @@
+use std::{
+    net::TcpStream,
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Mutex,
+    },
+    thread,
+};
+
+use bevy::prelude::*;
+use common::protocol::{
+    codec::{read_frame, write_frame, CodecError},
+    ClientMessage, Hello, PlayerId, ServerMessage,
+};
+
+#[derive(Debug, Default, Resource)]
+pub struct NetworkSettings {
+    /// Address of the server to play on, `None` to play locally.
+    pub server: Option<String>,
+}
+
+/// The player this client controls, known once the server welcomed us.
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct LocalPlayer(pub PlayerId);
+
+/// An open connection to the server. Messages are read on a background thread
+/// and picked up each frame.
+#[derive(Resource)]
+pub struct ServerConnection {
+    stream: TcpStream,
+    messages: Mutex<Receiver<ServerMessage>>,
+}
+
+impl ServerConnection {
+    /// Connects to `addr` and says hello.
+    pub fn connect(addr: &str) -> Result<Self, CodecError> {
+        let mut stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        write_frame(&mut stream, &Hello::default())?;
+
+        let mut reader = stream.try_clone()?;
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            while let Ok(message) = read_frame::<ServerMessage>(&mut reader) {
+                if sender.send(message).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Self {
+            stream,
+            messages: Mutex::new(receiver),
+        })
+    }
+
+    pub fn send(&mut self, message: &ClientMessage) -> Result<(), CodecError> {
+        write_frame(&mut self.stream, message)
+    }
+
+    /// Every message that arrived since the last call, or `None` once the
+    /// server is gone and nothing is left to read.
+    pub fn receive(&self) -> Option<Vec<ServerMessage>> {
+        let messages = self.messages.lock().ok()?;
+        let mut received = Vec::new();
+
+        loop {
+            match messages.try_recv() {
+                Ok(message) => received.push(message),
+                Err(TryRecvError::Empty) => return Some(received),
+                Err(TryRecvError::Disconnected) if received.is_empty() => return None,
+                Err(TryRecvError::Disconnected) => return Some(received),
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use bevy::prelude::*;
+use common::protocol::{ClientMessage, ServerMessage, PROTOCOL_VERSION};
+
+use crate::{
+    core_gameplay::{
+        components::Owner,
+        events::{TurnEndEvent, TurnStartEvent},
+        resources::TurnManager,
+        states::GameplayState,
+    },
+    lobby::{events::LobbyCommand, resources::Lobby},
+    map::resources::HexGrid,
+    player::components::{HasMoved, SelectedHero},
+};
+
+use super::{
+    components::ServerHero,
+    events::ServerMessageEvent,
+    resources::{LocalPlayer, NetworkSettings, ServerConnection},
+    utils::{send, GameStarter},
+};
+
+pub fn connect_to_server(mut commands: Commands, settings: Res<NetworkSettings>) {
+    let Some(addr) = settings.server.as_ref() else {
+        return;
+    };
+
+    match ServerConnection::connect(addr) {
+        Ok(connection) => {
+            info!("Connected to {addr}");
+            commands.insert_resource(connection);
+        }
+        Err(error) => error!("Could not connect to {addr}, playing locally: {error}"),
+    }
+}
+
+pub fn receive_server_messages(
+    mut commands: Commands,
+    connection: Res<ServerConnection>,
+    mut ev_server_message: EventWriter<ServerMessageEvent>,
+) {
+    match connection.receive() {
+        Some(messages) => {
+            ev_server_message.send_batch(messages.into_iter().map(ServerMessageEvent));
+        }
+        None => {
+            error!("Lost the connection to the server");
+            commands.remove_resource::<ServerConnection>();
+        }
+    }
+}
+
+/// Seats us in the server's lobby and keeps it up to date.
+pub fn apply_lobby_changes(
+    mut commands: Commands,
+    mut ev_server_message: EventReader<ServerMessageEvent>,
+    mut lobby: ResMut<Lobby>,
+) {
+    for ServerMessageEvent(message) in ev_server_message.read() {
+        let state = match message {
+            ServerMessage::Welcome { player, lobby } => {
+                commands.insert_resource(LocalPlayer(*player));
+                info!("Joined the lobby as player {}", player.0);
+                lobby
+            }
+            ServerMessage::LobbyChanged(lobby) => lobby,
+            _ => continue,
+        };
+
+        lobby.setup = state.setup.clone();
+        lobby.seats = state.seats.clone();
+    }
+}
+
+/// Replaces the local game with the one the server started.
+pub fn apply_game_started(
+    mut ev_server_message: EventReader<ServerMessageEvent>,
+    mut starter: GameStarter,
+) {
+    for ServerMessageEvent(message) in ev_server_message.read() {
+        if let ServerMessage::GameStarted(state) = message {
+            starter.start(state);
+            info!("The game started");
+        }
+    }
+}
+
+pub fn apply_hero_moved(
+    mut commands: Commands,
+    mut ev_server_message: EventReader<ServerMessageEvent>,
+    grid: Res<HexGrid>,
+    mut heroes: Query<(Entity, &ServerHero, &mut Transform)>,
+) {
+    for ServerMessageEvent(message) in ev_server_message.read() {
+        let ServerMessage::HeroMoved { hero, path } = message else {
+            continue;
+        };
+        let Some(&destination) = path.last() else {
+            continue;
+        };
+
+        let position = grid.layout.hex_to_world_pos(destination);
+        for (entity, server_hero, mut transform) in heroes.iter_mut() {
+            if server_hero.id == *hero {
+                transform.translation = Vec3::new(position.x, transform.translation.y, position.y);
+                commands.entity(entity).insert(HasMoved);
+            }
+        }
+    }
+}
+
+pub fn apply_turn_started(
+    mut ev_server_message: EventReader<ServerMessageEvent>,
+    mut ev_turn_start: EventWriter<TurnStartEvent>,
+    mut turn_manager: ResMut<TurnManager>,
+    mut next_state: ResMut<NextState<GameplayState>>,
+    local_player: Option<Res<LocalPlayer>>,
+) {
+    for ServerMessageEvent(message) in ev_server_message.read() {
+        let ServerMessage::TurnStarted { player, turn } = message else {
+            continue;
+        };
+
+        let turn_state = GameplayState::PlayerTurn(*player);
+        turn_manager.current_turn = *turn;
+        turn_manager.current_state = turn_state.clone();
+        next_state.set(turn_state);
+        ev_turn_start.send(TurnStartEvent { player_id: *player });
+
+        if local_player
+            .as_ref()
+            .is_some_and(|local| local.0 == *player)
+        {
+            info!("Turn {turn}, your move");
+        }
+    }
+}
+
+pub fn apply_game_over(
+    mut ev_server_message: EventReader<ServerMessageEvent>,
+    mut turn_manager: ResMut<TurnManager>,
+    mut next_state: ResMut<NextState<GameplayState>>,
+) {
+    for ServerMessageEvent(message) in ev_server_message.read() {
+        if matches!(message, ServerMessage::GameOver) {
+            info!("The game is over");
+            turn_manager.current_state = GameplayState::GameOver;
+            next_state.set(GameplayState::GameOver);
+        }
+    }
+}
+
+pub fn log_rejections(mut ev_server_message: EventReader<ServerMessageEvent>) {
+    for ServerMessageEvent(message) in ev_server_message.read() {
+        match message {
+            ServerMessage::Rejected(rejection) => warn!("The server refused: {rejection:?}"),
+            ServerMessage::VersionMismatch { server_version } => error!(
+                "The server speaks protocol version {server_version}, this client {PROTOCOL_VERSION}"
+            ),
+            _ => (),
+        }
+    }
+}
+
+pub fn send_hero_selection(
+    mut connection: ResMut<ServerConnection>,
+    local_player: Option<Res<LocalPlayer>>,
+    selected: Query<(&ServerHero, &Owner), Added<SelectedHero>>,
+    mut deselected: RemovedComponents<SelectedHero>,
+    heroes: Query<&Owner, With<ServerHero>>,
+) {
+    let Some(local_player) = local_player else {
+        return;
+    };
+    let is_own = |owner: &Owner| owner.0 == local_player.0;
+
+    for entity in deselected.read() {
+        if heroes.get(entity).is_ok_and(is_own) {
+            send(&mut connection, &ClientMessage::DeselectHero);
+        }
+    }
+
+    for (hero, _) in selected.iter().filter(|(_, owner)| is_own(owner)) {
+        send(
+            &mut connection,
+            &ClientMessage::SelectHero { hero: hero.id },
+        );
+    }
+}
+
+pub fn send_end_turn(
+    mut connection: ResMut<ServerConnection>,
+    mut ev_turn_end: EventReader<TurnEndEvent>,
+) {
+    for _ in ev_turn_end.read() {
+        send(&mut connection, &ClientMessage::EndTurn);
+    }
+}
+
+pub fn send_lobby_commands(
+    mut connection: ResMut<ServerConnection>,
+    mut ev_lobby_command: EventReader<LobbyCommand>,
+) {
+    for command in ev_lobby_command.read() {
+        let message = match command {
+            LobbyCommand::ChangeSetup(setup) => ClientMessage::ChangeSetup(setup.clone()),
+            LobbyCommand::SetReady(ready) => ClientMessage::SetReady(*ready),
+            LobbyCommand::Start => continue,
+        };
+        send(&mut connection, &message);
+    }
+}
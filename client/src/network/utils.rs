@@ -0,0 +1,175 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use common::{
+    protocol::{ClientMessage, GameSnapshot},
+    setup::{SlotKind, TurnMode},
+};
+
+use crate::{
+    camera::components::GameCamera,
+    core_gameplay::{
+        resources::{Players, TurnManager},
+        states::{AppState, GameplayState},
+    },
+    lobby::resources::Lobby,
+    map::{
+        components::{Cross, RiverSegment},
+        resources::{HexGrid, MapSettings},
+        utils::{spawn_rivers, spawn_tiles},
+    },
+    player::{
+        components::{HasMoved, Hero, HeroIndex, HeroUnits, MovePathPreview, MovementPoints},
+        utils::spawn_hero,
+    },
+};
+
+use super::{
+    components::ServerHero,
+    resources::{LocalPlayer, ServerConnection},
+};
+
+/// The address after `--connect`, given either as `--connect host:port` or
+/// `--connect=host:port`.
+pub fn connect_address(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--connect" {
+            return args.next();
+        }
+        if let Some(addr) = arg.strip_prefix("--connect=") {
+            return Some(addr.to_string());
+        }
+    }
+
+    None
+}
+
+/// Sends `message`, logging it if the server can't be reached. The reader
+/// thread notices when the connection is gone for good.
+pub fn send(connection: &mut ServerConnection, message: &ClientMessage) -> bool {
+    match connection.send(message) {
+        Ok(()) => true,
+        Err(error) => {
+            error!("Could not send {message:?} to the server: {error}");
+            false
+        }
+    }
+}
+
+/// What is left of the map and the paths shown on it besides the tiles.
+type Leftovers = Or<(With<RiverSegment>, With<Cross>, With<MovePathPreview>)>;
+
+/// Everything the game the server started replaces.
+#[derive(SystemParam)]
+pub struct GameStarter<'w, 's> {
+    commands: Commands<'w, 's>,
+    map_settings: ResMut<'w, MapSettings>,
+    grid: ResMut<'w, HexGrid>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+    turn_manager: ResMut<'w, TurnManager>,
+    players: ResMut<'w, Players>,
+    next_state: ResMut<'w, NextState<GameplayState>>,
+    next_app_state: ResMut<'w, NextState<AppState>>,
+    lobby: Res<'w, Lobby>,
+    local_player: Option<Res<'w, LocalPlayer>>,
+    heroes: Query<'w, 's, (Entity, &'static HeroUnits), With<Hero>>,
+    leftovers: Query<'w, 's, Entity, Leftovers>,
+    cameras: Query<'w, 's, &'static mut Transform, With<GameCamera>>,
+}
+
+impl GameStarter<'_, '_> {
+    /// Replaces the map, the players and their heroes with those of `state`,
+    /// and centers the camera on the local player's hero.
+    pub fn start(&mut self, state: &GameSnapshot) {
+        self.grid.entities.values().for_each(|&entity| {
+            self.commands.entity(entity).despawn_recursive();
+        });
+        for (hero, slots) in self.heroes.iter() {
+            slots.0.iter().flatten().for_each(|&unit| {
+                self.commands.entity(unit).despawn_recursive();
+            });
+            self.commands.entity(hero).despawn_recursive();
+        }
+        self.leftovers.iter().for_each(|entity| {
+            self.commands.entity(entity).despawn_recursive();
+        });
+
+        self.map_settings.generator = state.map.generator.clone();
+        self.grid.entities = spawn_tiles(
+            &state.map.tiles(),
+            self.map_settings.hex_size,
+            &mut self.commands,
+            &mut self.meshes,
+            &mut self.materials,
+        );
+        self.grid.shape = state.map.generator.shape;
+        self.grid.reachable_entities.clear();
+
+        let rivers = state.map.rivers();
+        spawn_rivers(
+            &rivers,
+            &self.grid,
+            &mut self.commands,
+            &mut self.meshes,
+            &mut self.materials,
+        );
+        self.commands.insert_resource(rivers);
+
+        // seats that weren't in the lobby are played by the AI on the server
+        let slots: Vec<SlotKind> = state
+            .players
+            .iter()
+            .map(|&player| {
+                self.lobby
+                    .seats
+                    .iter()
+                    .find(|seat| seat.player == player)
+                    .map_or(SlotKind::Ai, |seat| seat.kind)
+            })
+            .collect();
+        *self.players = Players::from_slots(&slots);
+        self.turn_manager.turn_order = state.players.clone();
+
+        for hero in state.heroes.iter() {
+            let entity = spawn_hero(
+                hero.hex,
+                hero.owner,
+                self.players.color(hero.owner),
+                &self.grid,
+                &mut self.commands,
+                &mut self.meshes,
+                &mut self.materials,
+            );
+            let mut entity_commands = self.commands.entity(entity);
+            entity_commands.insert((
+                ServerHero { id: hero.id },
+                HeroIndex(hero.id),
+                MovementPoints(hero.movement_points),
+            ));
+            if hero.has_moved {
+                entity_commands.insert(HasMoved);
+            }
+
+            if self
+                .local_player
+                .as_ref()
+                .is_some_and(|local| local.0 == hero.owner)
+            {
+                let position = self.grid.layout.hex_to_world_pos(hero.hex);
+                for mut transform in self.cameras.iter_mut() {
+                    transform.translation.x = position.x;
+                    transform.translation.z = position.y;
+                }
+            }
+        }
+
+        let turn_state = GameplayState::PlayerTurn(state.active_player);
+        self.turn_manager.current_turn = state.turn;
+        self.turn_manager.max_turns = state.max_turns;
+        self.turn_manager.mode = TurnMode::Sequential;
+        self.turn_manager.current_state = turn_state.clone();
+        self.next_state.set(turn_state);
+        self.next_app_state.set(AppState::InGame);
+    }
+}
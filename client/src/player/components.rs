@@ -1,9 +1,15 @@
 use bevy::prelude::*;
+use common::protocol::HeroId;
 use hexx::Hex;
 
 #[derive(Component)]
 pub struct Hero;
 
+/// Number of a hero, counting the start positions in turn order like the
+/// server does. Logged commands find their hero by it when replayed.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct HeroIndex(pub HeroId);
+
 #[derive(Component, Reflect)]
 pub struct Position(pub Hex);
 
@@ -28,6 +34,10 @@ pub struct DefensePoints(pub i32);
 #[derive(Component, Reflect, Debug)]
 pub struct MovementPoints(pub u32);
 
+/// Movement points a unit gets back at the start of its owner's turn.
+#[derive(Component, Reflect, Debug)]
+pub struct MaxMovementPoints(pub u32);
+
 #[derive(Component, Reflect)]
 pub struct Range(pub u32);
 
@@ -71,29 +81,18 @@ pub struct MoveTarget(pub Hex);
 #[derive(Component)]
 pub struct MovePath(pub Vec<Entity>);
 
-#[derive(Component)]
-pub struct MovePathPreview(pub Entity);
-
-#[derive(Component)]
-pub struct Player1Marker;
-
-#[derive(Component)]
-pub struct Player2Marker;
-
-#[derive(Component)]
-pub struct Player3Marker;
-
-#[derive(Component)]
-pub struct Player4Marker;
-
-#[derive(Component)]
-pub struct Player5Marker;
-
-#[derive(Component)]
-pub struct Player6Marker;
+/// Where a hero ordered further than it gets in one turn is headed, and the
+/// hexes it still has to cross. Each move along the way takes the hexes it
+/// crossed off.
+#[derive(Component, Clone)]
+pub struct Journey {
+    pub to: Hex,
+    pub path: Vec<Hex>,
+}
 
+/// Shows on screen in which turn a hero gets to a hex of its path.
 #[derive(Component)]
-pub struct Player7Marker;
+pub struct TurnMarker(pub Vec3);
 
 #[derive(Component)]
-pub struct Player8Marker;
+pub struct MovePathPreview(pub Entity);
@@ -1,6 +1,22 @@
 use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
 
+/// A hero was clicked. It is only selected if its player may command it.
+#[derive(Event)]
+pub struct HeroSelectEvent {
+    pub hero: Entity,
+    pub button: PointerButton,
+}
+
+impl From<ListenerInput<Pointer<Click>>> for HeroSelectEvent {
+    fn from(value: ListenerInput<Pointer<Click>>) -> Self {
+        Self {
+            hero: value.target,
+            button: value.button,
+        }
+    }
+}
+
 #[derive(Event)]
 pub struct HeroDeselectEvent {
     pub hero: Entity,
@@ -1,18 +1,25 @@
 use bevy::prelude::*;
 use components::{
-    AttackPoints, DefensePoints, Experience, Health, HeroMaxUnits, HeroUnits, Level, MoveTarget,
-    MovementPoints, Position, Range, UnitType,
+    AttackPoints, DefensePoints, Experience, Health, HeroMaxUnits, HeroUnits, Level,
+    MaxMovementPoints, MoveTarget, MovementPoints, Position, Range, UnitType,
 };
-use events::{HeroDeselectEvent, PathCalculatedEvent};
+use events::{HeroDeselectEvent, HeroSelectEvent, PathCalculatedEvent};
 use systems::{
     calculate_path_system, clear_move_path, display_field_of_movement, draw_move_path,
-    handle_hero_deselect, handle_hero_movement, setup_player,
+    handle_hero_attack, handle_hero_deselect, handle_hero_movement, handle_hero_select,
+    handle_hero_wait, place_turn_markers, setup_player,
+};
+
+use crate::{
+    actions::ActionQueueSet, core_gameplay::states::AppState, map::MapSetupSet,
+    network::resources::ServerConnection,
 };
 
 pub mod components;
 pub mod events;
 pub mod resources;
 mod systems;
+pub mod utils;
 
 pub struct PlayerPlugin;
 
@@ -25,23 +32,37 @@ impl Plugin for PlayerPlugin {
             .register_type::<AttackPoints>()
             .register_type::<DefensePoints>()
             .register_type::<MovementPoints>()
+            .register_type::<MaxMovementPoints>()
             .register_type::<Range>()
             .register_type::<UnitType>()
             .register_type::<HeroUnits>()
             .register_type::<HeroMaxUnits>()
             .register_type::<MoveTarget>()
+            .add_event::<HeroSelectEvent>()
             .add_event::<HeroDeselectEvent>()
             .add_event::<PathCalculatedEvent>()
-            .add_systems(Startup, setup_player)
+            .add_systems(
+                OnEnter(AppState::InGame),
+                setup_player
+                    .after(MapSetupSet)
+                    .run_if(not(resource_exists::<ServerConnection>)),
+            )
             .add_systems(
                 Update,
                 (
+                    handle_hero_select,
                     display_field_of_movement,
                     handle_hero_deselect,
                     calculate_path_system,
-                    handle_hero_movement,
+                    // journeys are set before the moves walk heroes on them
+                    handle_hero_movement.before(ActionQueueSet),
+                    // the server only knows how to move heroes
+                    (handle_hero_attack, handle_hero_wait)
+                        .before(ActionQueueSet)
+                        .run_if(not(resource_exists::<ServerConnection>)),
                     draw_move_path,
                     clear_move_path,
+                    place_turn_markers,
                 ),
             );
     }
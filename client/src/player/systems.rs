@@ -1,61 +1,99 @@
-use bevy::{prelude::*, utils::HashSet};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use bevy_mod_picking::prelude::*;
-use hexx::{
-    algorithms::{a_star, field_of_movement},
-    Hex,
+use common::{
+    map::{
+        components::Tile, pathfinding::turn_stops, rivers::Rivers, start_positions::StartPositions,
+    },
+    protocol::HeroId,
+};
+use hexx::{algorithms::field_of_movement, Hex};
+
+use crate::actions::{combat::AttackAction, moves::MoveAction, turns::WaitAction};
+use crate::camera::components::GameCamera;
+use crate::core_gameplay::{
+    components::{Actor, Owner},
+    resources::{Players, TurnManager},
+    states::GameplayState,
 };
-
 use crate::map::{
-    components::Tile,
     events::{TileDeselectEvent, TileSelectEvent},
     resources::HexGrid,
+    utils::collect_tiles,
 };
+use crate::network::resources::LocalPlayer;
 
 use super::{
     components::{
-        Experience, HasCalculatedFieldOfMovement, HasCalculatedPath, HasMoved, Health, Hero,
-        HeroMaxUnits, HeroUnits, Level, MovePath, MovePathPreview, MoveTarget, MovementPoints,
-        SelectedHero,
+        HasCalculatedFieldOfMovement, HasCalculatedPath, HasMoved, Hero, HeroIndex,
+        MaxMovementPoints, MovePath, MovePathPreview, MoveTarget, MovementPoints, SelectedHero,
+        TurnMarker,
+    },
+    events::{HeroDeselectEvent, HeroSelectEvent, PathCalculatedEvent},
+    utils::{
+        hero_way, order_leg, path_hexes, planning_ahead, HeroSpawner, JourneyOrders, PathMarkers,
     },
-    events::{HeroDeselectEvent, PathCalculatedEvent},
 };
 
+/// Gives every player a hero on their start position.
 pub fn setup_player(
-    mut commands: Commands,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    mut meshes: ResMut<Assets<Mesh>>,
+    mut spawner: HeroSpawner,
+    starts: Res<StartPositions>,
+    turn_manager: Res<TurnManager>,
+    players: Res<Players>,
+    mut cameras: Query<&mut Transform, With<GameCamera>>,
 ) {
-    let hero_shape = meshes.add(Cuboid {
-        half_size: Vec3::new(8.0, 8.0, 8.0),
-    });
-    let hero_mat = materials.add(Color::rgb(0.0, 0.0, 1.0));
-
-    commands.spawn((
-        Name::new("Hero".to_string()),
-        PbrBundle {
-            mesh: hero_shape,
-            material: hero_mat,
-            transform: Transform::from_translation(Vec3::new(0.0, 11.0, 1.0)),
-            ..Default::default()
-        },
-        Hero,
-        Experience(0),
-        Level(1),
-        Health {
-            current: 100,
-            max: 100,
-        },
-        MovementPoints(5),
-        HeroUnits(vec![None; 10]),
-        HeroMaxUnits(10),
-        PickableBundle::default(),
-        On::<Pointer<Click>>::target_commands_mut(|click, target_commands| {
-            if click.button == PointerButton::Primary {
-                target_commands.insert(SelectedHero { 0: click.target });
+    for (index, &player) in turn_manager.turn_order.iter().enumerate() {
+        let start = starts
+            .positions
+            .get(index)
+            .map(|start| start.hex)
+            .unwrap_or(Hex::ZERO);
+
+        if index == 0 {
+            let position = spawner.grid.layout.hex_to_world_pos(start);
+            for mut transform in cameras.iter_mut() {
+                transform.translation.x = position.x;
+                transform.translation.z = position.y;
             }
-        }),
-        On::<Pointer<Down>>::send_event::<HeroDeselectEvent>(),
-    ));
+        }
+
+        let hero = spawner.spawn(start, player, players.color(player));
+        spawner
+            .commands
+            .entity(hero)
+            .insert(HeroIndex(HeroId(index as u32)));
+    }
+}
+
+/// Selects a clicked hero if it is the turn of its player, or online if it
+/// belongs to this client.
+pub fn handle_hero_select(
+    mut commands: Commands,
+    mut ev_hero_select: EventReader<HeroSelectEvent>,
+    state: Res<State<GameplayState>>,
+    local_player: Option<Res<LocalPlayer>>,
+    heroes: Query<&Owner, With<Hero>>,
+) {
+    let commanding = match local_player {
+        Some(local_player) => Some(local_player.0),
+        None => state.get().player(),
+    };
+
+    for event in ev_hero_select.read() {
+        if event.button != PointerButton::Primary {
+            continue;
+        }
+        let Ok(owner) = heroes.get(event.hero) else {
+            continue;
+        };
+
+        if Some(owner.0) == commanding {
+            commands.entity(event.hero).insert(SelectedHero(event.hero));
+        }
+    }
 }
 
 pub fn display_field_of_movement(
@@ -79,7 +117,7 @@ pub fn display_field_of_movement(
     }
 
     let (hero_entity, movement_points, hero_transform, has_calculated_fom) = selected_hero.single();
-    let hero_hex = grid.layout.world_pos_to_hex(Vec2::new(
+    let hero_hex = grid.world_pos_to_hex(Vec2::new(
         hero_transform.translation.x,
         hero_transform.translation.z,
     ));
@@ -92,7 +130,7 @@ pub fn display_field_of_movement(
 
     let field_of_movement = field_of_movement(hero_hex, movement_points.0, |h| {
         for (entity, tile) in tiles.iter() {
-            if grid.entities.get(&h).map(|&ent| ent) == Some(entity) {
+            if grid.get(h) == Some(entity) {
                 if movement_points.0 < tile.cost()? {
                     return None;
                 }
@@ -106,7 +144,7 @@ pub fn display_field_of_movement(
 
     let reachable_entities: HashSet<_> = field_of_movement
         .into_iter()
-        .filter_map(|h| grid.entities.get(&h).map(|&ent| ent))
+        .filter_map(|h| grid.get(h))
         .collect();
 
     for (entity, mut transform) in tile_transforms.iter_mut() {
@@ -123,110 +161,145 @@ pub fn display_field_of_movement(
         .insert(HasCalculatedFieldOfMovement);
 }
 
-fn calculate_path(
-    start: Hex,
-    goal: Hex,
-    grid: &HexGrid,
-    tiles: Vec<(Entity, &Tile)>,
-    movement_points: u32,
-) -> Option<Vec<Hex>> {
-    a_star(start, goal, |_, h| {
-        for (entity, tile) in tiles.iter() {
-            if grid.entities.get(&h).map(|&ent| ent) == Some(*entity) {
-                if movement_points < tile.cost()? {
-                    return None;
-                }
-
-                return tile.cost();
-            }
-        }
-
-        None
-    })
-}
-
+/// A selected hero with the path it was ordered down.
+type OrderedHero = (
+    Entity,
+    &'static MovePath,
+    &'static MovementPoints,
+    &'static MaxMovementPoints,
+    &'static mut Actor,
+);
+
+/// Orders the selected hero as far along its path as it gets this turn. A
+/// longer path is kept as the hero's journey, which the move walks it on. With
+/// Shift held the move is added to the hero's plan.
 pub fn handle_hero_movement(
-    mut hero_query: Query<
-        (
-            Entity,
-            Option<&mut MovePath>,
-            &mut Transform,
-            Option<&HasMoved>,
-        ),
-        With<SelectedHero>,
-    >,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut hero_query: Query<OrderedHero, (With<SelectedHero>, Without<HasMoved>)>,
     grid: Res<HexGrid>,
-    mut commands: Commands,
+    rivers: Res<Rivers>,
+    tiles: Query<&Tile>,
+    mut journeys: JourneyOrders,
 ) {
-    for (hero_entity, move_path_option, mut transform, has_moved) in hero_query.iter_mut() {
-        if has_moved.is_some() {
+    for (hero, move_path, points, max_points, mut actor) in hero_query.iter_mut() {
+        let path = path_hexes(move_path, &grid);
+        let Some((&start, path)) = path.split_first() else {
             continue;
+        };
+        let (to, journey) = order_leg(
+            &collect_tiles(&grid, &tiles),
+            &grid,
+            &rivers,
+            start,
+            path,
+            points.0,
+            max_points.0,
+        );
+
+        journeys
+            .commands
+            .entity(hero)
+            .remove::<MovePath>()
+            .remove::<HasCalculatedFieldOfMovement>()
+            .remove::<HasCalculatedPath>();
+        match (journey, path.last()) {
+            (Some(journey), Some(&goal)) => journeys.start(hero, goal, journey),
+            _ => journeys.end(hero),
         }
+        if let Some(to) = to {
+            actor.plan(Box::new(MoveAction { hero, to }), planning_ahead(&keys));
+        }
+    }
+}
 
-        // todo: move player along the path
-        let move_path = match move_path_option {
-            Some(move_path) => move_path,
-            None => continue,
+/// Orders the selected hero to attack an enemy hero that was clicked. With
+/// Shift held the attack is added to the hero's plan, otherwise it replaces
+/// the plan and the hero's journey.
+pub fn handle_hero_attack(
+    mut journeys: JourneyOrders,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut ev_hero_select: EventReader<HeroSelectEvent>,
+    mut selected: Query<(Entity, &Owner, &mut Actor), With<SelectedHero>>,
+    heroes: Query<&Owner, With<Hero>>,
+) {
+    for event in ev_hero_select.read() {
+        if event.button != PointerButton::Primary {
+            continue;
+        }
+        let Ok(target_owner) = heroes.get(event.hero) else {
+            continue;
         };
 
-        let mut path = move_path.0.iter().rev().map(|&entity| {
-            grid.entities
-                .iter()
-                .find_map(|(hex, ent)| if *ent == entity { Some(hex) } else { None })
-                .unwrap()
-        }); // now we have the path reversed from goal to start
-
-        // we have to get the first element of the path that is in reach of the hero
-
-        let first_reachable_hex =
-            path.find(|&hex| grid.reachable_entities.contains(&grid.entities[hex]));
-
-        if let Some(first_reachable_hex) = first_reachable_hex {
-            let move_pos = grid.layout.hex_to_world_pos(*first_reachable_hex);
+        for (attacker, owner, mut actor) in selected.iter_mut() {
+            if owner != target_owner {
+                let attack = AttackAction {
+                    attacker,
+                    target: event.hero,
+                };
+                actor.plan(Box::new(attack), planning_ahead(&keys));
+                if !planning_ahead(&keys) {
+                    journeys.end(attacker);
+                }
+            }
+        }
+    }
+}
 
-            transform.translation = Vec3::new(move_pos.x, transform.translation.y, move_pos.y);
+/// Lets the selected hero sit out the turn when Space is pressed. With Shift
+/// held it does so once it is done with its plan, otherwise it gives up its
+/// plan and journey.
+pub fn handle_hero_wait(
+    mut journeys: JourneyOrders,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut selected: Query<(Entity, &mut Actor), With<SelectedHero>>,
+) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
 
-            commands
-                .entity(hero_entity)
-                .remove::<MovePath>()
-                .remove::<HasCalculatedFieldOfMovement>()
-                .remove::<HasCalculatedPath>()
-                .insert(HasMoved);
+    for (hero, mut actor) in selected.iter_mut() {
+        actor.plan(Box::new(WaitAction { hero }), planning_ahead(&keys));
+        if !planning_ahead(&keys) {
+            journeys.end(hero);
         }
     }
 }
 
+/// Finds the way to the selected hero's target, over tiles a full turn's
+/// movement points get it across. The path starts with the hero's own hex.
 pub fn calculate_path_system(
     mut commands: Commands,
     grid: ResMut<HexGrid>,
-    tiles: Query<(Entity, &Tile)>,
+    rivers: Res<Rivers>,
+    tiles: Query<&Tile>,
     hero_query: Query<
-        (Entity, &Hero, &Transform, &MovementPoints, &MoveTarget),
+        (Entity, &Hero, &Transform, &MaxMovementPoints, &MoveTarget),
         With<SelectedHero>,
     >,
     mut ev_tile_select: EventReader<TileSelectEvent>,
     mut ev_path_calculated: EventWriter<PathCalculatedEvent>,
 ) {
     ev_tile_select.read().for_each(|_| {
-        for (hero_entity, _, transform, hero_movement_points, move_target) in hero_query.iter() {
-            let start = grid
-                .layout
-                .world_pos_to_hex(Vec2::new(transform.translation.x, transform.translation.z));
+        for (hero_entity, _, transform, max_movement_points, move_target) in hero_query.iter() {
+            let start =
+                grid.world_pos_to_hex(Vec2::new(transform.translation.x, transform.translation.z));
             let goal = move_target.0;
 
-            let path = calculate_path(
+            let path_entites: Vec<Entity> = hero_way(
+                &collect_tiles(&grid, &tiles),
+                &grid,
+                &rivers,
                 start,
                 goal,
-                &grid,
-                tiles.iter().collect(),
-                hero_movement_points.0,
+                max_movement_points.0,
             )
-            .unwrap_or_else(|| vec![]);
-
-            let path_entites: Vec<Entity> = path
-                .iter()
-                .filter_map(|h| grid.entities.get(h).map(|&ent| ent))
-                .collect();
+            .map(|way| {
+                std::iter::once(start)
+                    .chain(way)
+                    .filter_map(|h| grid.get(h))
+                    .collect()
+            })
+            .unwrap_or_default();
 
             commands.entity(hero_entity).insert(MovePath(path_entites));
 
@@ -274,28 +347,29 @@ pub fn handle_hero_deselect(
     }
 }
 
+/// Marks the hexes of the selected hero's path. A path that takes several
+/// turns is numbered with the turn the hero gets to each stop.
 pub fn draw_move_path(
-    mut commands: Commands,
+    mut markers: PathMarkers,
     grid: ResMut<HexGrid>,
-    move_path_query: Query<(Entity, &MovePath), With<SelectedHero>>,
-    assets_server: Res<AssetServer>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    mut meshes: ResMut<Assets<Mesh>>,
+    rivers: Res<Rivers>,
+    tiles: Query<&Tile>,
+    move_path_query: Query<(&MovePath, &MovementPoints, &MaxMovementPoints), With<SelectedHero>>,
     mut ev_path_calculated: EventReader<PathCalculatedEvent>,
 ) {
     ev_path_calculated.read().for_each(|_| {
-        for (_, move_path) in move_path_query.iter() {
-            for entity in move_path.0.iter() {
-                let donut_sprite = assets_server.load("sprites/donut.png");
-
-                let quad = meshes.add(Rectangle::new(8.0, 8.0));
-                let quad_material = materials.add(StandardMaterial {
-                    base_color_texture: Some(donut_sprite.clone()),
-                    alpha_mode: AlphaMode::Blend,
-                    unlit: true,
-                    ..default()
-                });
+        for (move_path, movement_points, max_movement_points) in move_path_query.iter() {
+            spawn_turn_markers(
+                move_path,
+                movement_points.0,
+                max_movement_points.0,
+                &collect_tiles(&grid, &tiles),
+                &grid,
+                &rivers,
+                &mut markers.commands,
+            );
 
+            for entity in move_path.0.iter() {
                 // check if the entity is in the grid
                 if let Some(hex) =
                     grid.entities.iter().find_map(
@@ -308,24 +382,92 @@ pub fn draw_move_path(
                         },
                     )
                 {
-                    let pos = grid.layout.hex_to_world_pos(*hex);
-                    commands.spawn(((
-                        PbrBundle {
-                            mesh: quad.clone().into(),
-                            material: quad_material.clone(),
-                            transform: Transform::from_xyz(pos.x, 2.0, pos.y)
-                                .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
-                            ..default()
-                        },
-                        MovePathPreview(*entity),
-                        Name::new("MovePathPreview".to_string()),
-                    ),));
+                    markers.mark(*entity, grid.layout.hex_to_world_pos(*hex));
                 }
             }
         }
     });
 }
 
+/// Numbers the stops of a path that takes more than one turn.
+fn spawn_turn_markers(
+    move_path: &MovePath,
+    movement_points: u32,
+    max_movement_points: u32,
+    tiles: &HashMap<Hex, Tile>,
+    grid: &HexGrid,
+    rivers: &Rivers,
+    commands: &mut Commands,
+) {
+    let path = path_hexes(move_path, grid);
+    let Some((&start, path)) = path.split_first() else {
+        return;
+    };
+    let stops = turn_stops(
+        tiles,
+        &grid.shape,
+        rivers,
+        start,
+        path,
+        movement_points,
+        max_movement_points,
+    );
+    if stops.len() < 2 {
+        return;
+    }
+
+    for (turn, &stop) in stops.iter().enumerate() {
+        // a hero that can't get anywhere this turn has no stop to mark
+        let Some(last) = stop.checked_sub(1) else {
+            continue;
+        };
+        let Some(tile) = grid.get(path[last]) else {
+            continue;
+        };
+        let position = grid.layout.hex_to_world_pos(path[last]);
+
+        commands.spawn((
+            TextBundle::from_section(
+                (turn + 1).to_string(),
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                ..default()
+            }),
+            TurnMarker(Vec3::new(position.x, 2.0, position.y)),
+            MovePathPreview(tile),
+            Pickable::IGNORE,
+            Name::new("TurnMarker".to_string()),
+        ));
+    }
+}
+
+/// Keeps the turn markers over their hexes as the camera moves.
+pub fn place_turn_markers(
+    cameras: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
+    mut markers: Query<(&TurnMarker, &mut Style, &mut Visibility)>,
+) {
+    let Some((camera, camera_transform)) = cameras.iter().next() else {
+        return;
+    };
+
+    for (marker, mut style, mut visibility) in markers.iter_mut() {
+        match camera.world_to_viewport(camera_transform, marker.0) {
+            Some(position) => {
+                style.left = Val::Px(position.x);
+                style.top = Val::Px(position.y);
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
 pub fn clear_move_path(
     mut commands: Commands,
     mut ev_tile_deselect: EventReader<TileDeselectEvent>,
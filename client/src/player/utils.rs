@@ -0,0 +1,289 @@
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use bevy_mod_picking::prelude::*;
+use common::{
+    map::{
+        components::Tile,
+        pathfinding::{find_path, turn_stops},
+        rivers::Rivers,
+    },
+    protocol::{HeroId, PlayerId},
+    replay::LoggedAction,
+};
+use hexx::Hex;
+
+use crate::{
+    core_gameplay::{
+        components::{ActionPoints, Actor, MaxActionPoints, Owner},
+        resources::TurnManager,
+    },
+    map::resources::HexGrid,
+    replay::resources::CommandRecorder,
+};
+
+use super::{
+    components::{
+        Experience, Health, Hero, HeroIndex, HeroMaxUnits, HeroUnits, Journey, Level,
+        MaxMovementPoints, MovePath, MovePathPreview, MovementPoints, Unit, UnitType,
+    },
+    events::{HeroDeselectEvent, HeroSelectEvent},
+};
+
+/// Height of a hero's center above the tiles.
+pub const HERO_HEIGHT: f32 = 11.0;
+
+/// Movement points a hero gets each turn.
+pub const HERO_MOVEMENT_POINTS: u32 = 5;
+
+/// Actions a hero can carry out each turn.
+pub const HERO_ACTION_POINTS: u32 = 2;
+
+/// What spawning heroes takes.
+#[derive(SystemParam)]
+pub struct HeroSpawner<'w, 's> {
+    pub commands: Commands<'w, 's>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+    pub grid: Res<'w, HexGrid>,
+}
+
+impl HeroSpawner<'_, '_> {
+    pub fn spawn(&mut self, hex: Hex, owner: PlayerId, color: Color) -> Entity {
+        spawn_hero(
+            hex,
+            owner,
+            color,
+            &self.grid,
+            &mut self.commands,
+            &mut self.meshes,
+            &mut self.materials,
+        )
+    }
+}
+
+/// What marking the hexes of a hero's path takes.
+#[derive(SystemParam)]
+pub struct PathMarkers<'w, 's> {
+    pub commands: Commands<'w, 's>,
+    assets_server: Res<'w, AssetServer>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+}
+
+impl PathMarkers<'_, '_> {
+    /// Puts a marker over `tile`, which lies at `position`.
+    pub fn mark(&mut self, tile: Entity, position: Vec2) {
+        let donut_sprite = self.assets_server.load("sprites/donut.png");
+
+        let quad = self.meshes.add(Rectangle::new(8.0, 8.0));
+        let quad_material = self.materials.add(StandardMaterial {
+            base_color_texture: Some(donut_sprite),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        });
+
+        self.commands.spawn((
+            PbrBundle {
+                mesh: quad,
+                material: quad_material,
+                transform: Transform::from_xyz(position.x, 2.0, position.y)
+                    .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
+                ..default()
+            },
+            MovePathPreview(tile),
+            Name::new("MovePathPreview".to_string()),
+        ));
+    }
+}
+
+/// What sending heroes on journeys and making them give one up takes. Both
+/// are logged, the moves along a journey follow from it.
+#[derive(SystemParam)]
+pub struct JourneyOrders<'w, 's> {
+    pub commands: Commands<'w, 's>,
+    recorder: ResMut<'w, CommandRecorder>,
+    turn_manager: Res<'w, TurnManager>,
+    heroes: Query<'w, 's, (&'static Owner, &'static HeroIndex)>,
+    travellers: Query<'w, 's, (), With<Journey>>,
+}
+
+impl JourneyOrders<'_, '_> {
+    /// Sends `hero` on `journey`, which it was ordered on towards `goal`.
+    pub fn start(&mut self, hero: Entity, goal: Hex, journey: Journey) {
+        self.commands.entity(hero).insert(journey);
+        self.record(hero, |hero| LoggedAction::Journey { hero, to: goal });
+    }
+
+    /// Makes `hero` give up its journey, if it is on one.
+    pub fn end(&mut self, hero: Entity) {
+        if !self.travellers.contains(hero) {
+            return;
+        }
+        self.commands.entity(hero).remove::<Journey>();
+        self.record(hero, |hero| LoggedAction::EndJourney { hero });
+    }
+
+    fn record(&mut self, hero: Entity, action: impl FnOnce(HeroId) -> LoggedAction) {
+        if let Ok((owner, index)) = self.heroes.get(hero) {
+            let turn = self.turn_manager.current_turn;
+            self.recorder.record(turn, owner.0, action(index.0));
+        }
+    }
+}
+
+/// Spawns a fresh level 1 hero of `owner` standing on `hex`.
+pub fn spawn_hero(
+    hex: Hex,
+    owner: PlayerId,
+    color: Color,
+    grid: &HexGrid,
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) -> Entity {
+    let position = grid.layout.hex_to_world_pos(hex);
+    let hero_shape = meshes.add(Cuboid {
+        half_size: Vec3::new(8.0, 8.0, 8.0),
+    });
+    let hero_mat = materials.add(color);
+
+    commands
+        .spawn((
+            Name::new(format!("Hero of player {}", owner.0)),
+            PbrBundle {
+                mesh: hero_shape,
+                material: hero_mat,
+                transform: Transform::from_xyz(position.x, HERO_HEIGHT, position.y),
+                ..Default::default()
+            },
+            Hero,
+            Owner(owner),
+            Experience(0),
+            Level(1),
+            Health {
+                current: 100,
+                max: 100,
+            },
+            MovementPoints(HERO_MOVEMENT_POINTS),
+            MaxMovementPoints(HERO_MOVEMENT_POINTS),
+            (
+                Actor::default(),
+                ActionPoints(HERO_ACTION_POINTS),
+                MaxActionPoints(HERO_ACTION_POINTS),
+            ),
+            HeroUnits(vec![None; 10]),
+            HeroMaxUnits(10),
+            PickableBundle::default(),
+            On::<Pointer<Click>>::send_event::<HeroSelectEvent>(),
+            On::<Pointer<Down>>::send_event::<HeroDeselectEvent>(),
+        ))
+        .id()
+}
+
+/// Spawns a unit that can be put into one of a hero's `HeroUnits` slots.
+pub fn spawn_unit(unit_type: UnitType, commands: &mut Commands) -> Entity {
+    commands
+        .spawn((Name::new("Unit".to_string()), Unit, unit_type))
+        .id()
+}
+
+/// Whether Shift is held, which adds an order to the end of a hero's plan
+/// instead of replacing the plan.
+pub fn planning_ahead(keys: &ButtonInput<KeyCode>) -> bool {
+    keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+}
+
+/// Hexes of `path`, starting with the one the hero stands on.
+pub fn path_hexes(path: &MovePath, grid: &HexGrid) -> Vec<Hex> {
+    let hexes: HashMap<Entity, Hex> = grid
+        .entities
+        .iter()
+        .map(|(&hex, &entity)| (entity, hex))
+        .collect();
+
+    path.0
+        .iter()
+        .filter_map(|tile| hexes.get(tile).copied())
+        .collect()
+}
+
+/// The way a hero takes from `start` to `goal`, over tiles a full turn's
+/// movement points get it across, if there is one.
+pub fn hero_way(
+    tiles: &HashMap<Hex, Tile>,
+    grid: &HexGrid,
+    rivers: &Rivers,
+    start: Hex,
+    goal: Hex,
+    max_movement_points: u32,
+) -> Option<Vec<Hex>> {
+    // no turn is long enough to cross a tile that costs more than a full
+    // turn's points
+    let open: HashMap<Hex, Tile> = tiles
+        .iter()
+        .filter(|(_, tile)| tile.cost().is_some_and(|cost| cost <= max_movement_points))
+        .map(|(&hex, tile)| (hex, tile.clone()))
+        .collect();
+
+    find_path(&open, &grid.shape, rivers, start, goal).map(|(path, _)| path)
+}
+
+/// Where a hero ordered down `path` from `start` gets this turn, if anywhere,
+/// and the journey it is left with when that isn't the end of the way.
+pub fn order_leg(
+    tiles: &HashMap<Hex, Tile>,
+    grid: &HexGrid,
+    rivers: &Rivers,
+    start: Hex,
+    path: &[Hex],
+    movement_points: u32,
+    max_movement_points: u32,
+) -> (Option<Hex>, Option<Journey>) {
+    let (to, way) = next_leg(
+        tiles,
+        grid,
+        rivers,
+        start,
+        path,
+        movement_points,
+        max_movement_points,
+    );
+    let journey = match way.last() {
+        Some(&goal) if Some(goal) != to => Some(Journey {
+            to: goal,
+            path: way,
+        }),
+        _ => None,
+    };
+
+    (to, journey)
+}
+
+/// Where a hero following `path` on from `start` gets this turn, if anywhere,
+/// and the whole way it can make this turn and the turns after.
+pub fn next_leg(
+    tiles: &HashMap<Hex, Tile>,
+    grid: &HexGrid,
+    rivers: &Rivers,
+    start: Hex,
+    path: &[Hex],
+    movement_points: u32,
+    max_movement_points: u32,
+) -> (Option<Hex>, Vec<Hex>) {
+    let stops = turn_stops(
+        tiles,
+        &grid.shape,
+        rivers,
+        start,
+        path,
+        movement_points,
+        max_movement_points,
+    );
+    let reached = stops.first().copied().unwrap_or(0);
+    let end = stops.last().copied().unwrap_or(0);
+
+    (
+        reached.checked_sub(1).map(|last| path[last]),
+        path[..end].to_vec(),
+    )
+}
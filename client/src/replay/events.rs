@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+/// Starts a game from the command log in `ReplaySettings` and plays it back.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StartReplay;
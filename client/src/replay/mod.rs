@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+use events::StartReplay;
+use resources::{CommandRecorder, Replay, ReplaySettings};
+use systems::{feed_replay, finish_replay, start_recording, start_replay, write_command_log};
+
+use crate::{core_gameplay::states::AppState, network::resources::ServerConnection};
+
+pub mod events;
+pub mod resources;
+mod systems;
+pub mod utils;
+
+/// Logs what players do in a local game, so it can be played again from its
+/// setup. F7 writes the log. A replay starts the logged game from the lobby,
+/// carries out the commands in the turns they were given and checks that it
+/// ends up in the same game.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplaySettings>()
+            .init_resource::<CommandRecorder>()
+            .add_event::<StartReplay>()
+            .add_systems(Update, start_replay.run_if(in_state(AppState::Lobby)))
+            .add_systems(
+                OnEnter(AppState::InGame),
+                start_recording.run_if(not(resource_exists::<ServerConnection>)),
+            )
+            .add_systems(Update, write_command_log.run_if(in_state(AppState::InGame)))
+            .add_systems(
+                PreUpdate,
+                feed_replay
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(resource_exists::<Replay>),
+            )
+            .add_systems(
+                PostUpdate,
+                finish_replay
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(resource_exists::<Replay>),
+            );
+    }
+}
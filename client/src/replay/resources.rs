@@ -0,0 +1,57 @@
+use std::{collections::VecDeque, path::PathBuf};
+
+use bevy::prelude::*;
+use common::{
+    protocol::PlayerId,
+    replay::{CommandLog, LoggedAction, LoggedCommand},
+};
+
+#[derive(Debug, Resource)]
+pub struct ReplaySettings {
+    /// File the command log is written to and replayed from.
+    pub path: PathBuf,
+}
+
+impl Default for ReplaySettings {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("saves/commands.ron"),
+        }
+    }
+}
+
+/// Log of the game being played. There is none online, or once a save was
+/// loaded, since the seed alone no longer leads to the game.
+#[derive(Debug, Default, Resource)]
+pub struct CommandRecorder {
+    pub log: Option<CommandLog>,
+}
+
+impl CommandRecorder {
+    pub fn record(&mut self, turn: u32, player: PlayerId, action: LoggedAction) {
+        if let Some(log) = self.log.as_mut() {
+            log.record(turn, player, action);
+        }
+    }
+}
+
+/// A command log being played back.
+#[derive(Debug, Resource)]
+pub struct Replay {
+    /// Commands still to carry out, in order.
+    pub commands: VecDeque<LoggedCommand>,
+    /// Hash the game should end with.
+    pub final_hash: Option<u64>,
+    /// Frames in a row nothing happened after the last command.
+    pub idle_frames: u32,
+}
+
+impl From<CommandLog> for Replay {
+    fn from(log: CommandLog) -> Self {
+        Self {
+            commands: log.commands.into(),
+            final_hash: log.final_hash,
+            idle_frames: 0,
+        }
+    }
+}
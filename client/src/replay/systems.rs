@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+use common::replay::{world_hash, CommandLog, LoggedAction};
+
+use crate::{
+    actions::{events::HistoryCommand, resources::ActorQueue},
+    core_gameplay::{resources::TurnManager, states::GameplayState},
+    lobby::{events::LobbyCommand, resources::Lobby},
+    player::components::Journey,
+    save::utils::GameSaver,
+};
+
+use super::{
+    events::StartReplay,
+    resources::{CommandRecorder, Replay, ReplaySettings},
+    utils::{replayed_action, ReplayActors},
+};
+
+/// Frames nothing may happen after the last command before a replay counts
+/// as finished. Ending a turn takes a few frames to play out.
+const SETTLE_FRAMES: u32 = 3;
+
+pub fn start_recording(lobby: Res<Lobby>, mut recorder: ResMut<CommandRecorder>) {
+    recorder.log = Some(CommandLog::new(lobby.setup.clone()));
+}
+
+/// Writes the command log when F7 is pressed, along with the hash of the game
+/// as it is now.
+pub fn write_command_log(
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<ReplaySettings>,
+    mut recorder: ResMut<CommandRecorder>,
+    saver: GameSaver,
+) {
+    if !keys.just_pressed(KeyCode::F7) {
+        return;
+    }
+    let Some(log) = recorder.log.as_mut() else {
+        warn!("This game has no command log, it was loaded or is played online");
+        return;
+    };
+
+    log.final_hash = Some(world_hash(&saver.save()));
+    match log.write(&settings.path) {
+        Ok(()) => info!("Wrote command log to {}", settings.path.display()),
+        Err(error) => error!("Writing {} failed: {error}", settings.path.display()),
+    }
+}
+
+/// Sets up the logged game and starts it, its commands follow once it runs.
+pub fn start_replay(
+    mut commands: Commands,
+    mut ev_start_replay: EventReader<StartReplay>,
+    settings: Res<ReplaySettings>,
+    mut lobby: ResMut<Lobby>,
+    mut ev_lobby_command: EventWriter<LobbyCommand>,
+) {
+    if ev_start_replay.read().count() == 0 {
+        return;
+    }
+
+    let log = match CommandLog::read(&settings.path) {
+        Ok(log) => log,
+        Err(error) => {
+            error!("Replaying {} failed: {error}", settings.path.display());
+            return;
+        }
+    };
+
+    info!(
+        "Replaying {} commands from {}",
+        log.commands.len(),
+        settings.path.display()
+    );
+    lobby.setup = log.setup.clone();
+    commands.insert_resource(Replay::from(log));
+    ev_lobby_command.send(LobbyCommand::Start);
+}
+
+/// Gives the next logged command to its actor once the turn it was logged in
+/// comes round and the previous one was carried out. Commands are added to
+/// the actor's plan, as the players gave them, and journeys are found again
+/// from where the hero stands. Runs before the actions plugin looks for new
+/// actions, so it picks the command up the same frame.
+pub fn feed_replay(
+    mut commands: Commands,
+    mut replay: ResMut<Replay>,
+    queue: Res<ActorQueue>,
+    turn_manager: Res<TurnManager>,
+    state: Res<State<GameplayState>>,
+    mut actors: ReplayActors,
+    mut ev_history_command: EventWriter<HistoryCommand>,
+) {
+    let Some(next) = replay.commands.front() else {
+        return;
+    };
+    if !queue.0.is_empty()
+        || state.get().player() != Some(next.player)
+        || turn_manager.current_turn != next.turn
+    {
+        return;
+    }
+    let Some(command) = replay.commands.pop_front() else {
+        return;
+    };
+
+    let history_command = match command.action {
+        LoggedAction::Undo => Some(HistoryCommand::Undo),
+        LoggedAction::Redo => Some(HistoryCommand::Redo),
+        _ => None,
+    };
+    if let Some(history_command) = history_command {
+        ev_history_command.send(history_command);
+        return;
+    }
+
+    match command.action {
+        LoggedAction::Cancel { hero: id } => {
+            if let Some(mut actor) = actors.hero(id).and_then(|hero| actors.actor_mut(hero)) {
+                actor.0.clear();
+            }
+            return;
+        }
+        LoggedAction::Journey { hero: id, to } => {
+            if let Some(hero) = actors.hero(id) {
+                match actors.journey(hero, to) {
+                    Some(journey) => commands.entity(hero).insert(journey),
+                    None => commands.entity(hero).remove::<Journey>(),
+                };
+            }
+            return;
+        }
+        LoggedAction::EndJourney { hero: id } => {
+            if let Some(hero) = actors.hero(id) {
+                commands.entity(hero).remove::<Journey>();
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    let player_actor = actors.player(command.player);
+    let Some((actor, action)) =
+        replayed_action(command.action, command.player, player_actor, |id| {
+            actors.hero(id)
+        })
+    else {
+        error!("Replay stopped, {command:?} names a hero that is gone");
+        commands.remove_resource::<Replay>();
+        return;
+    };
+    if let Some(mut actor) = actors.actor_mut(actor) {
+        actor.plan(action, true);
+    }
+}
+
+/// Compares the game with the one the log was written in, once the last
+/// command played out.
+pub fn finish_replay(
+    mut commands: Commands,
+    mut replay: ResMut<Replay>,
+    queue: Res<ActorQueue>,
+    state: Res<State<GameplayState>>,
+    turn_manager: Res<TurnManager>,
+    saver: GameSaver,
+) {
+    if !replay.commands.is_empty() {
+        return;
+    }
+    if !queue.0.is_empty() || state.is_changed() || turn_manager.is_changed() {
+        replay.idle_frames = 0;
+        return;
+    }
+    replay.idle_frames += 1;
+    if replay.idle_frames < SETTLE_FRAMES {
+        return;
+    }
+
+    commands.remove_resource::<Replay>();
+    let hash = world_hash(&saver.save());
+    match replay.final_hash {
+        Some(expected) if expected == hash => info!("Replay finished in the logged game"),
+        Some(expected) => error!(
+            "Replay finished in a different game, its hash is {hash:016x} instead of {expected:016x}"
+        ),
+        None => info!("Replay finished, the log has no hash to compare with"),
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use common::{
+    map::{components::Tile, rivers::Rivers},
+    protocol::{HeroId, PlayerId},
+    replay::LoggedAction,
+};
+use hexx::Hex;
+
+use crate::{
+    actions::{
+        combat::AttackAction,
+        moves::MoveAction,
+        turns::{EndTurnAction, WaitAction},
+        Action,
+    },
+    core_gameplay::components::{Actor, Owner, Player},
+    map::{resources::HexGrid, utils::collect_tiles},
+    player::{
+        components::{HeroIndex, Journey, MaxMovementPoints, MovementPoints},
+        utils::{hero_way, order_leg},
+    },
+};
+
+/// The actors logged commands are given to, found by the ids in the log, and
+/// what finding the way of logged journeys takes.
+#[derive(SystemParam)]
+pub struct ReplayActors<'w, 's> {
+    heroes: Query<'w, 's, (Entity, &'static HeroIndex)>,
+    players: Query<'w, 's, (Entity, &'static Owner), With<Player>>,
+    actors: Query<'w, 's, &'static mut Actor>,
+    travellers: Query<
+        'w,
+        's,
+        (
+            &'static Transform,
+            &'static MovementPoints,
+            &'static MaxMovementPoints,
+        ),
+    >,
+    grid: Res<'w, HexGrid>,
+    rivers: Res<'w, Rivers>,
+    tiles: Query<'w, 's, &'static Tile>,
+}
+
+impl ReplayActors<'_, '_> {
+    pub fn hero(&self, id: HeroId) -> Option<Entity> {
+        self.heroes
+            .iter()
+            .find(|(_, index)| index.0 == id)
+            .map(|(hero, _)| hero)
+    }
+
+    /// The actor ending the player's turns.
+    pub fn player(&self, id: PlayerId) -> Option<Entity> {
+        self.players
+            .iter()
+            .find(|(_, owner)| owner.0 == id)
+            .map(|(actor, _)| actor)
+    }
+
+    pub fn actor_mut(&mut self, entity: Entity) -> Option<Mut<'_, Actor>> {
+        self.actors.get_mut(entity).ok()
+    }
+
+    /// The journey `hero` goes on when it is ordered towards `goal` from
+    /// where it stands, found the way the order found it.
+    pub fn journey(&self, hero: Entity, goal: Hex) -> Option<Journey> {
+        let (transform, points, max_points) = self.travellers.get(hero).ok()?;
+        let start = self
+            .grid
+            .world_pos_to_hex(Vec2::new(transform.translation.x, transform.translation.z));
+        let tiles = collect_tiles(&self.grid, &self.tiles);
+        let way = hero_way(&tiles, &self.grid, &self.rivers, start, goal, max_points.0)?;
+
+        order_leg(
+            &tiles,
+            &self.grid,
+            &self.rivers,
+            start,
+            &way,
+            points.0,
+            max_points.0,
+        )
+        .1
+    }
+}
+
+/// The action a logged command stands for, and the actor to carry it out.
+/// Heroes are found by `hero`, `player_actor` ends the turn. Taking back,
+/// carrying out again, cancelling plans and journey orders aren't actions, so
+/// they have none.
+pub fn replayed_action(
+    action: LoggedAction,
+    player: PlayerId,
+    player_actor: Option<Entity>,
+    hero: impl Fn(HeroId) -> Option<Entity>,
+) -> Option<(Entity, Box<dyn Action>)> {
+    let replayed: (Entity, Box<dyn Action>) = match action {
+        LoggedAction::Move { hero: id, to } => {
+            let hero = hero(id)?;
+            (hero, Box::new(MoveAction { hero, to }))
+        }
+        LoggedAction::Attack { attacker, target } => {
+            let (attacker, target) = (hero(attacker)?, hero(target)?);
+            (attacker, Box::new(AttackAction { attacker, target }))
+        }
+        LoggedAction::Wait { hero: id } => {
+            let hero = hero(id)?;
+            (hero, Box::new(WaitAction { hero }))
+        }
+        LoggedAction::EndTurn => (player_actor?, Box::new(EndTurnAction { player })),
+        LoggedAction::Cancel { .. }
+        | LoggedAction::Journey { .. }
+        | LoggedAction::EndJourney { .. }
+        | LoggedAction::Undo
+        | LoggedAction::Redo => return None,
+    };
+
+    Some(replayed)
+}
@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+use resources::SaveSettings;
+use systems::{load_game, save_game};
+
+use crate::core_gameplay::states::AppState;
+
+pub mod resources;
+mod systems;
+pub mod utils;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SaveSettings>().add_systems(
+            Update,
+            (save_game, load_game).run_if(in_state(AppState::InGame)),
+        );
+    }
+}
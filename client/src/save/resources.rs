@@ -0,0 +1,17 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+#[derive(Debug, Resource)]
+pub struct SaveSettings {
+    /// File the quicksave and quickload keys use.
+    pub path: PathBuf,
+}
+
+impl Default for SaveSettings {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("saves/quicksave.ron"),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use common::save::SaveGame;
+
+use super::{
+    resources::SaveSettings,
+    utils::{GameLoader, GameSaver},
+};
+
+pub fn save_game(keys: Res<ButtonInput<KeyCode>>, settings: Res<SaveSettings>, saver: GameSaver) {
+    if !keys.just_pressed(KeyCode::F5) {
+        return;
+    }
+
+    let save = saver.save();
+    match save.write(&settings.path) {
+        Ok(()) => info!("Saved game to {}", settings.path.display()),
+        Err(error) => error!("Saving to {} failed: {error}", settings.path.display()),
+    }
+}
+
+pub fn load_game(
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<SaveSettings>,
+    mut loader: GameLoader,
+) {
+    if !keys.just_pressed(KeyCode::F9) {
+        return;
+    }
+
+    let save = match SaveGame::read(&settings.path) {
+        Ok(save) => save,
+        Err(error) => {
+            error!("Loading {} failed: {error}", settings.path.display());
+            return;
+        }
+    };
+    loader.load(&save);
+
+    info!(
+        "Loaded game from {}, turn {}",
+        settings.path.display(),
+        save.turn.current_turn
+    );
+}
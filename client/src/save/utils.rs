@@ -0,0 +1,450 @@
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use common::{
+    map::{components::Tile, rivers::Rivers},
+    protocol::{HeroId, PlayerId},
+    replay::LoggedAction,
+    save::{
+        SaveGame, SavedAction, SavedGameplayState, SavedHealth, SavedHero, SavedJourney, SavedMap,
+        SavedPlayer, SavedTurn, SavedUnit, SavedUnitType,
+    },
+};
+
+use crate::{
+    actions::resources::{ActionHistory, ActorQueue},
+    core_gameplay::{
+        components::{ActionPoints, Actor, Owner},
+        resources::{PlayerInfo, Players, TurnManager},
+        states::GameplayState,
+    },
+    map::{
+        components::{Cross, RiverSegment},
+        resources::{HexGrid, MapSettings},
+        utils::{collect_tiles, spawn_rivers, spawn_tiles},
+    },
+    player::{
+        components::{
+            AttackPoints, DefensePoints, Experience, HasMoved, Health, Hero, HeroIndex,
+            HeroMaxUnits, HeroUnits, Journey, Level, MovePathPreview, MovementPoints, Range, Unit,
+            UnitType,
+        },
+        utils::{spawn_hero, spawn_unit},
+    },
+    replay::{resources::CommandRecorder, utils::replayed_action},
+    simultaneous::resources::PendingOrders,
+};
+
+/// What a hero saves of itself.
+type SavedHeroData = (
+    Entity,
+    &'static Owner,
+    &'static Transform,
+    &'static Experience,
+    &'static Level,
+    &'static Health,
+    &'static MovementPoints,
+    Option<&'static HasMoved>,
+    &'static ActionPoints,
+    Option<&'static Journey>,
+    &'static Actor,
+    &'static HeroMaxUnits,
+    &'static HeroUnits,
+);
+
+/// What a unit saves of itself. Stats it doesn't have are left out.
+type SavedUnitData = (
+    &'static UnitType,
+    Option<&'static Health>,
+    Option<&'static AttackPoints>,
+    Option<&'static DefensePoints>,
+    Option<&'static Range>,
+    Option<&'static MovementPoints>,
+);
+
+/// Everything that goes into a save.
+#[derive(SystemParam)]
+pub struct GameSaver<'w, 's> {
+    map_settings: Res<'w, MapSettings>,
+    grid: Res<'w, HexGrid>,
+    rivers: Res<'w, Rivers>,
+    tiles: Query<'w, 's, &'static Tile>,
+    heroes: Query<'w, 's, SavedHeroData, With<Hero>>,
+    units: Query<'w, 's, SavedUnitData, With<Unit>>,
+    turn_manager: Res<'w, TurnManager>,
+    players: Res<'w, Players>,
+    state: Res<'w, State<GameplayState>>,
+}
+
+impl GameSaver<'_, '_> {
+    /// The game as it is now. Heroes are sorted by owner and hex, so the same
+    /// game always makes the same save.
+    pub fn save(&self) -> SaveGame {
+        let grid = &self.grid;
+        let tiles = collect_tiles(grid, &self.tiles);
+
+        let mut heroes: Vec<(Entity, &Actor, SavedHero)> = self
+            .heroes
+            .iter()
+            .map(
+                |(
+                    entity,
+                    owner,
+                    transform,
+                    experience,
+                    level,
+                    health,
+                    movement_points,
+                    has_moved,
+                    action_points,
+                    journey,
+                    actor,
+                    max_units,
+                    slots,
+                )| {
+                    let hero = SavedHero {
+                        owner: owner.0 .0,
+                        hex: grid.world_pos_to_hex(Vec2::new(
+                            transform.translation.x,
+                            transform.translation.z,
+                        )),
+                        experience: experience.0,
+                        level: level.0,
+                        health: SavedHealth {
+                            current: health.current,
+                            max: health.max,
+                        },
+                        movement_points: movement_points.0,
+                        has_moved: has_moved.is_some(),
+                        action_points: action_points.0,
+                        journey: journey.map(|journey| SavedJourney {
+                            to: journey.to,
+                            path: journey.path.clone(),
+                        }),
+                        // filled in once every hero has its place
+                        plan: Vec::new(),
+                        max_units: max_units.0,
+                        units: slots
+                            .0
+                            .iter()
+                            .map(|slot| {
+                                let (unit_type, health, attack, defense, range, movement_points) =
+                                    self.units.get((*slot)?).ok()?;
+
+                                Some(SavedUnit {
+                                    unit_type: unit_type.into(),
+                                    health: health.map(|health| SavedHealth {
+                                        current: health.current,
+                                        max: health.max,
+                                    }),
+                                    attack: attack.map(|attack| attack.0),
+                                    defense: defense.map(|defense| defense.0),
+                                    range: range.map(|range| range.0),
+                                    movement_points: movement_points.map(|points| points.0),
+                                })
+                            })
+                            .collect(),
+                    };
+
+                    (entity, actor, hero)
+                },
+            )
+            .collect();
+        heroes.sort_by_key(|(_, _, hero)| (hero.owner, hero.hex.x, hero.hex.y));
+
+        let indices: HashMap<Entity, HeroId> = heroes
+            .iter()
+            .enumerate()
+            .map(|(index, &(entity, _, _))| (entity, HeroId(index as u32)))
+            .collect();
+        let hero_index = |hero| indices.get(&hero).copied();
+        let heroes = heroes
+            .into_iter()
+            .map(|(_, actor, mut hero)| {
+                hero.plan = actor
+                    .0
+                    .iter()
+                    .filter_map(|action| saved_action(action.logged(&hero_index)?))
+                    .collect();
+                hero
+            })
+            .collect();
+
+        SaveGame::new(
+            SavedMap::new(self.map_settings.generator.clone(), &tiles, &self.rivers),
+            self.turn_manager
+                .turn_order
+                .iter()
+                .filter_map(|&id| self.players.get(id).map(Into::into))
+                .collect(),
+            heroes,
+            SavedTurn {
+                current_turn: self.turn_manager.current_turn,
+                max_turns: self.turn_manager.max_turns,
+                mode: (&self.turn_manager.mode).into(),
+                state: self.state.get().into(),
+            },
+        )
+    }
+}
+
+/// What is left of the map and the paths shown on it besides the tiles.
+type Leftovers = Or<(With<RiverSegment>, With<Cross>, With<MovePathPreview>)>;
+
+/// Everything a save replaces when it is loaded.
+#[derive(SystemParam)]
+pub struct GameLoader<'w, 's> {
+    commands: Commands<'w, 's>,
+    map_settings: ResMut<'w, MapSettings>,
+    grid: ResMut<'w, HexGrid>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+    turn_manager: ResMut<'w, TurnManager>,
+    players: ResMut<'w, Players>,
+    next_state: ResMut<'w, NextState<GameplayState>>,
+    recorder: ResMut<'w, CommandRecorder>,
+    history: ResMut<'w, ActionHistory>,
+    queue: ResMut<'w, ActorQueue>,
+    orders: ResMut<'w, PendingOrders>,
+    heroes: Query<'w, 's, (Entity, &'static HeroUnits), With<Hero>>,
+    leftovers: Query<'w, 's, Entity, Leftovers>,
+}
+
+impl GameLoader<'_, '_> {
+    /// Replaces the map, the players and their heroes with those of `save`,
+    /// heroes with what they planned. What was done before can't be taken
+    /// back.
+    pub fn load(&mut self, save: &SaveGame) {
+        self.history.clear();
+        self.queue.0.clear();
+        *self.orders = PendingOrders::default();
+
+        self.grid.entities.values().for_each(|&entity| {
+            self.commands.entity(entity).despawn_recursive();
+        });
+        for (hero, slots) in self.heroes.iter() {
+            slots.0.iter().flatten().for_each(|&unit| {
+                self.commands.entity(unit).despawn_recursive();
+            });
+            self.commands.entity(hero).despawn_recursive();
+        }
+        self.leftovers.iter().for_each(|entity| {
+            self.commands.entity(entity).despawn_recursive();
+        });
+
+        self.map_settings.generator = save.map.generator.clone();
+        self.grid.entities = spawn_tiles(
+            &save.map.tiles(),
+            self.map_settings.hex_size,
+            &mut self.commands,
+            &mut self.meshes,
+            &mut self.materials,
+        );
+        self.grid.shape = save.map.generator.shape;
+        self.grid.reachable_entities.clear();
+
+        let rivers = save.map.rivers();
+        spawn_rivers(
+            &rivers,
+            &self.grid,
+            &mut self.commands,
+            &mut self.meshes,
+            &mut self.materials,
+        );
+        self.commands.insert_resource(rivers);
+
+        *self.players = Players(save.players.iter().map(Into::into).collect());
+
+        let mut entities = Vec::with_capacity(save.heroes.len());
+        for (index, hero) in save.heroes.iter().enumerate() {
+            let units: Vec<Option<Entity>> = hero
+                .units
+                .iter()
+                .map(|slot| {
+                    let unit = slot.as_ref()?;
+                    let entity = spawn_unit(unit.unit_type.into(), &mut self.commands);
+                    let mut entity_commands = self.commands.entity(entity);
+
+                    if let Some(health) = unit.health {
+                        entity_commands.insert(Health {
+                            current: health.current,
+                            max: health.max,
+                        });
+                    }
+                    if let Some(attack) = unit.attack {
+                        entity_commands.insert(AttackPoints(attack));
+                    }
+                    if let Some(defense) = unit.defense {
+                        entity_commands.insert(DefensePoints(defense));
+                    }
+                    if let Some(range) = unit.range {
+                        entity_commands.insert(Range(range));
+                    }
+                    if let Some(movement_points) = unit.movement_points {
+                        entity_commands.insert(MovementPoints(movement_points));
+                    }
+
+                    Some(entity)
+                })
+                .collect();
+
+            let owner = PlayerId(hero.owner);
+            let entity = spawn_hero(
+                hero.hex,
+                owner,
+                self.players.color(owner),
+                &self.grid,
+                &mut self.commands,
+                &mut self.meshes,
+                &mut self.materials,
+            );
+            let mut entity_commands = self.commands.entity(entity);
+            entity_commands.insert((
+                Experience(hero.experience),
+                Level(hero.level),
+                Health {
+                    current: hero.health.current,
+                    max: hero.health.max,
+                },
+                MovementPoints(hero.movement_points),
+                ActionPoints(hero.action_points),
+                HeroMaxUnits(hero.max_units),
+                HeroUnits(units),
+                HeroIndex(HeroId(index as u32)),
+            ));
+            if hero.has_moved {
+                entity_commands.insert(HasMoved);
+            }
+            if let Some(journey) = &hero.journey {
+                entity_commands.insert(Journey {
+                    to: journey.to,
+                    path: journey.path.clone(),
+                });
+            }
+            entities.push(entity);
+        }
+
+        // plans name other heroes, so they wait until every hero is spawned
+        let hero_entity = |id: HeroId| entities.get(id.0 as usize).copied();
+        for (index, (hero, &entity)) in save.heroes.iter().zip(&entities).enumerate() {
+            let plan = hero
+                .plan
+                .iter()
+                .filter_map(|&action| {
+                    let action = logged_action(action, HeroId(index as u32));
+                    replayed_action(action, PlayerId(hero.owner), None, hero_entity)
+                })
+                .map(|(_, action)| action)
+                .collect();
+            self.commands.entity(entity).insert(Actor(plan));
+        }
+
+        let state = GameplayState::from(save.turn.state);
+        self.turn_manager.current_turn = save.turn.current_turn;
+        self.turn_manager.max_turns = save.turn.max_turns;
+        self.turn_manager.mode = save.turn.mode.into();
+        self.turn_manager.current_state = state.clone();
+        self.turn_manager.turn_order = self.players.ids();
+        self.next_state.set(state);
+        // the seed alone no longer leads to this game
+        self.recorder.log = None;
+    }
+}
+
+/// A logged action as a hero plans it, if heroes plan it at all.
+fn saved_action(action: LoggedAction) -> Option<SavedAction> {
+    match action {
+        LoggedAction::Move { to, .. } => Some(SavedAction::Move { to }),
+        LoggedAction::Attack { target, .. } => Some(SavedAction::Attack { target: target.0 }),
+        LoggedAction::Wait { .. } => Some(SavedAction::Wait),
+        _ => None,
+    }
+}
+
+/// A planned action of `hero` as it goes into the command log.
+fn logged_action(action: SavedAction, hero: HeroId) -> LoggedAction {
+    match action {
+        SavedAction::Move { to } => LoggedAction::Move { hero, to },
+        SavedAction::Attack { target } => LoggedAction::Attack {
+            attacker: hero,
+            target: HeroId(target),
+        },
+        SavedAction::Wait => LoggedAction::Wait { hero },
+    }
+}
+
+impl From<&GameplayState> for SavedGameplayState {
+    fn from(state: &GameplayState) -> Self {
+        match state {
+            GameplayState::PlayerTurn(player) => SavedGameplayState::PlayerTurn(player.0),
+            GameplayState::TurnTransition => SavedGameplayState::TurnTransition,
+            GameplayState::GameOver => SavedGameplayState::GameOver,
+        }
+    }
+}
+
+impl From<SavedGameplayState> for GameplayState {
+    fn from(state: SavedGameplayState) -> Self {
+        match state {
+            SavedGameplayState::PlayerTurn(player) => GameplayState::PlayerTurn(PlayerId(player)),
+            SavedGameplayState::TurnTransition => GameplayState::TurnTransition,
+            SavedGameplayState::GameOver => GameplayState::GameOver,
+        }
+    }
+}
+
+impl From<&PlayerInfo> for SavedPlayer {
+    fn from(player: &PlayerInfo) -> Self {
+        Self {
+            id: player.id.0,
+            name: player.name.clone(),
+            color: [player.color.r(), player.color.g(), player.color.b()],
+            team: player.team,
+            kind: (&player.kind).into(),
+        }
+    }
+}
+
+impl From<&SavedPlayer> for PlayerInfo {
+    fn from(player: &SavedPlayer) -> Self {
+        let [r, g, b] = player.color;
+
+        Self {
+            id: PlayerId(player.id),
+            name: player.name.clone(),
+            color: Color::rgb(r, g, b),
+            team: player.team,
+            kind: player.kind.into(),
+        }
+    }
+}
+
+impl From<&UnitType> for SavedUnitType {
+    fn from(unit_type: &UnitType) -> Self {
+        match unit_type {
+            UnitType::Melee => SavedUnitType::Melee,
+            UnitType::Ranged => SavedUnitType::Ranged,
+            UnitType::Support => SavedUnitType::Support,
+            UnitType::Naval => SavedUnitType::Naval,
+            UnitType::Air => SavedUnitType::Air,
+            UnitType::Siege => SavedUnitType::Siege,
+            UnitType::Cavalry => SavedUnitType::Cavalry,
+            UnitType::Artillery => SavedUnitType::Artillery,
+            UnitType::Armor => SavedUnitType::Armor,
+        }
+    }
+}
+
+impl From<SavedUnitType> for UnitType {
+    fn from(unit_type: SavedUnitType) -> Self {
+        match unit_type {
+            SavedUnitType::Melee => UnitType::Melee,
+            SavedUnitType::Ranged => UnitType::Ranged,
+            SavedUnitType::Support => UnitType::Support,
+            SavedUnitType::Naval => UnitType::Naval,
+            SavedUnitType::Air => UnitType::Air,
+            SavedUnitType::Siege => UnitType::Siege,
+            SavedUnitType::Cavalry => UnitType::Cavalry,
+            SavedUnitType::Artillery => UnitType::Artillery,
+            SavedUnitType::Armor => UnitType::Armor,
+        }
+    }
+}
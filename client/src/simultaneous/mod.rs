@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+use resources::PendingOrders;
+use systems::{finish_resolution, plan_orders, resolve_orders};
+
+use crate::{
+    core_gameplay::{
+        states::{AppState, GameplayState},
+        utils::simultaneous_turns,
+    },
+    network::resources::ServerConnection,
+};
+
+pub mod resources;
+mod systems;
+pub mod utils;
+
+/// Turns where every player gives their orders first. Once the last player
+/// ends their turn the orders are carried out together, in an order that
+/// only depends on the turn, before the next turn starts.
+pub struct SimultaneousPlugin;
+
+impl Plugin for SimultaneousPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingOrders>()
+            .add_systems(
+                OnEnter(GameplayState::TurnTransition),
+                resolve_orders.run_if(simultaneous_turns),
+            )
+            .add_systems(
+                Update,
+                (plan_orders, finish_resolution)
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(simultaneous_turns)
+                    .run_if(not(resource_exists::<ServerConnection>)),
+            );
+    }
+}
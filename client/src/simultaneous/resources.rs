@@ -0,0 +1,12 @@
+use bevy::{prelude::*, utils::HashMap};
+
+#[derive(Default, Resource)]
+pub struct PendingOrders {
+    /// Heroes with orders, in the order they were first given. Heroes with
+    /// orders left over from earlier turns keep their place.
+    pub heroes: Vec<Entity>,
+    /// How many of each hero's orders are in the command log.
+    pub logged: HashMap<Entity, usize>,
+    /// Whether the orders are being carried out.
+    pub resolving: bool,
+}
@@ -0,0 +1,145 @@
+use bevy::{prelude::*, utils::HashMap};
+use common::replay::LoggedAction;
+
+use crate::{
+    actions::{
+        events::{ActionsCompleteEvent, TickEvent},
+        resources::ActorQueue,
+    },
+    core_gameplay::{
+        components::{Actor, Owner},
+        resources::TurnManager,
+        utils::TurnPasser,
+    },
+    player::components::{Hero, HeroIndex},
+    replay::resources::CommandRecorder,
+};
+
+use super::{resources::PendingOrders, utils::resolution_rank};
+
+/// Heroes whose plans changed.
+type ChangedPlans = (Changed<Actor>, With<Hero>);
+
+/// Keeps the actions heroes are given as orders until the turn ends. A hero
+/// given a new order keeps its place. Orders are logged as they are given,
+/// carrying them out again leads to the same outcome.
+pub fn plan_orders(
+    mut orders: ResMut<PendingOrders>,
+    mut recorder: ResMut<CommandRecorder>,
+    turn_manager: Res<TurnManager>,
+    heroes: Query<(Entity, &Actor, &Owner), ChangedPlans>,
+    indices: Query<&HeroIndex>,
+) {
+    // carrying the orders out changes the plans as well
+    if turn_manager.current_state.player().is_none() {
+        return;
+    }
+    let hero_index = |hero| indices.get(hero).ok().map(|index| index.0);
+    let turn = turn_manager.current_turn;
+
+    for (hero, actor, owner) in heroes.iter() {
+        // a plan that didn't grow was replaced, one without logged orders
+        // has nothing to cancel
+        let logged = orders.logged.get(&hero).copied().unwrap_or(0);
+        let new_orders = if actor.0.len() > logged {
+            logged
+        } else {
+            if let (true, Some(index)) = (logged > 0, hero_index(hero)) {
+                recorder.record(turn, owner.0, LoggedAction::Cancel { hero: index });
+            }
+            0
+        };
+        for action in actor.0.iter().skip(new_orders) {
+            if let Some(logged) = action.logged(&hero_index) {
+                recorder.record(turn, owner.0, logged);
+            }
+        }
+        orders.logged.insert(hero, actor.0.len());
+
+        if !actor.0.is_empty() && !orders.heroes.contains(&hero) {
+            orders.heroes.push(hero);
+        }
+    }
+}
+
+/// Queues this turn's orders and starts carrying them out. Orders go by the
+/// rank of their player, then in the order they were given. Of several heroes
+/// heading for the same hex only the first gets to go, the others carry out
+/// their orders up to that move.
+pub fn resolve_orders(
+    mut orders: ResMut<PendingOrders>,
+    mut queue: ResMut<ActorQueue>,
+    turn_manager: Res<TurnManager>,
+    mut actors: Query<(&mut Actor, &Owner)>,
+    mut ev_tick: EventWriter<TickEvent>,
+) {
+    let mut ranked: Vec<(usize, usize, Entity)> = orders
+        .heroes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &hero)| {
+            let (actor, owner) = actors.get(hero).ok()?;
+            if actor.0.is_empty() {
+                return None;
+            }
+            let rank =
+                resolution_rank(owner.0, &turn_manager.turn_order, turn_manager.current_turn);
+            Some((rank, index, hero))
+        })
+        .collect();
+    ranked.sort();
+
+    let mut claimed = HashMap::new();
+    for (_, _, hero) in ranked {
+        let Ok((mut actor, _)) = actors.get_mut(hero) else {
+            continue;
+        };
+        // the plan stops short of the first hex another hero claimed
+        let conflict = actor.0.iter().position(|action| {
+            action
+                .target()
+                .is_some_and(|target| *claimed.entry(target).or_insert(hero) != hero)
+        });
+        if let Some(conflict) = conflict {
+            actor.0.truncate(conflict);
+        }
+
+        if !actor.0.is_empty() {
+            queue.0.push_back(hero);
+        }
+    }
+
+    orders.resolving = true;
+    ev_tick.send(TickEvent);
+}
+
+/// Starts the next turn once every order was carried out.
+pub fn finish_resolution(
+    mut orders: ResMut<PendingOrders>,
+    queue: Res<ActorQueue>,
+    mut ev_actions_complete: EventReader<ActionsCompleteEvent>,
+    plans: Query<&Actor>,
+    mut turns: TurnPasser,
+) {
+    // the queue may have run dry before the orders were queued
+    if ev_actions_complete.read().count() == 0 || !orders.resolving || !queue.0.is_empty() {
+        return;
+    }
+    orders.resolving = false;
+
+    // what heroes had no action points left for waits for the next turn
+    orders
+        .heroes
+        .retain(|&hero| plans.get(hero).is_ok_and(|plan| !plan.0.is_empty()));
+    orders.logged = orders
+        .heroes
+        .iter()
+        .filter_map(|&hero| Some((hero, plans.get(hero).ok()?.0.len())))
+        .collect();
+
+    let live = turns.live_players();
+    let state = turns
+        .turn_manager
+        .end_transition(|player| live.contains(&player));
+    turns.enter(state);
+}
@@ -0,0 +1,14 @@
+use common::protocol::PlayerId;
+
+/// Position of `player` among those whose orders are carried out in `turn`.
+/// The first player moves one place back every turn, so nobody always wins
+/// a contested hex.
+pub fn resolution_rank(player: PlayerId, turn_order: &[PlayerId], turn: u32) -> usize {
+    let count = turn_order.len();
+    let Some(index) = turn_order.iter().position(|&other| other == player) else {
+        return count;
+    };
+    let first = turn.saturating_sub(1) as usize % count;
+
+    (index + count - first) % count
+}
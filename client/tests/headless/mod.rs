@@ -0,0 +1,231 @@
+// every test uses only some of these
+#![allow(dead_code)]
+
+use bevy::{input::InputPlugin, prelude::*, utils::HashMap};
+use client::{
+    actions::{events::HistoryCommand, turns::EndTurnAction, Action, ActionsPlugin},
+    core_gameplay::{
+        components::{Actor, Owner, Player},
+        states::AppState,
+        CoreGameplayPlugin,
+    },
+    editor::states::EditorState,
+    lobby::{events::LobbyCommand, resources::Lobby, LobbyPlugin},
+    map::{events::TileSelectEvent, resources::HexGrid, MapPlugin},
+    player::{
+        components::{Hero, MaxMovementPoints, MoveTarget, MovementPoints, SelectedHero},
+        utils::{hero_way, order_leg},
+        PlayerPlugin,
+    },
+    replay::ReplayPlugin,
+    save::SavePlugin,
+    simultaneous::SimultaneousPlugin,
+};
+use common::{
+    map::{
+        components::Tile,
+        generation::MapType,
+        pathfinding::{find_path, turn_stops},
+        rivers::Rivers,
+    },
+    protocol::PlayerId,
+    setup::{GameSetup, MapSize, SlotKind, TurnMode},
+};
+use hexx::Hex;
+
+/// Frames a game gets to play out what it was given.
+pub const SETTLE_FRAMES: usize = 10;
+
+/// The game without anything that needs a window.
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), InputPlugin))
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>()
+        .init_asset::<Image>()
+        .init_state::<EditorState>()
+        .add_plugins((
+            MapPlugin,
+            PlayerPlugin,
+            CoreGameplayPlugin,
+            LobbyPlugin,
+            ActionsPlugin,
+            SimultaneousPlugin,
+            ReplayPlugin,
+            SavePlugin,
+        ));
+    app
+}
+
+/// Two human players on a small seeded map.
+pub fn setup(turn_mode: TurnMode) -> GameSetup {
+    GameSetup {
+        map_size: MapSize::Tiny,
+        map_type: MapType::Pangaea,
+        seed: 42,
+        turn_mode,
+        slots: vec![SlotKind::Human, SlotKind::Human],
+        ..Default::default()
+    }
+}
+
+pub fn settle(app: &mut App) {
+    for _ in 0..SETTLE_FRAMES {
+        app.update();
+    }
+}
+
+/// Starts the game from the lobby, as the start button does.
+pub fn start(app: &mut App, setup: GameSetup) {
+    app.world.resource_mut::<Lobby>().setup = setup;
+    app.world.send_event(LobbyCommand::Start);
+    settle(app);
+    assert_eq!(
+        app.world.resource::<State<AppState>>().get(),
+        &AppState::InGame
+    );
+}
+
+pub fn hero_of(app: &mut App, player: u32) -> Entity {
+    let mut heroes = app
+        .world
+        .query_filtered::<(Entity, &Owner), (With<Hero>, With<Actor>)>();
+    heroes
+        .iter(&app.world)
+        .find(|(_, owner)| owner.0 == PlayerId(player))
+        .map(|(hero, _)| hero)
+        .expect("every player starts with a hero")
+}
+
+pub fn player_actor(app: &mut App, player: u32) -> Entity {
+    let mut players = app
+        .world
+        .query_filtered::<(Entity, &Owner), (With<Player>, With<Actor>)>();
+    players
+        .iter(&app.world)
+        .find(|(_, owner)| owner.0 == PlayerId(player))
+        .map(|(actor, _)| actor)
+        .expect("every player has an actor")
+}
+
+/// Tiles of the map keyed by hex.
+pub fn tiles(app: &App) -> HashMap<Hex, Tile> {
+    app.world
+        .resource::<HexGrid>()
+        .entities
+        .iter()
+        .filter_map(|(&hex, &entity)| Some((hex, app.world.get::<Tile>(entity)?.clone())))
+        .collect()
+}
+
+/// Hex the hero stands on.
+pub fn hex_of(app: &App, hero: Entity) -> Hex {
+    let transform = app.world.get::<Transform>(hero).unwrap();
+    app.world
+        .resource::<HexGrid>()
+        .world_pos_to_hex(Vec2::new(transform.translation.x, transform.translation.z))
+}
+
+/// A hex next to `from` that `points` reach.
+pub fn reachable_neighbor(app: &App, from: Hex, points: u32) -> Hex {
+    let tiles = tiles(app);
+    let grid = app.world.resource::<HexGrid>();
+    let rivers = app.world.resource::<Rivers>();
+
+    from.all_neighbors()
+        .into_iter()
+        .find(|&to| {
+            find_path(&tiles, &grid.shape, rivers, from, to)
+                .is_some_and(|(path, cost)| !path.is_empty() && cost <= points)
+        })
+        .expect("the hero can go somewhere")
+}
+
+/// Adds `action` to the actor's plan and lets it play out.
+pub fn plan(app: &mut App, actor: Entity, action: impl Action + 'static) {
+    app.world
+        .get_mut::<Actor>(actor)
+        .unwrap()
+        .plan(Box::new(action), true);
+    settle(app);
+}
+
+pub fn end_turn(app: &mut App, player: u32) {
+    let actor = player_actor(app, player);
+    plan(
+        app,
+        actor,
+        EndTurnAction {
+            player: PlayerId(player),
+        },
+    );
+}
+
+pub fn history(app: &mut App, command: HistoryCommand) {
+    app.world.send_event(command);
+    settle(app);
+}
+
+/// Orders player's hero towards a hex it takes at least `turns` turns to get
+/// to, past no other hero, the way a click on the map does. Returns the hero,
+/// where it gets this turn and the whole way.
+pub fn send_on_journey(app: &mut App, player: u32, turns: usize) -> (Entity, Hex, Vec<Hex>) {
+    let hero = hero_of(app, player);
+    let tiles = tiles(app);
+    let start = hex_of(app, hero);
+    let others: Vec<Hex> = {
+        let mut heroes = app.world.query_filtered::<Entity, With<Hero>>();
+        let heroes: Vec<Entity> = heroes.iter(&app.world).collect();
+        heroes
+            .into_iter()
+            .filter(|&other| other != hero)
+            .map(|other| hex_of(app, other))
+            .collect()
+    };
+    let grid = app.world.resource::<HexGrid>();
+    let rivers = app.world.resource::<Rivers>();
+    let points = app.world.get::<MovementPoints>(hero).unwrap().0;
+    let max_points = app.world.get::<MaxMovementPoints>(hero).unwrap().0;
+
+    let (goal, to, way) = (6..16)
+        .flat_map(|range| {
+            let mut ring: Vec<Hex> = start.ring(range).collect();
+            ring.sort_by_key(|hex| (hex.x, hex.y));
+            ring
+        })
+        .find_map(|goal| {
+            let path = hero_way(&tiles, grid, rivers, start, goal, max_points)?;
+            let stops = turn_stops(
+                &tiles,
+                &grid.shape,
+                rivers,
+                start,
+                &path,
+                points,
+                max_points,
+            );
+            if stops.len() < turns || path.iter().any(|hex| others.contains(hex)) {
+                return None;
+            }
+            match order_leg(&tiles, grid, rivers, start, &path, points, max_points) {
+                (Some(to), Some(journey)) => Some((goal, to, journey.path)),
+                _ => None,
+            }
+        })
+        .expect("the hero can go far");
+
+    let entity = grid.get(goal).unwrap();
+    let tile = tiles[&goal].clone();
+    app.world
+        .entity_mut(hero)
+        .insert((SelectedHero(hero), MoveTarget(goal)));
+    app.world.send_event(TileSelectEvent {
+        entity,
+        tile,
+        hex: goal,
+    });
+    settle(app);
+    app.world.entity_mut(hero).remove::<SelectedHero>();
+
+    (hero, to, way)
+}
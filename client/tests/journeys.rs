@@ -0,0 +1,175 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use client::{
+    actions::events::HistoryCommand,
+    map::resources::HexGrid,
+    player::{
+        components::{Journey, MaxMovementPoints, SelectedHero},
+        utils::next_leg,
+    },
+};
+use common::{map::rivers::Rivers, setup::TurnMode};
+use headless::{
+    end_turn, headless_app, hero_of, hex_of, history, send_on_journey, setup, start, tiles,
+};
+use hexx::Hex;
+
+mod headless;
+
+/// Where the hero gets in a turn of its own along what is left of its
+/// journey, and what is left after that.
+fn next_stop(app: &App, hero: Entity) -> (Hex, Vec<Hex>) {
+    let tiles = tiles(app);
+    let grid = app.world.resource::<HexGrid>();
+    let rivers = app.world.resource::<Rivers>();
+    let points = app.world.get::<MaxMovementPoints>(hero).unwrap().0;
+    let path = journey(app, hero).unwrap();
+
+    let (to, _) = next_leg(
+        &tiles,
+        grid,
+        rivers,
+        hex_of(app, hero),
+        &path,
+        points,
+        points,
+    );
+    let to = to.expect("the hero gets on");
+    let crossed = path.iter().position(|&hex| hex == to).unwrap() + 1;
+    (to, path[crossed..].to_vec())
+}
+
+/// Puts player 2's hero on `hex`.
+fn block(app: &mut App, hex: Hex) {
+    let blocker = hero_of(app, 2);
+    let position = app.world.resource::<HexGrid>().layout.hex_to_world_pos(hex);
+    let mut transform = app.world.get_mut::<Transform>(blocker).unwrap();
+    transform.translation = Vec3::new(position.x, transform.translation.y, position.y);
+}
+
+fn key(app: &mut App, key_code: KeyCode, logical_key: Key, state: ButtonState) {
+    app.world.send_event(KeyboardInput {
+        key_code,
+        logical_key,
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+}
+
+fn journey(app: &App, hero: Entity) -> Option<Vec<Hex>> {
+    app.world
+        .get::<Journey>(hero)
+        .map(|journey| journey.path.clone())
+}
+
+#[test]
+fn moves_take_the_hexes_they_crossed_off_the_journey() {
+    let mut app = headless_app();
+    start(&mut app, setup(TurnMode::Sequential));
+
+    let (hero, to, way) = send_on_journey(&mut app, 1, 2);
+    let crossed = way.iter().position(|&hex| hex == to).unwrap() + 1;
+
+    assert_eq!(hex_of(&app, hero), to);
+    assert_eq!(journey(&app, hero), Some(way[crossed..].to_vec()));
+}
+
+#[test]
+fn taking_a_move_back_restores_the_journey() {
+    let mut app = headless_app();
+    start(&mut app, setup(TurnMode::Sequential));
+    let (hero, _, way) = send_on_journey(&mut app, 1, 2);
+    let walked = journey(&app, hero);
+
+    history(&mut app, HistoryCommand::Undo);
+    assert_eq!(journey(&app, hero), Some(way));
+
+    history(&mut app, HistoryCommand::Redo);
+    assert_eq!(journey(&app, hero), walked);
+}
+
+#[test]
+fn journeys_go_on_in_the_next_turn() {
+    let mut app = headless_app();
+    start(&mut app, setup(TurnMode::Sequential));
+    let (hero, _, _) = send_on_journey(&mut app, 1, 3);
+
+    end_turn(&mut app, 1);
+    let (stop, rest) = next_stop(&app, hero);
+    end_turn(&mut app, 2);
+
+    assert_eq!(hex_of(&app, hero), stop);
+    assert_eq!(journey(&app, hero), Some(rest));
+}
+
+#[test]
+fn blocked_journeys_find_another_way() {
+    let mut app = headless_app();
+    start(&mut app, setup(TurnMode::Sequential));
+    let (hero, to, _) = send_on_journey(&mut app, 1, 3);
+    let way = journey(&app, hero).unwrap();
+    let goal = *way.last().unwrap();
+    let blocked = way[0];
+    assert_ne!(blocked, goal);
+
+    end_turn(&mut app, 1);
+    block(&mut app, blocked);
+    end_turn(&mut app, 2);
+
+    let way = journey(&app, hero).expect("there is another way");
+    assert_ne!(hex_of(&app, hero), to);
+    assert!(!way.contains(&blocked));
+    assert_eq!(way.last(), Some(&goal));
+    assert_eq!(app.world.get::<Journey>(hero).unwrap().to, goal);
+}
+
+#[test]
+fn journeys_to_a_taken_hex_are_given_up() {
+    let mut app = headless_app();
+    start(&mut app, setup(TurnMode::Sequential));
+    let (hero, to, _) = send_on_journey(&mut app, 1, 3);
+    let goal = app.world.get::<Journey>(hero).unwrap().to;
+
+    end_turn(&mut app, 1);
+    block(&mut app, goal);
+    end_turn(&mut app, 2);
+
+    assert_eq!(journey(&app, hero), None);
+    assert_eq!(hex_of(&app, hero), to);
+}
+
+#[test]
+fn new_orders_give_up_the_journey() {
+    let mut app = headless_app();
+    start(&mut app, setup(TurnMode::Sequential));
+    let (hero, _, _) = send_on_journey(&mut app, 1, 2);
+    app.world.entity_mut(hero).insert(SelectedHero(hero));
+
+    // with Shift the wait comes after the journey
+    key(
+        &mut app,
+        KeyCode::ShiftLeft,
+        Key::Shift,
+        ButtonState::Pressed,
+    );
+    key(&mut app, KeyCode::Space, Key::Space, ButtonState::Pressed);
+    app.update();
+    assert!(journey(&app, hero).is_some());
+
+    key(&mut app, KeyCode::Space, Key::Space, ButtonState::Released);
+    key(
+        &mut app,
+        KeyCode::ShiftLeft,
+        Key::Shift,
+        ButtonState::Released,
+    );
+    app.update();
+    key(&mut app, KeyCode::Space, Key::Space, ButtonState::Pressed);
+    app.update();
+    assert!(journey(&app, hero).is_none());
+}
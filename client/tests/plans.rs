@@ -0,0 +1,31 @@
+use client::{
+    actions::moves::MoveAction, core_gameplay::components::Actor,
+    player::components::MaxMovementPoints,
+};
+use common::setup::TurnMode;
+use headless::{end_turn, headless_app, hero_of, hex_of, plan, reachable_neighbor, setup, start};
+
+mod headless;
+
+#[test]
+fn plans_with_two_moves_finish_in_the_next_turn() {
+    let mut app = headless_app();
+    start(&mut app, setup(TurnMode::Sequential));
+
+    let hero = hero_of(&mut app, 1);
+    let points = app.world.get::<MaxMovementPoints>(hero).unwrap().0;
+    let first = reachable_neighbor(&app, hex_of(&app, hero), points);
+    let second = reachable_neighbor(&app, first, points);
+    plan(&mut app, hero, MoveAction { hero, to: first });
+    plan(&mut app, hero, MoveAction { hero, to: second });
+
+    // a hero moves once a turn, the second move waits
+    assert_eq!(hex_of(&app, hero), first);
+    assert_eq!(app.world.get::<Actor>(hero).unwrap().0.len(), 1);
+
+    end_turn(&mut app, 1);
+    end_turn(&mut app, 2);
+
+    assert_eq!(hex_of(&app, hero), second);
+    assert!(app.world.get::<Actor>(hero).unwrap().0.is_empty());
+}
@@ -0,0 +1,159 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use client::{
+    actions::{events::HistoryCommand, moves::MoveAction},
+    core_gameplay::states::AppState,
+    player::components::{Journey, MovementPoints},
+    replay::{
+        events::StartReplay,
+        resources::{CommandRecorder, Replay, ReplaySettings},
+    },
+    save::utils::GameSaver,
+};
+use common::{
+    replay::{world_hash, CommandLog, LoggedAction},
+    setup::TurnMode,
+};
+use headless::{
+    end_turn, headless_app, hero_of, hex_of, history, plan, reachable_neighbor, send_on_journey,
+    setup, start, SETTLE_FRAMES,
+};
+
+mod headless;
+
+fn hash(app: &mut App) -> u64 {
+    app.world
+        .run_system_once(|saver: GameSaver| world_hash(&saver.save()))
+}
+
+fn move_hero(app: &mut App, player: u32) {
+    let hero = hero_of(app, player);
+    let from = hex_of(app, hero);
+    let points = app.world.get::<MovementPoints>(hero).unwrap().0;
+    let to = reachable_neighbor(app, from, points);
+    plan(app, hero, MoveAction { hero, to });
+}
+
+fn finish_log(app: &mut App) -> CommandLog {
+    let mut log = app
+        .world
+        .resource_mut::<CommandRecorder>()
+        .log
+        .take()
+        .expect("local games are logged");
+    log.final_hash = Some(hash(app));
+    log
+}
+
+/// Plays a few turns, taking a move back and carrying it out again, and
+/// returns the log with the hash of the game it ended in.
+fn play(turn_mode: TurnMode) -> CommandLog {
+    let mut app = headless_app();
+    start(&mut app, setup(turn_mode));
+
+    move_hero(&mut app, 1);
+    history(&mut app, HistoryCommand::Undo);
+    history(&mut app, HistoryCommand::Redo);
+    end_turn(&mut app, 1);
+    move_hero(&mut app, 2);
+    end_turn(&mut app, 2);
+    move_hero(&mut app, 1);
+    end_turn(&mut app, 1);
+
+    finish_log(&mut app)
+}
+
+/// Sets player 1's hero off on a journey and plays on until it went on by
+/// itself, and returns the log with the hash of the game it ended in.
+fn play_journey(turn_mode: TurnMode) -> CommandLog {
+    let mut app = headless_app();
+    start(&mut app, setup(turn_mode));
+
+    let (hero, _, _) = send_on_journey(&mut app, 1, 4);
+    end_turn(&mut app, 1);
+    move_hero(&mut app, 2);
+    end_turn(&mut app, 2);
+    end_turn(&mut app, 1);
+    end_turn(&mut app, 2);
+    assert!(
+        app.world.get::<Journey>(hero).is_some(),
+        "the journey is still under way"
+    );
+
+    finish_log(&mut app)
+}
+
+/// Replays the log from the lobby, as the replay button does, and returns
+/// the hash of the game it ended in.
+fn replay(log: &CommandLog, name: &str) -> u64 {
+    let path = std::env::temp_dir().join(format!("replay-{}-{name}.ron", std::process::id()));
+    log.write(&path).unwrap();
+
+    let mut app = headless_app();
+    app.world.resource_mut::<ReplaySettings>().path = path.clone();
+    app.world.send_event(StartReplay);
+    for _ in 0..log.commands.len() * SETTLE_FRAMES + 100 {
+        app.update();
+        if app.world.get_resource::<Replay>().is_none()
+            && app.world.resource::<State<AppState>>().get() == &AppState::InGame
+        {
+            break;
+        }
+    }
+    std::fs::remove_file(path).unwrap();
+
+    assert!(
+        app.world.get_resource::<Replay>().is_none(),
+        "the replay never finished"
+    );
+    hash(&mut app)
+}
+
+#[test]
+fn replay_ends_in_the_logged_game() {
+    let log = play(TurnMode::Sequential);
+
+    assert!(log
+        .commands
+        .iter()
+        .any(|command| command.action == LoggedAction::Undo));
+    assert!(log
+        .commands
+        .iter()
+        .any(|command| command.action == LoggedAction::Redo));
+    assert_eq!(replay(&log, "sequential"), log.final_hash.unwrap());
+}
+
+#[test]
+fn simultaneous_replay_ends_in_the_logged_game() {
+    let log = play(TurnMode::Simultaneous);
+
+    assert_eq!(replay(&log, "simultaneous"), log.final_hash.unwrap());
+}
+
+#[test]
+fn replay_of_another_game_ends_elsewhere() {
+    let log = play(TurnMode::Sequential);
+    let mut other = log.clone();
+    other.commands.retain(|command| command.turn == 1);
+
+    assert_ne!(replay(&other, "shortened"), log.final_hash.unwrap());
+}
+
+#[test]
+fn replay_of_a_journey_ends_in_the_logged_game() {
+    for (turn_mode, name) in [
+        (TurnMode::Sequential, "journey-sequential"),
+        (TurnMode::Simultaneous, "journey-simultaneous"),
+    ] {
+        let log = play_journey(turn_mode);
+
+        assert_eq!(
+            log.commands
+                .iter()
+                .filter(|command| matches!(command.action, LoggedAction::Journey { .. }))
+                .count(),
+            1
+        );
+        assert_eq!(replay(&log, name), log.final_hash.unwrap(), "{name}");
+    }
+}
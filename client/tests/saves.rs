@@ -0,0 +1,77 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use client::{
+    actions::{combat::AttackAction, moves::MoveAction, turns::WaitAction},
+    core_gameplay::components::{ActionPoints, Actor},
+    player::components::Journey,
+    save::utils::{GameLoader, GameSaver},
+};
+use common::{
+    save::{SaveGame, SavedAction, SavedJourney},
+    setup::TurnMode,
+};
+use headless::{headless_app, hero_of, hex_of, plan, settle, setup, start};
+use hexx::Hex;
+
+mod headless;
+
+fn save(app: &mut App) -> SaveGame {
+    app.world.run_system_once(|saver: GameSaver| saver.save())
+}
+
+fn load(app: &mut App, save: SaveGame) {
+    app.world
+        .run_system_once(move |mut loader: GameLoader| loader.load(&save));
+    settle(app);
+}
+
+#[test]
+fn loading_keeps_journeys_and_plans() {
+    let mut app = headless_app();
+    start(&mut app, setup(TurnMode::Sequential));
+
+    let hero = hero_of(&mut app, 1);
+    let enemy = hero_of(&mut app, 2);
+    let to = hex_of(&app, hero) + Hex::X;
+    let path = vec![to, to + Hex::X];
+    app.world.entity_mut(hero).insert((
+        ActionPoints(0),
+        Journey {
+            to: path[1],
+            path: path.clone(),
+        },
+    ));
+    // without action points the plan waits for the next turn
+    plan(&mut app, hero, MoveAction { hero, to });
+    plan(
+        &mut app,
+        hero,
+        AttackAction {
+            attacker: hero,
+            target: enemy,
+        },
+    );
+    plan(&mut app, hero, WaitAction { hero });
+
+    let saved = save(&mut app);
+    let planner = saved
+        .heroes
+        .iter()
+        .find(|hero| hero.owner == 1)
+        .expect("player 1 has a hero");
+    assert_eq!(planner.action_points, 0);
+    assert_eq!(planner.journey, Some(SavedJourney { to: path[1], path }));
+    assert!(matches!(
+        planner.plan[..],
+        [
+            SavedAction::Move { to: planned },
+            SavedAction::Attack { .. },
+            SavedAction::Wait
+        ] if planned == to
+    ));
+
+    load(&mut app, saved.clone());
+
+    let hero = hero_of(&mut app, 1);
+    assert_eq!(app.world.get::<Actor>(hero).unwrap().0.len(), 3);
+    assert_eq!(save(&mut app), saved);
+}
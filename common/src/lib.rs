@@ -0,0 +1,5 @@
+pub mod map;
+pub mod protocol;
+pub mod replay;
+pub mod save;
+pub mod setup;
@@ -0,0 +1,284 @@
+use bevy::{prelude::Component, reflect::Reflect};
+use serde::{Deserialize, Serialize};
+
+/// Tile attributes are kept within `0..=MAX_ATTRIBUTE`.
+pub const MAX_ATTRIBUTE: i32 = 100;
+
+#[derive(Debug, Clone, Copy, Reflect, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Biome {
+    Mountain,
+    Plains,
+    Forest,
+    Desert,
+    ShallowWater,
+    DeepWater,
+    Snow,
+}
+
+impl Biome {
+    pub const ALL: [Biome; 7] = [
+        Biome::Mountain,
+        Biome::Plains,
+        Biome::Forest,
+        Biome::Desert,
+        Biome::ShallowWater,
+        Biome::DeepWater,
+        Biome::Snow,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Biome::Mountain => "Mountain",
+            Biome::Plains => "Plains",
+            Biome::Forest => "Forest",
+            Biome::Desert => "Desert",
+            Biome::ShallowWater => "ShallowWater",
+            Biome::DeepWater => "DeepWater",
+            Biome::Snow => "Snow",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Biome> {
+        Self::ALL.into_iter().find(|biome| biome.name() == name)
+    }
+
+    pub fn is_water(&self) -> bool {
+        matches!(self, Biome::ShallowWater | Biome::DeepWater)
+    }
+
+    pub fn cost(&self) -> Option<u32> {
+        match self {
+            Biome::Mountain => None,
+            Biome::Plains => Some(1),
+            Biome::Forest => Some(5),
+            Biome::Desert => Some(10),
+            Biome::ShallowWater => Some(12),
+            Biome::DeepWater => None,
+            Biome::Snow => Some(13),
+        }
+    }
+
+    /// Classifies a tile from its fractal elevation (roughly `-1.0..1.0`, sea
+    /// level at `0.0`) and moisture (`0.0..1.0`).
+    pub fn from_elevation_and_moisture(elevation: f64, moisture: f64) -> Biome {
+        if elevation < -0.2 {
+            Biome::DeepWater
+        } else if elevation < 0.0 {
+            Biome::ShallowWater
+        } else if elevation < 0.25 {
+            if moisture < 0.35 {
+                Biome::Desert
+            } else if moisture < 0.6 {
+                Biome::Plains
+            } else {
+                Biome::Forest
+            }
+        } else if elevation < 0.4 {
+            if moisture < 0.5 {
+                Biome::Plains
+            } else {
+                Biome::Forest
+            }
+        } else if elevation < 0.55 || moisture < 0.4 {
+            Biome::Mountain
+        } else {
+            Biome::Snow
+        }
+    }
+
+    pub fn simple_biome(value: f64) -> Biome {
+        if value < 0.1 {
+            Biome::Plains
+        } else if value < 0.2 {
+            Biome::Forest
+        } else if value < 0.3 {
+            Biome::Desert
+        } else if value < 0.4 {
+            Biome::Mountain
+        } else {
+            Biome::Snow
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Reflect, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TileResource {
+    Wood,
+    Stone,
+    Iron,
+    Nitre,
+    Coal,
+    Oil,
+    Uranium,
+    Tea,
+    Marble,
+    Salt,
+    Copper,
+    Diamond,
+    Ivory,
+    Banana,
+    Wheat,
+    Rice,
+    Sugar,
+    Spices,
+}
+
+/// Strategic resources are needed to build things and go in a tile's
+/// `strategic_resource`, luxury and bonus resources are traded and go in its
+/// `trade_resource`.
+#[derive(Debug, Clone, Copy, Reflect, PartialEq, Eq, Hash)]
+pub enum ResourceCategory {
+    Strategic,
+    Luxury,
+    Bonus,
+}
+
+impl TileResource {
+    pub const ALL: [TileResource; 18] = [
+        TileResource::Wood,
+        TileResource::Stone,
+        TileResource::Iron,
+        TileResource::Nitre,
+        TileResource::Coal,
+        TileResource::Oil,
+        TileResource::Uranium,
+        TileResource::Tea,
+        TileResource::Marble,
+        TileResource::Salt,
+        TileResource::Copper,
+        TileResource::Diamond,
+        TileResource::Ivory,
+        TileResource::Banana,
+        TileResource::Wheat,
+        TileResource::Rice,
+        TileResource::Sugar,
+        TileResource::Spices,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Wood => "Wood",
+            Self::Stone => "Stone",
+            Self::Iron => "Iron",
+            Self::Nitre => "Nitre",
+            Self::Coal => "Coal",
+            Self::Oil => "Oil",
+            Self::Uranium => "Uranium",
+            Self::Tea => "Tea",
+            Self::Marble => "Marble",
+            Self::Salt => "Salt",
+            Self::Copper => "Copper",
+            Self::Diamond => "Diamond",
+            Self::Ivory => "Ivory",
+            Self::Banana => "Banana",
+            Self::Wheat => "Wheat",
+            Self::Rice => "Rice",
+            Self::Sugar => "Sugar",
+            Self::Spices => "Spices",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<TileResource> {
+        Self::ALL
+            .into_iter()
+            .find(|resource| resource.name() == name)
+    }
+
+    pub fn category(&self) -> ResourceCategory {
+        match self {
+            Self::Iron | Self::Nitre | Self::Coal | Self::Oil | Self::Uranium | Self::Copper => {
+                ResourceCategory::Strategic
+            }
+            Self::Tea
+            | Self::Marble
+            | Self::Salt
+            | Self::Diamond
+            | Self::Ivory
+            | Self::Sugar
+            | Self::Spices => ResourceCategory::Luxury,
+            Self::Wood | Self::Stone | Self::Banana | Self::Wheat | Self::Rice => {
+                ResourceCategory::Bonus
+            }
+        }
+    }
+
+    pub fn is_strategic(&self) -> bool {
+        self.category() == ResourceCategory::Strategic
+    }
+}
+
+#[derive(Debug, Clone, Reflect, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TileAttributes {
+    pub production: i32,
+    pub science: i32,
+    pub attractiveness: i32,
+}
+
+impl TileAttributes {
+    pub fn production(&self) -> i32 {
+        self.production
+    }
+
+    pub fn science(&self) -> i32 {
+        self.science
+    }
+
+    pub fn attractiveness(&self) -> i32 {
+        self.attractiveness
+    }
+}
+
+impl Default for TileAttributes {
+    fn default() -> Self {
+        Self {
+            production: 0,
+            science: 0,
+            attractiveness: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Component, Reflect, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tile {
+    pub biome: Biome,
+    pub attributes: TileAttributes,
+    pub strategic_resource: Option<TileResource>,
+    pub trade_resource: Option<TileResource>,
+}
+
+impl Default for Tile {
+    fn default() -> Self {
+        Self {
+            biome: Biome::Plains,
+            attributes: TileAttributes::default(),
+            strategic_resource: None,
+            trade_resource: None,
+        }
+    }
+}
+
+impl Tile {
+    pub fn new(
+        biome: Biome,
+        production: i32,
+        science: i32,
+        attractiveness: i32,
+        special_resource: Option<TileResource>,
+        trade_resource: Option<TileResource>,
+    ) -> Self {
+        Self {
+            biome,
+            attributes: TileAttributes {
+                production,
+                science,
+                attractiveness,
+            },
+            strategic_resource: special_resource,
+            trade_resource,
+        }
+    }
+
+    pub fn cost(&self) -> Option<u32> {
+        self.biome.cost()
+    }
+}
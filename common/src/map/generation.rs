@@ -0,0 +1,394 @@
+use std::f64::consts::TAU;
+
+use bevy::{reflect::Reflect, utils::HashMap};
+use hexx::{Hex, HexLayout};
+use noise::{Fbm, MultiFractal, NoiseFn, Simplex};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    components::{Biome, Tile, MAX_ATTRIBUTE},
+    placement::{balance_start_resources, place_resources, ResourceSettings},
+    rivers::{trace_rivers, Rivers},
+    shape::MapShape,
+    start_positions::{find_start_positions, score_starts, StartPositions},
+};
+
+/// Lowest elevation a river may spring from.
+const RIVER_SOURCE_ELEVATION: f64 = 0.35;
+/// Bonuses for tiles on a river bank.
+const RIVER_PRODUCTION_BONUS: i32 = 10;
+const RIVER_ATTRACTIVENESS_BONUS: i32 = 15;
+
+/// Parameters of a fractal (fBm) noise layer, sampled on a layout of unit-sized
+/// hexes.
+#[derive(Debug, Clone, Reflect, PartialEq, Serialize, Deserialize)]
+pub struct NoiseSettings {
+    pub frequency: f64,
+    pub octaves: usize,
+    pub lacunarity: f64,
+    pub persistence: f64,
+}
+
+impl NoiseSettings {
+    pub fn build(&self, seed: u32) -> Fbm<Simplex> {
+        Fbm::<Simplex>::new(seed)
+            .set_frequency(self.frequency)
+            .set_octaves(self.octaves)
+            .set_lacunarity(self.lacunarity)
+            .set_persistence(self.persistence)
+    }
+}
+
+/// Shape of the landmasses a map is generated with.
+#[derive(Debug, Clone, Copy, Default, Reflect, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MapType {
+    /// A few large continents separated by deep water.
+    #[default]
+    Continents,
+    /// A single landmass in the middle of the map.
+    Pangaea,
+    /// Many small islands.
+    Archipelago,
+    /// A ring of land around a central sea.
+    InlandSea,
+}
+
+impl MapType {
+    pub const ALL: [MapType; 4] = [
+        MapType::Continents,
+        MapType::Pangaea,
+        MapType::Archipelago,
+        MapType::InlandSea,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MapType::Continents => "Continents",
+            MapType::Pangaea => "Pangaea",
+            MapType::Archipelago => "Archipelago",
+            MapType::InlandSea => "Inland sea",
+        }
+    }
+
+    /// Scale applied to noise coordinates, higher values give smaller landmasses.
+    fn noise_scale(&self) -> f64 {
+        match self {
+            MapType::Archipelago => 3.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Elevation added on top of the noise at `point`, where `point` is
+    /// normalized so that the map spans `-1.0..=1.0` on both axes. On wrapping
+    /// maps `wrap_period` is the normalized width after which x repeats.
+    fn land_bias(&self, point: [f64; 2], continents: &[[f64; 2]], wrap_period: Option<f64>) -> f64 {
+        let distance = length(point);
+
+        match self {
+            MapType::Continents => {
+                let mut distances: Vec<f64> = continents
+                    .iter()
+                    .map(|center| {
+                        let mut dx = point[0] - center[0];
+                        if let Some(period) = wrap_period {
+                            dx -= period * (dx / period).round();
+                        }
+                        length([dx, point[1] - center[1]])
+                    })
+                    .collect();
+                distances.sort_by(|a, b| a.total_cmp(b));
+
+                let bias = 1.0 - distances[0] / 0.55;
+
+                // sink the tiles halfway between two continents so they never touch
+                let gap = distances
+                    .get(1)
+                    .map_or(f64::MAX, |second| second - distances[0]);
+                if gap < CONTINENT_CHANNEL_WIDTH {
+                    bias - 1.5 * (1.0 - gap / CONTINENT_CHANNEL_WIDTH)
+                } else {
+                    bias
+                }
+            }
+            MapType::Pangaea => 0.8 - 1.6 * distance,
+            MapType::Archipelago => -0.3 * distance,
+            MapType::InlandSea => 0.8 - 2.5 * (distance - 0.6).abs(),
+        }
+    }
+
+    fn continent_centers(&self, rng: &mut ChaCha8Rng) -> Vec<[f64; 2]> {
+        if *self != MapType::Continents {
+            return vec![];
+        }
+
+        let count = rng.gen_range(2..=3);
+        let start_angle = rng.gen_range(0.0..TAU);
+
+        (0..count)
+            .map(|i| {
+                let angle = start_angle + i as f64 * TAU / count as f64 + rng.gen_range(-0.3..0.3);
+                [0.5 * angle.cos(), 0.5 * angle.sin()]
+            })
+            .collect()
+    }
+}
+
+const CONTINENT_CHANNEL_WIDTH: f64 = 0.15;
+
+#[derive(Debug, Clone, Reflect, PartialEq, Serialize, Deserialize)]
+pub struct GeneratorSettings {
+    pub seed: u64,
+    pub shape: MapShape,
+    pub map_type: MapType,
+    /// Fraction of tiles that end up as land, between `0.0` and `1.0`.
+    pub land_ratio: f64,
+    pub elevation: NoiseSettings,
+    pub moisture: NoiseSettings,
+    /// How many rivers to trace, fewer may fit on small maps.
+    pub river_count: u32,
+    pub resources: ResourceSettings,
+    /// Number of start positions to place.
+    pub player_count: u32,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            seed: rand::random(),
+            shape: MapShape::default(),
+            map_type: MapType::default(),
+            land_ratio: 0.4,
+            elevation: NoiseSettings {
+                frequency: 0.04,
+                octaves: 5,
+                lacunarity: 2.0,
+                persistence: 0.5,
+            },
+            moisture: NoiseSettings {
+                frequency: 0.07,
+                octaves: 4,
+                lacunarity: 2.0,
+                persistence: 0.5,
+            },
+            river_count: 16,
+            resources: ResourceSettings::default(),
+            player_count: 2,
+        }
+    }
+}
+
+/// Everything that makes up a freshly generated map.
+#[derive(Debug, Clone)]
+pub struct GeneratedMap {
+    pub tiles: HashMap<Hex, Tile>,
+    pub rivers: Rivers,
+    pub start_positions: StartPositions,
+}
+
+/// Generates a map without touching the ECS, so it can run on the server or in
+/// tests. The same settings always produce the same map.
+pub fn generate_map(settings: &GeneratorSettings) -> GeneratedMap {
+    let mut rng = ChaCha8Rng::seed_from_u64(settings.seed);
+    let elevation_noise = settings.elevation.build(rng.gen());
+    let moisture_noise = settings.moisture.build(rng.gen());
+    let continents = settings.map_type.continent_centers(&mut rng);
+
+    // noise is sampled in hex units so frequencies don't depend on render size
+    let layout = HexLayout::default();
+
+    let coords = settings.shape.hexes();
+    let points: Vec<[f64; 2]> = coords
+        .iter()
+        .map(|&coord| {
+            let pos = layout.hex_to_world_pos(coord);
+            [pos.x as f64, pos.y as f64]
+        })
+        .collect();
+    let (normalized, half_extent) = normalize_points(&points);
+
+    // world distance after which a wrapping map repeats
+    let wrap_period = settings.shape.wrap_width().map(|width| {
+        let seam = layout.hex_to_world_pos(Hex::new(width as i32, 0));
+        let origin = layout.hex_to_world_pos(Hex::ZERO);
+        (seam.x - origin.x) as f64
+    });
+
+    let scale = settings.map_type.noise_scale();
+    let raw_elevations: Vec<f64> = points
+        .iter()
+        .zip(normalized.iter())
+        .map(|(point, &normalized)| {
+            let noise = sample(
+                &elevation_noise,
+                [point[0] * scale, point[1] * scale],
+                wrap_period.map(|period| period * scale),
+            );
+            let bias = settings.map_type.land_bias(
+                normalized,
+                &continents,
+                wrap_period.map(|period| period / half_extent[0]),
+            );
+            noise * 0.6 + bias
+        })
+        .collect();
+    let elevations = flood_to_land_ratio(&raw_elevations, settings.land_ratio);
+
+    let mut tiles: HashMap<Hex, Tile> = coords
+        .iter()
+        .zip(points.iter())
+        .zip(elevations.iter())
+        .map(|((&coord, &point), &elevation)| {
+            let moisture = sample(&moisture_noise, point, wrap_period);
+            let moisture = (moisture * 0.5 + 0.5).clamp(0.0, 1.0);
+            let biome = Biome::from_elevation_and_moisture(elevation, moisture);
+
+            let tile = Tile::new(
+                biome,
+                rng.gen_range(0..100),
+                rng.gen_range(0..100),
+                rng.gen_range(0..100),
+                None,
+                None,
+            );
+
+            (coord, tile)
+        })
+        .collect();
+
+    let mut sources: Vec<Hex> = coords
+        .iter()
+        .zip(elevations.iter())
+        .filter(|(_, &elevation)| elevation >= RIVER_SOURCE_ELEVATION)
+        .map(|(&coord, _)| coord)
+        .collect();
+    sources.shuffle(&mut rng);
+
+    let elevations: HashMap<Hex, f64> = coords.iter().copied().zip(elevations).collect();
+    let rivers = trace_rivers(&elevations, &settings.shape, sources, settings.river_count);
+
+    for hex in rivers.banks() {
+        if let Some(tile) = tiles.get_mut(&hex) {
+            let attributes = &mut tile.attributes;
+            attributes.production =
+                (attributes.production + RIVER_PRODUCTION_BONUS).min(MAX_ATTRIBUTE);
+            attributes.attractiveness =
+                (attributes.attractiveness + RIVER_ATTRACTIVENESS_BONUS).min(MAX_ATTRIBUTE);
+        }
+    }
+
+    place_resources(
+        &mut tiles,
+        &coords,
+        &settings.shape,
+        &settings.resources,
+        &mut rng,
+    );
+
+    let radius = settings.resources.start_radius;
+    let starts = find_start_positions(
+        &tiles,
+        &settings.shape,
+        &coords,
+        settings.player_count,
+        radius,
+    );
+    balance_start_resources(
+        &mut tiles,
+        &settings.shape,
+        &starts,
+        &settings.resources,
+        &mut rng,
+    );
+    let start_positions = score_starts(&tiles, &settings.shape, &starts, radius);
+
+    GeneratedMap {
+        tiles,
+        rivers,
+        start_positions,
+    }
+}
+
+/// Samples `noise` at `point`. When the map wraps, x is rolled onto a cylinder
+/// of circumference `wrap_period` so the noise is continuous across the seam.
+fn sample(noise: &Fbm<Simplex>, point: [f64; 2], wrap_period: Option<f64>) -> f64 {
+    match wrap_period {
+        Some(period) => {
+            let angle = point[0] / period * TAU;
+            let radius = period / TAU;
+            noise.get([radius * angle.cos(), radius * angle.sin(), point[1]])
+        }
+        None => noise.get(point),
+    }
+}
+
+/// Maps points into `-1.0..=1.0` on both axes, relative to their bounding box.
+/// Also returns the half extent of that box.
+fn normalize_points(points: &[[f64; 2]]) -> (Vec<[f64; 2]>, [f64; 2]) {
+    let mut min = [f64::MAX, f64::MAX];
+    let mut max = [f64::MIN, f64::MIN];
+    for point in points {
+        for axis in 0..2 {
+            min[axis] = min[axis].min(point[axis]);
+            max[axis] = max[axis].max(point[axis]);
+        }
+    }
+
+    let center = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+    let half_extent = [
+        ((max[0] - min[0]) / 2.0).max(1.0),
+        ((max[1] - min[1]) / 2.0).max(1.0),
+    ];
+
+    let normalized = points
+        .iter()
+        .map(|point| {
+            [
+                (point[0] - center[0]) / half_extent[0],
+                (point[1] - center[1]) / half_extent[1],
+            ]
+        })
+        .collect();
+
+    (normalized, half_extent)
+}
+
+/// Picks a sea level so that exactly `land_ratio` of the tiles are land, then
+/// rescales elevations so that water lies in `-1.0..0.0` and land in
+/// `0.0..=0.8`, the range `Biome::from_elevation_and_moisture` expects.
+fn flood_to_land_ratio(raw: &[f64], land_ratio: f64) -> Vec<f64> {
+    if raw.is_empty() {
+        return vec![];
+    }
+
+    let mut order: Vec<usize> = (0..raw.len()).collect();
+    order.sort_by(|&a, &b| raw[a].total_cmp(&raw[b]).then(a.cmp(&b)));
+
+    let land_count = (land_ratio.clamp(0.0, 1.0) * raw.len() as f64).round() as usize;
+    let water_count = raw.len() - land_count;
+
+    let lowest = raw[order[0]];
+    let highest = raw[order[raw.len() - 1]];
+    let sea_level = if land_count == 0 {
+        highest
+    } else {
+        raw[order[water_count]]
+    };
+
+    let mut elevations = vec![0.0; raw.len()];
+    for (rank, &index) in order.iter().enumerate() {
+        elevations[index] = if rank < water_count {
+            let depth = (sea_level - raw[index]) / (sea_level - lowest).max(f64::EPSILON);
+            (-depth).min(-f64::EPSILON)
+        } else {
+            (raw[index] - sea_level) / (highest - sea_level).max(f64::EPSILON) * 0.8
+        };
+    }
+
+    elevations
+}
+
+fn length(point: [f64; 2]) -> f64 {
+    (point[0] * point[0] + point[1] * point[1]).sqrt()
+}
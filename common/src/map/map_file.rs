@@ -0,0 +1,291 @@
+//! Hand editable map files, written in RON. A map file lists the map's shape
+//! and every tile on it:
+//!
+//! ```ron
+//! (
+//!     shape: Hexagon(radius: 1),
+//!     tiles: [
+//!         (
+//!             hex: (0, 0),
+//!             biome: "Plains",
+//!             production: 40,
+//!             science: 20,
+//!             attractiveness: 55,
+//!             strategic_resource: Some("Iron"),
+//!             trade_resource: Some("Wheat"),
+//!         ),
+//!         (hex: (1, 0), biome: "DeepWater", production: 0, science: 0, attractiveness: 20),
+//!     ],
+//! )
+//! ```
+//!
+//! - `shape` is `Hexagon(radius: r)` or `Rectangle(width: w, height: h, wrap_x: bool)`.
+//! - `hex` is the tile's axial `(x, y)` coordinate, every hex may appear once
+//!   and must lie within `shape`. Hexes of the shape without a tile are holes.
+//! - `biome` is one of the `Biome` variants, spelled as in the code.
+//! - `production`, `science` and `attractiveness` range from `0` to
+//!   `MAX_ATTRIBUTE`.
+//! - `strategic_resource` takes a strategic `TileResource` and
+//!   `trade_resource` a luxury or bonus one. Both may be left out.
+
+use std::{fmt, fs, io, path::Path};
+
+use bevy::utils::{HashMap, HashSet};
+use hexx::Hex;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    components::{Biome, Tile, TileAttributes, TileResource, MAX_ATTRIBUTE},
+    generation::GeneratedMap,
+    rivers::Rivers,
+    shape::MapShape,
+    start_positions::{find_start_positions, score_starts},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapFile {
+    pub shape: MapShape,
+    pub tiles: Vec<MapFileTile>,
+}
+
+/// A tile as written in a map file. Names are kept as text until
+/// `MapFile::tiles` checks them, so mistakes can be reported by hex.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapFileTile {
+    pub hex: (i32, i32),
+    pub biome: String,
+    pub production: i32,
+    pub science: i32,
+    pub attractiveness: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategic_resource: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trade_resource: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum MapFileError {
+    Io(io::Error),
+    Serialize(ron::Error),
+    Parse(ron::error::SpannedError),
+    UnknownBiome {
+        hex: Hex,
+        biome: String,
+    },
+    UnknownResource {
+        hex: Hex,
+        resource: String,
+    },
+    /// A strategic resource in the trade slot or the other way around.
+    MisplacedResource {
+        hex: Hex,
+        resource: TileResource,
+    },
+    DuplicateHex(Hex),
+    OutsideShape(Hex),
+    AttributeOutOfRange {
+        hex: Hex,
+        attribute: &'static str,
+        value: i32,
+    },
+}
+
+impl fmt::Display for MapFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapFileError::Io(error) => write!(f, "could not access map file: {error}"),
+            MapFileError::Serialize(error) => write!(f, "could not write map file: {error}"),
+            MapFileError::Parse(error) => write!(f, "could not read map file: {error}"),
+            MapFileError::UnknownBiome { hex, biome } => {
+                write!(f, "unknown biome {biome:?} at {:?}", (hex.x, hex.y))
+            }
+            MapFileError::UnknownResource { hex, resource } => {
+                write!(f, "unknown resource {resource:?} at {:?}", (hex.x, hex.y))
+            }
+            MapFileError::MisplacedResource { hex, resource } => write!(
+                f,
+                "{} at {:?} is in the wrong resource slot",
+                resource.name(),
+                (hex.x, hex.y)
+            ),
+            MapFileError::DuplicateHex(hex) => {
+                write!(f, "hex {:?} appears more than once", (hex.x, hex.y))
+            }
+            MapFileError::OutsideShape(hex) => {
+                write!(f, "hex {:?} lies outside the map shape", (hex.x, hex.y))
+            }
+            MapFileError::AttributeOutOfRange {
+                hex,
+                attribute,
+                value,
+            } => write!(
+                f,
+                "{attribute} {value} at {:?} is outside 0..={MAX_ATTRIBUTE}",
+                (hex.x, hex.y)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MapFileError {}
+
+impl From<io::Error> for MapFileError {
+    fn from(error: io::Error) -> Self {
+        MapFileError::Io(error)
+    }
+}
+
+impl From<ron::Error> for MapFileError {
+    fn from(error: ron::Error) -> Self {
+        MapFileError::Serialize(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for MapFileError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        MapFileError::Parse(error)
+    }
+}
+
+impl MapFileTile {
+    fn new(hex: Hex, tile: &Tile) -> Self {
+        Self {
+            hex: (hex.x, hex.y),
+            biome: tile.biome.name().to_string(),
+            production: tile.attributes.production,
+            science: tile.attributes.science,
+            attractiveness: tile.attributes.attractiveness,
+            strategic_resource: tile.strategic_resource.map(|r| r.name().to_string()),
+            trade_resource: tile.trade_resource.map(|r| r.name().to_string()),
+        }
+    }
+
+    fn to_tile(&self) -> Result<Tile, MapFileError> {
+        let hex = Hex::new(self.hex.0, self.hex.1);
+
+        let biome = Biome::from_name(&self.biome).ok_or_else(|| MapFileError::UnknownBiome {
+            hex,
+            biome: self.biome.clone(),
+        })?;
+
+        let attributes = [
+            ("production", self.production),
+            ("science", self.science),
+            ("attractiveness", self.attractiveness),
+        ];
+        for (attribute, value) in attributes {
+            if !(0..=MAX_ATTRIBUTE).contains(&value) {
+                return Err(MapFileError::AttributeOutOfRange {
+                    hex,
+                    attribute,
+                    value,
+                });
+            }
+        }
+
+        let resource = |name: &Option<String>, strategic: bool| {
+            let Some(name) = name else {
+                return Ok(None);
+            };
+            let resource =
+                TileResource::from_name(name).ok_or_else(|| MapFileError::UnknownResource {
+                    hex,
+                    resource: name.clone(),
+                })?;
+            if resource.is_strategic() != strategic {
+                return Err(MapFileError::MisplacedResource { hex, resource });
+            }
+
+            Ok(Some(resource))
+        };
+
+        Ok(Tile {
+            biome,
+            attributes: TileAttributes {
+                production: self.production,
+                science: self.science,
+                attractiveness: self.attractiveness,
+            },
+            strategic_resource: resource(&self.strategic_resource, true)?,
+            trade_resource: resource(&self.trade_resource, false)?,
+        })
+    }
+}
+
+impl MapFile {
+    /// Describes `tiles`, sorted by hex so exports are stable.
+    pub fn new(shape: MapShape, tiles: &HashMap<Hex, Tile>) -> Self {
+        let mut tiles: Vec<MapFileTile> = tiles
+            .iter()
+            .map(|(&hex, tile)| MapFileTile::new(hex, tile))
+            .collect();
+        tiles.sort_by_key(|tile| tile.hex);
+
+        Self { shape, tiles }
+    }
+
+    /// Checks every tile and turns them into game tiles.
+    pub fn tiles(&self) -> Result<HashMap<Hex, Tile>, MapFileError> {
+        let mut seen = HashSet::default();
+        let mut tiles = HashMap::default();
+
+        for tile in self.tiles.iter() {
+            let hex = Hex::new(tile.hex.0, tile.hex.1);
+            if !self.shape.contains(hex) {
+                return Err(MapFileError::OutsideShape(hex));
+            }
+            if !seen.insert(hex) {
+                return Err(MapFileError::DuplicateHex(hex));
+            }
+
+            tiles.insert(hex, tile.to_tile()?);
+        }
+
+        Ok(tiles)
+    }
+
+    /// Builds a playable map from the file. Map files have no rivers, start
+    /// positions are picked the same way generated maps get them.
+    pub fn to_map(&self, players: u32, start_radius: u32) -> Result<GeneratedMap, MapFileError> {
+        let tiles = self.tiles()?;
+        let hexes: Vec<Hex> = self
+            .shape
+            .hexes()
+            .into_iter()
+            .filter(|hex| tiles.contains_key(hex))
+            .collect();
+
+        let starts = find_start_positions(&tiles, &self.shape, &hexes, players, start_radius);
+        let start_positions = score_starts(&tiles, &self.shape, &starts, start_radius);
+
+        Ok(GeneratedMap {
+            tiles,
+            rivers: Rivers::default(),
+            start_positions,
+        })
+    }
+
+    pub fn to_ron(&self) -> Result<String, MapFileError> {
+        Ok(ron::ser::to_string_pretty(self, PrettyConfig::default())?)
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, MapFileError> {
+        Ok(ron::from_str(text)?)
+    }
+
+    /// Writes the file to `path`, creating its directory if needed.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), MapFileError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, MapFileError> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+}
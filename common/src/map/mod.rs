@@ -0,0 +1,8 @@
+pub mod components;
+pub mod generation;
+pub mod map_file;
+pub mod pathfinding;
+pub mod placement;
+pub mod rivers;
+pub mod shape;
+pub mod start_positions;
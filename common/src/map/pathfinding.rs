@@ -0,0 +1,121 @@
+use bevy::utils::{HashMap, HashSet};
+use hexx::{
+    algorithms::{a_star, field_of_movement},
+    Hex,
+};
+
+use super::{components::Tile, rivers::Rivers, shape::MapShape};
+
+/// Extra movement cost of crossing a river edge.
+pub const RIVER_CROSSING_COST: u32 = 3;
+
+/// Cost of stepping from `from` onto its neighbour `to`, `None` if `to` can't
+/// be entered. Either hex may lie past the seam of a wrapping map.
+pub fn step_cost(
+    tiles: &HashMap<Hex, Tile>,
+    shape: &MapShape,
+    rivers: &Rivers,
+    from: Hex,
+    to: Hex,
+) -> Option<u32> {
+    let tile = tiles.get(&shape.normalize(to)?)?;
+    let river_cost = if rivers.crosses(shape.wrap(from), shape.wrap(to)) {
+        RIVER_CROSSING_COST
+    } else {
+        0
+    };
+
+    Some(tile.cost()? + river_cost)
+}
+
+/// Cheapest path from `start` to `goal` and its cost, without `start` itself.
+/// The path is wrapped back onto the map.
+pub fn find_path(
+    tiles: &HashMap<Hex, Tile>,
+    shape: &MapShape,
+    rivers: &Rivers,
+    start: Hex,
+    goal: Hex,
+) -> Option<(Vec<Hex>, u32)> {
+    // on wrapping maps head for the copy of the goal across the seam if it is closer
+    let goal = shape.nearest(start, goal);
+    let search_limit = shape.search_limit();
+
+    let path = a_star(start, goal, |from, to| {
+        if search_limit.is_some_and(|limit| start.unsigned_distance_to(to) > limit) {
+            return None;
+        }
+
+        step_cost(tiles, shape, rivers, from, to)
+    })?;
+
+    let cost = path
+        .windows(2)
+        .map(|step| step_cost(tiles, shape, rivers, step[0], step[1]))
+        .sum::<Option<u32>>()?;
+    let path = path
+        .into_iter()
+        .skip(1)
+        .map(|hex| shape.wrap(hex))
+        .collect();
+
+    Some((path, cost))
+}
+
+/// How many hexes of `path` a hero has covered by the end of each turn, with
+/// `movement_points` left this turn and `max_movement_points` every turn
+/// after. `path` leads on from `start`, as `find_path` returns it, and is cut
+/// short at a step no turn's points cover.
+pub fn turn_stops(
+    tiles: &HashMap<Hex, Tile>,
+    shape: &MapShape,
+    rivers: &Rivers,
+    start: Hex,
+    path: &[Hex],
+    movement_points: u32,
+    max_movement_points: u32,
+) -> Vec<usize> {
+    let mut stops = Vec::new();
+    let mut points = movement_points;
+    let mut from = start;
+    let mut covered = 0;
+
+    for &to in path {
+        let Some(cost) = step_cost(tiles, shape, rivers, from, to) else {
+            break;
+        };
+        if cost > points {
+            if cost > max_movement_points {
+                break;
+            }
+            stops.push(covered);
+            points = max_movement_points;
+        }
+
+        points -= cost;
+        from = to;
+        covered += 1;
+    }
+    if covered > stops.last().copied().unwrap_or(0) {
+        stops.push(covered);
+    }
+
+    stops
+}
+
+/// Every hex reachable from `start` with `budget` movement points, ignoring
+/// rivers.
+pub fn reachable(
+    tiles: &HashMap<Hex, Tile>,
+    shape: &MapShape,
+    start: Hex,
+    budget: u32,
+) -> HashSet<Hex> {
+    field_of_movement(start, budget, |hex| {
+        let cost = tiles.get(&shape.normalize(hex)?)?.cost()?;
+        (cost <= budget).then_some(cost)
+    })
+    .into_iter()
+    .map(|hex| shape.wrap(hex))
+    .collect()
+}
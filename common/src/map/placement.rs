@@ -0,0 +1,240 @@
+use bevy::{
+    reflect::Reflect,
+    utils::{HashMap, HashSet},
+};
+use hexx::Hex;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    components::{Biome, ResourceCategory, Tile, TileResource},
+    shape::MapShape,
+};
+
+#[derive(Debug, Clone, Reflect, PartialEq, Serialize, Deserialize)]
+pub struct ResourceSettings {
+    /// Chance that a tile able to hold a strategic resource gets one.
+    pub strategic_frequency: f64,
+    /// Chance that a tile able to hold a luxury resource gets one.
+    pub luxury_frequency: f64,
+    /// Chance that a tile able to hold a bonus resource gets one.
+    pub bonus_frequency: f64,
+    /// Resources of the same category are never placed closer than this.
+    pub min_spacing: u32,
+    /// How far around a start position `balance_start_resources` looks.
+    pub start_radius: u32,
+    /// Strategic resources every start position gets within `start_radius`.
+    pub strategic_near_start: u32,
+    /// Luxury resources every start position gets within `start_radius`.
+    pub luxury_near_start: u32,
+}
+
+impl Default for ResourceSettings {
+    fn default() -> Self {
+        Self {
+            strategic_frequency: 0.08,
+            luxury_frequency: 0.05,
+            bonus_frequency: 0.12,
+            min_spacing: 2,
+            start_radius: 3,
+            strategic_near_start: 2,
+            luxury_near_start: 1,
+        }
+    }
+}
+
+/// Resources that can spawn on a biome, with their relative weights.
+pub fn spawn_table(biome: &Biome) -> &'static [(TileResource, u32)] {
+    match biome {
+        Biome::Plains => &[
+            (TileResource::Wheat, 4),
+            (TileResource::Rice, 2),
+            (TileResource::Iron, 2),
+            (TileResource::Nitre, 2),
+            (TileResource::Copper, 1),
+            (TileResource::Ivory, 1),
+            (TileResource::Sugar, 2),
+        ],
+        Biome::Forest => &[
+            (TileResource::Wood, 5),
+            (TileResource::Banana, 2),
+            (TileResource::Coal, 2),
+            (TileResource::Iron, 1),
+            (TileResource::Tea, 2),
+            (TileResource::Spices, 2),
+            (TileResource::Ivory, 1),
+        ],
+        Biome::Desert => &[
+            (TileResource::Stone, 2),
+            (TileResource::Oil, 4),
+            (TileResource::Uranium, 1),
+            (TileResource::Salt, 3),
+            (TileResource::Marble, 1),
+            (TileResource::Diamond, 1),
+        ],
+        Biome::Mountain => &[
+            (TileResource::Stone, 3),
+            (TileResource::Iron, 3),
+            (TileResource::Copper, 2),
+            (TileResource::Coal, 1),
+            (TileResource::Uranium, 2),
+            (TileResource::Marble, 2),
+            (TileResource::Diamond, 2),
+        ],
+        Biome::Snow => &[
+            (TileResource::Oil, 2),
+            (TileResource::Uranium, 2),
+            (TileResource::Ivory, 1),
+            (TileResource::Diamond, 1),
+        ],
+        Biome::ShallowWater | Biome::DeepWater => &[],
+    }
+}
+
+/// Rolls resources for every tile from its biome's spawn table.
+pub fn place_resources(
+    tiles: &mut HashMap<Hex, Tile>,
+    hexes: &[Hex],
+    shape: &MapShape,
+    settings: &ResourceSettings,
+    rng: &mut ChaCha8Rng,
+) {
+    let mut placed = placed_resources(tiles);
+    let mut order = hexes.to_vec();
+    order.shuffle(rng);
+
+    let frequencies = [
+        (ResourceCategory::Strategic, settings.strategic_frequency),
+        (ResourceCategory::Luxury, settings.luxury_frequency),
+        (ResourceCategory::Bonus, settings.bonus_frequency),
+    ];
+
+    for hex in order {
+        for (category, frequency) in frequencies {
+            if rng.gen_bool(frequency.clamp(0.0, 1.0)) {
+                try_place(
+                    tiles,
+                    &mut placed,
+                    shape,
+                    hex,
+                    category,
+                    settings.min_spacing,
+                    rng,
+                );
+            }
+        }
+    }
+}
+
+/// Tops up the strategic and luxury resources around every start position so
+/// that no player starts without them. Spacing is relaxed if there is no
+/// other way to fit them.
+pub fn balance_start_resources(
+    tiles: &mut HashMap<Hex, Tile>,
+    shape: &MapShape,
+    starts: &[Hex],
+    settings: &ResourceSettings,
+    rng: &mut ChaCha8Rng,
+) {
+    let mut placed = placed_resources(tiles);
+
+    for &start in starts {
+        let wanted = [
+            (ResourceCategory::Strategic, settings.strategic_near_start),
+            (ResourceCategory::Luxury, settings.luxury_near_start),
+        ];
+
+        for (category, wanted) in wanted {
+            let mut area: Vec<Hex> = start
+                .range(settings.start_radius)
+                .filter_map(|hex| shape.normalize(hex))
+                .collect();
+            let present = area
+                .iter()
+                .filter(|hex| placed[&category].contains(*hex))
+                .count() as u32;
+            let mut missing = wanted.saturating_sub(present);
+
+            area.shuffle(rng);
+            for min_spacing in [settings.min_spacing, 0] {
+                for &hex in area.iter() {
+                    if missing == 0 {
+                        break;
+                    }
+
+                    if try_place(tiles, &mut placed, shape, hex, category, min_spacing, rng) {
+                        missing -= 1;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn placed_resources(tiles: &HashMap<Hex, Tile>) -> HashMap<ResourceCategory, HashSet<Hex>> {
+    let mut placed: HashMap<ResourceCategory, HashSet<Hex>> = [
+        ResourceCategory::Strategic,
+        ResourceCategory::Luxury,
+        ResourceCategory::Bonus,
+    ]
+    .into_iter()
+    .map(|category| (category, HashSet::default()))
+    .collect();
+
+    for (&hex, tile) in tiles.iter() {
+        for resource in [&tile.strategic_resource, &tile.trade_resource]
+            .into_iter()
+            .flatten()
+        {
+            placed.get_mut(&resource.category()).unwrap().insert(hex);
+        }
+    }
+
+    placed
+}
+
+/// Puts a resource of `category` on `hex` if its slot is free, its biome
+/// allows one and none of the same category lies within `min_spacing`.
+fn try_place(
+    tiles: &mut HashMap<Hex, Tile>,
+    placed: &mut HashMap<ResourceCategory, HashSet<Hex>>,
+    shape: &MapShape,
+    hex: Hex,
+    category: ResourceCategory,
+    min_spacing: u32,
+    rng: &mut ChaCha8Rng,
+) -> bool {
+    let Some(tile) = tiles.get_mut(&hex) else {
+        return false;
+    };
+
+    let slot = match category {
+        ResourceCategory::Strategic => &mut tile.strategic_resource,
+        ResourceCategory::Luxury | ResourceCategory::Bonus => &mut tile.trade_resource,
+    };
+    if slot.is_some() {
+        return false;
+    }
+
+    let too_close = hex
+        .range(min_spacing.saturating_sub(1))
+        .filter_map(|near| shape.normalize(near))
+        .any(|near| placed[&category].contains(&near));
+    if too_close {
+        return false;
+    }
+
+    let candidates: Vec<(TileResource, u32)> = spawn_table(&tile.biome)
+        .iter()
+        .copied()
+        .filter(|(resource, _)| resource.category() == category)
+        .collect();
+    let Ok((resource, _)) = candidates.choose_weighted(rng, |(_, weight)| *weight) else {
+        return false;
+    };
+
+    *slot = Some(*resource);
+    placed.get_mut(&category).unwrap().insert(hex);
+    true
+}
@@ -0,0 +1,145 @@
+use bevy::{
+    prelude::Resource,
+    utils::{HashMap, HashSet},
+};
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+
+use super::shape::MapShape;
+
+/// Longest river, in edges, before tracing gives up.
+const MAX_RIVER_LENGTH: usize = 200;
+
+/// The border between two neighbouring hexes. The hexes are stored in a fixed
+/// order so that both sides name the same edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HexEdge(Hex, Hex);
+
+impl HexEdge {
+    pub fn new(a: Hex, b: Hex) -> Self {
+        if (a.x, a.y) <= (b.x, b.y) {
+            Self(a, b)
+        } else {
+            Self(b, a)
+        }
+    }
+
+    pub fn hexes(&self) -> [Hex; 2] {
+        [self.0, self.1]
+    }
+}
+
+/// Rivers run along the edges between tiles rather than through them.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct Rivers {
+    pub edges: HashSet<HexEdge>,
+}
+
+impl Rivers {
+    /// Whether moving from `a` to `b` crosses a river.
+    pub fn crosses(&self, a: Hex, b: Hex) -> bool {
+        self.edges.contains(&HexEdge::new(a, b))
+    }
+
+    /// Every hex with a river on at least one of its edges.
+    pub fn banks(&self) -> HashSet<Hex> {
+        self.edges.iter().flat_map(|edge| edge.hexes()).collect()
+    }
+}
+
+/// Traces up to `count` rivers downhill from `sources` until they reach water
+/// or the edge of the map. Water is any elevation below `0.0`.
+pub fn trace_rivers(
+    elevations: &HashMap<Hex, f64>,
+    shape: &MapShape,
+    sources: impl IntoIterator<Item = Hex>,
+    count: u32,
+) -> Rivers {
+    let mut rivers = Rivers::default();
+    let mut banks = HashSet::new();
+    let mut traced = 0;
+
+    for source in sources {
+        if traced == count {
+            break;
+        }
+
+        if banks.contains(&source) {
+            continue;
+        }
+
+        if let Some(edges) = trace_river(source, elevations, shape, &rivers) {
+            banks.extend(edges.iter().flat_map(|edge| edge.hexes()));
+            rivers.edges.extend(edges);
+            traced += 1;
+        }
+    }
+
+    rivers
+}
+
+/// Follows the corners of the map downhill from a corner of `source`. Every
+/// step from one corner to the next runs along the edge between two hexes.
+/// Returns `None` if the river gets stuck in a pit.
+fn trace_river(
+    source: Hex,
+    elevations: &HashMap<Hex, f64>,
+    shape: &MapShape,
+    rivers: &Rivers,
+) -> Option<Vec<HexEdge>> {
+    // tiles past the edge of the map count as sea
+    let elevation_of = |hex: Hex| {
+        shape
+            .normalize(hex)
+            .and_then(|hex| elevations.get(&hex).copied())
+            .unwrap_or(-1.0)
+    };
+    let corner_elevation =
+        |corner: &[Hex; 3]| corner.iter().map(|&hex| elevation_of(hex)).sum::<f64>() / 3.0;
+
+    let first = source.all_neighbors()[0];
+    let second = common_neighbors(source, first)[0];
+    let mut corner = [source, first, second];
+    let mut edges = vec![];
+
+    for _ in 0..MAX_RIVER_LENGTH {
+        if corner.iter().any(|&hex| elevation_of(hex) < 0.0) {
+            return (!edges.is_empty()).then_some(edges);
+        }
+
+        let (next, a, b) = (0..3)
+            .map(|i| {
+                let (a, b, c) = (corner[i], corner[(i + 1) % 3], corner[(i + 2) % 3]);
+                let [first, second] = common_neighbors(a, b);
+                let d = if first == c { second } else { first };
+                ([a, b, d], a, b)
+            })
+            .min_by(|(x, ..), (y, ..)| corner_elevation(x).total_cmp(&corner_elevation(y)))?;
+
+        if corner_elevation(&next) >= corner_elevation(&corner) {
+            return None;
+        }
+
+        let edge = HexEdge::new(shape.wrap(a), shape.wrap(b));
+        edges.push(edge);
+
+        // flowing into another river ends this one
+        if rivers.edges.contains(&edge) {
+            return Some(edges);
+        }
+
+        corner = next;
+    }
+
+    None
+}
+
+/// The two hexes next to both `a` and `b`, which must be neighbours.
+fn common_neighbors(a: Hex, b: Hex) -> [Hex; 2] {
+    let mut shared = a
+        .all_neighbors()
+        .into_iter()
+        .filter(|neighbor| neighbor.unsigned_distance_to(b) == 1);
+
+    [shared.next().unwrap(), shared.next().unwrap()]
+}
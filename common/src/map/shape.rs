@@ -0,0 +1,140 @@
+use bevy::reflect::Reflect;
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+
+/// Outline of the map. Rectangles use pointy "odd-r" offset coordinates and
+/// are centered on `Hex::ZERO`.
+#[derive(Debug, Clone, Copy, Reflect, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MapShape {
+    Hexagon {
+        radius: u32,
+    },
+    Rectangle {
+        width: u32,
+        height: u32,
+        /// Wraps east-west, so the map is a cylinder.
+        wrap_x: bool,
+    },
+}
+
+impl Default for MapShape {
+    fn default() -> Self {
+        MapShape::Hexagon { radius: 80 }
+    }
+}
+
+impl MapShape {
+    /// Every hex of the map, always in the same order.
+    pub fn hexes(&self) -> Vec<Hex> {
+        match *self {
+            MapShape::Hexagon { radius } => Hex::ZERO.spiral_range(0..=radius).collect(),
+            MapShape::Rectangle { width, height, .. } => {
+                let (first_col, first_row) = rectangle_origin(width, height);
+                (first_row..first_row + height as i32)
+                    .flat_map(|row| {
+                        (first_col..first_col + width as i32)
+                            .map(move |col| from_offset_coordinates([col, row]))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    pub fn wraps(&self) -> bool {
+        matches!(self, MapShape::Rectangle { wrap_x: true, .. })
+    }
+
+    /// Number of columns after which a wrapping map repeats itself.
+    pub fn wrap_width(&self) -> Option<u32> {
+        match *self {
+            MapShape::Rectangle {
+                width,
+                wrap_x: true,
+                ..
+            } => Some(width),
+            _ => None,
+        }
+    }
+
+    /// Moves `hex` back onto the map if it lies past the seam of a wrapping
+    /// map. Any other hex is returned unchanged.
+    pub fn wrap(&self, hex: Hex) -> Hex {
+        let MapShape::Rectangle {
+            width,
+            height,
+            wrap_x: true,
+        } = *self
+        else {
+            return hex;
+        };
+
+        let (first_col, _) = rectangle_origin(width, height);
+        let [col, row] = to_offset_coordinates(hex);
+        let col = first_col + (col - first_col).rem_euclid(width as i32);
+
+        from_offset_coordinates([col, row])
+    }
+
+    pub fn contains(&self, hex: Hex) -> bool {
+        match *self {
+            MapShape::Hexagon { radius } => hex.unsigned_distance_to(Hex::ZERO) <= radius,
+            MapShape::Rectangle { width, height, .. } => {
+                let (first_col, first_row) = rectangle_origin(width, height);
+                let [col, row] = to_offset_coordinates(hex);
+
+                (first_col..first_col + width as i32).contains(&col)
+                    && (first_row..first_row + height as i32).contains(&row)
+            }
+        }
+    }
+
+    /// The on-map hex `hex` refers to, wrapping across the seam if needed.
+    pub fn normalize(&self, hex: Hex) -> Option<Hex> {
+        let hex = self.wrap(hex);
+        self.contains(hex).then_some(hex)
+    }
+
+    /// The copy of `to` closest to `from`. On a wrapping map this may lie
+    /// past the seam, which is what pathfinding needs to cross it.
+    pub fn nearest(&self, from: Hex, to: Hex) -> Hex {
+        let Some(width) = self.wrap_width() else {
+            return to;
+        };
+
+        let to = self.wrap(to);
+        [-1, 0, 1]
+            .into_iter()
+            .map(|copy| to + Hex::new(copy * width as i32, 0))
+            .min_by_key(|candidate| from.unsigned_distance_to(*candidate))
+            .unwrap_or(to)
+    }
+
+    pub fn distance(&self, a: Hex, b: Hex) -> u32 {
+        a.unsigned_distance_to(self.nearest(a, b))
+    }
+
+    /// How far from its start a search may stray. Wrapping maps are infinite
+    /// once unrolled, so a search for an unreachable hex must be bounded.
+    pub fn search_limit(&self) -> Option<u32> {
+        match *self {
+            MapShape::Rectangle {
+                width,
+                height,
+                wrap_x: true,
+            } => Some(width + height),
+            _ => None,
+        }
+    }
+}
+
+fn rectangle_origin(width: u32, height: u32) -> (i32, i32) {
+    (-(width as i32 / 2), -(height as i32 / 2))
+}
+
+fn to_offset_coordinates(hex: Hex) -> [i32; 2] {
+    [hex.x + (hex.y - (hex.y & 1)) / 2, hex.y]
+}
+
+fn from_offset_coordinates([col, row]: [i32; 2]) -> Hex {
+    Hex::new(col - (row - (row & 1)) / 2, row)
+}
@@ -0,0 +1,184 @@
+use bevy::{prelude::Resource, utils::HashMap};
+use hexx::Hex;
+
+use super::{
+    components::{ResourceCategory, Tile},
+    shape::MapShape,
+};
+
+/// Worth of a resource in the area around a start position, in the same units
+/// as a tile's averaged attributes.
+const STRATEGIC_RESOURCE_SCORE: f64 = 20.0;
+const LUXURY_RESOURCE_SCORE: f64 = 15.0;
+const BONUS_RESOURCE_SCORE: f64 = 10.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StartPosition {
+    pub hex: Hex,
+    /// How good the area around the start is, see `score_start`.
+    pub score: f64,
+}
+
+/// Where each player starts, in player order.
+#[derive(Debug, Clone, Default, PartialEq, Resource)]
+pub struct StartPositions {
+    pub positions: Vec<StartPosition>,
+    /// Worst start score divided by the best, `1.0` means every player starts
+    /// equally well off.
+    pub fairness: f64,
+}
+
+impl StartPositions {
+    pub fn hexes(&self) -> Vec<Hex> {
+        self.positions.iter().map(|start| start.hex).collect()
+    }
+}
+
+/// Picks up to `players` land tiles that can be walked on, as far apart as
+/// the map allows, preferring the ones with the best surroundings. Fewer are
+/// returned only if the map has fewer such tiles than players.
+pub fn find_start_positions(
+    tiles: &HashMap<Hex, Tile>,
+    shape: &MapShape,
+    hexes: &[Hex],
+    players: u32,
+    radius: u32,
+) -> Vec<Hex> {
+    let players = players as usize;
+    if players == 0 {
+        return vec![];
+    }
+
+    let mut candidates: Vec<(Hex, f64)> = hexes
+        .iter()
+        .filter(|&hex| {
+            tiles
+                .get(hex)
+                .is_some_and(|tile| !tile.biome.is_water() && tile.cost().is_some())
+        })
+        .map(|&hex| (hex, score_start(tiles, shape, hex, radius)))
+        .collect();
+    // `hexes` comes in a fixed order, so ties keep it and the result is stable
+    candidates.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    let scores: HashMap<Hex, f64> = candidates.iter().copied().collect();
+    let worst_score = |chosen: &[Hex]| {
+        chosen
+            .iter()
+            .map(|hex| scores[hex])
+            .fold(f64::MAX, f64::min)
+    };
+
+    // find the widest spacing everyone fits at, then give up a little of it
+    // if that lets the worst placed player start somewhere better
+    let max_spacing = (2.0 * (candidates.len() as f64 / players as f64).sqrt()) as u32;
+    let mut best: Vec<Hex> = vec![];
+    let mut widest_fit = None;
+
+    for spacing in (1..=max_spacing.max(1)).rev() {
+        if widest_fit.is_some_and(|widest| spacing * 4 < widest * 3) {
+            break;
+        }
+
+        let chosen = spread_out(&candidates, shape, players, spacing);
+
+        if chosen.len() == players {
+            widest_fit.get_or_insert(spacing);
+            if best.len() < players || worst_score(&chosen) > worst_score(&best) {
+                best = chosen;
+            }
+        } else if chosen.len() > best.len() {
+            best = chosen;
+        }
+    }
+
+    best
+}
+
+/// Greedily takes the best scoring candidates that are at least `spacing`
+/// away from every one taken before.
+fn spread_out(
+    candidates: &[(Hex, f64)],
+    shape: &MapShape,
+    players: usize,
+    spacing: u32,
+) -> Vec<Hex> {
+    let mut chosen: Vec<Hex> = vec![];
+
+    for &(hex, _) in candidates.iter() {
+        if chosen.len() == players {
+            break;
+        }
+
+        if chosen
+            .iter()
+            .all(|&other| shape.distance(hex, other) >= spacing)
+        {
+            chosen.push(hex);
+        }
+    }
+
+    chosen
+}
+
+/// Scores each start and how evenly matched they are.
+pub fn score_starts(
+    tiles: &HashMap<Hex, Tile>,
+    shape: &MapShape,
+    starts: &[Hex],
+    radius: u32,
+) -> StartPositions {
+    let positions: Vec<StartPosition> = starts
+        .iter()
+        .map(|&hex| StartPosition {
+            hex,
+            score: score_start(tiles, shape, hex, radius),
+        })
+        .collect();
+
+    let worst = positions
+        .iter()
+        .map(|start| start.score)
+        .fold(f64::MAX, f64::min);
+    let best = positions
+        .iter()
+        .map(|start| start.score)
+        .fold(0.0, f64::max);
+    let fairness = if positions.is_empty() || best <= 0.0 {
+        1.0
+    } else {
+        worst / best
+    };
+
+    StartPositions {
+        positions,
+        fairness,
+    }
+}
+
+/// Sums the averaged attributes and the resources of every walkable tile
+/// within `radius` of `hex`.
+pub fn score_start(tiles: &HashMap<Hex, Tile>, shape: &MapShape, hex: Hex, radius: u32) -> f64 {
+    hex.range(radius)
+        .filter_map(|near| shape.normalize(near))
+        .filter_map(|near| tiles.get(&near))
+        .filter(|tile| tile.cost().is_some())
+        .map(|tile| {
+            let attributes = &tile.attributes;
+            let yields =
+                (attributes.production + attributes.science + attributes.attractiveness) as f64;
+
+            let resources: f64 = [&tile.strategic_resource, &tile.trade_resource]
+                .into_iter()
+                .flatten()
+                .map(|resource| match resource.category() {
+                    ResourceCategory::Strategic => STRATEGIC_RESOURCE_SCORE,
+                    ResourceCategory::Luxury => LUXURY_RESOURCE_SCORE,
+                    ResourceCategory::Bonus => BONUS_RESOURCE_SCORE,
+                })
+                .sum();
+
+            yields / 3.0 + resources
+        })
+        .sum()
+}
@@ -0,0 +1,86 @@
+//! Frames are a little endian `u32` length followed by that many bytes of
+//! bincode.
+
+use std::{
+    fmt,
+    io::{self, Read, Write},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Largest frame accepted, so a bad length can't make us allocate gigabytes.
+pub const MAX_FRAME_LEN: u32 = 16 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum CodecError {
+    Io(io::Error),
+    Bincode(bincode::Error),
+    FrameTooLarge(u32),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::Io(error) => write!(f, "connection failed: {error}"),
+            CodecError::Bincode(error) => write!(f, "malformed message: {error}"),
+            CodecError::FrameTooLarge(len) => write!(
+                f,
+                "frame of {len} bytes is larger than the limit of {MAX_FRAME_LEN}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+impl From<io::Error> for CodecError {
+    fn from(error: io::Error) -> Self {
+        CodecError::Io(error)
+    }
+}
+
+impl From<bincode::Error> for CodecError {
+    fn from(error: bincode::Error) -> Self {
+        CodecError::Bincode(error)
+    }
+}
+
+impl CodecError {
+    /// Whether the other side simply went away.
+    pub fn is_disconnect(&self) -> bool {
+        matches!(self, CodecError::Io(error) if error.kind() == io::ErrorKind::UnexpectedEof)
+    }
+}
+
+pub fn encode<T: Serialize>(message: &T) -> Result<Vec<u8>, CodecError> {
+    let body = bincode::serialize(message)?;
+    let len = u32::try_from(body.len()).unwrap_or(u32::MAX);
+    if len > MAX_FRAME_LEN {
+        return Err(CodecError::FrameTooLarge(len));
+    }
+
+    let mut frame = Vec::with_capacity(4 + body.len());
+    frame.extend_from_slice(&len.to_le_bytes());
+    frame.extend_from_slice(&body);
+    Ok(frame)
+}
+
+pub fn write_frame<T: Serialize>(writer: &mut impl Write, message: &T) -> Result<(), CodecError> {
+    writer.write_all(&encode(message)?)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Blocks until a whole frame has arrived.
+pub fn read_frame<T: DeserializeOwned>(reader: &mut impl Read) -> Result<T, CodecError> {
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len);
+    if len > MAX_FRAME_LEN {
+        return Err(CodecError::FrameTooLarge(len));
+    }
+
+    let mut body = vec![0; len as usize];
+    reader.read_exact(&mut body)?;
+    Ok(bincode::deserialize(&body)?)
+}
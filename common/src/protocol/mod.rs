@@ -0,0 +1,164 @@
+//! Messages exchanged between the game server and its clients. Every message
+//! travels as one frame, see `codec`.
+//!
+//! A client opens with a `Hello` carrying its `PROTOCOL_VERSION`. The server
+//! answers with `Welcome` if it speaks the same version, after which the
+//! client sends `ClientMessage`s and receives `ServerMessage`s. Clients start
+//! out in the lobby, where the host sets up the game, and `GameStarted` is
+//! sent once every human player is ready.
+
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    save::SavedMap,
+    setup::{GameSetup, SlotKind},
+};
+
+pub mod codec;
+
+/// Version of the messages below. Bump it whenever one of them changes, there
+/// is no translation between versions.
+pub const PROTOCOL_VERSION: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PlayerId(pub u32);
+
+/// The player who may change the setup in the lobby.
+pub const HOST: PlayerId = PlayerId(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct HeroId(pub u32);
+
+/// First frame of every connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hello {
+    pub protocol_version: u32,
+}
+
+impl Default for Hello {
+    fn default() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+        }
+    }
+}
+
+/// What a client asks the server to do.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// Only the host may change the setup, which unreadies everyone.
+    ChangeSetup(GameSetup),
+    SetReady(bool),
+    SelectHero {
+        hero: HeroId,
+    },
+    DeselectHero,
+    MoveHero {
+        hero: HeroId,
+        to: Hex,
+    },
+    EndTurn,
+}
+
+/// What the server tells its clients. Apart from the answers to `Hello` these
+/// are deltas to apply to the state the client was welcomed with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// Answers a `Hello` from another protocol version. The server hangs up
+    /// right after.
+    VersionMismatch {
+        server_version: u32,
+    },
+    /// Answers a `Hello` the server accepted.
+    Welcome {
+        player: PlayerId,
+        lobby: LobbyState,
+    },
+    /// Someone joined, left, got ready or the setup changed.
+    LobbyChanged(LobbyState),
+    /// Sent when the game starts, and right after `Welcome` to players joining
+    /// a running game.
+    GameStarted(Box<GameSnapshot>),
+    /// The hero walked `path`, which ends on its new hex, and is done moving
+    /// for this turn.
+    HeroMoved {
+        hero: HeroId,
+        path: Vec<Hex>,
+    },
+    TurnStarted {
+        player: PlayerId,
+        turn: u32,
+    },
+    /// The turn limit was reached.
+    GameOver,
+    /// Only sent to the client whose command was refused.
+    Rejected(Rejection),
+}
+
+/// The lobby as the server sees it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LobbyState {
+    pub setup: GameSetup,
+    /// One seat per slot of the setup.
+    pub seats: Vec<Seat>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Seat {
+    pub player: PlayerId,
+    pub kind: SlotKind,
+    /// Whether a client sits here. AI seats are never taken.
+    pub taken: bool,
+    pub ready: bool,
+}
+
+/// The whole game as the server sees it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    pub map: SavedMap,
+    pub heroes: Vec<HeroState>,
+    pub players: Vec<PlayerId>,
+    pub active_player: PlayerId,
+    pub turn: u32,
+    pub max_turns: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeroState {
+    pub id: HeroId,
+    pub owner: PlayerId,
+    pub hex: Hex,
+    pub movement_points: u32,
+    pub has_moved: bool,
+}
+
+/// Why the server refused a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rejection {
+    NotYourTurn,
+    UnknownHero,
+    NotYourHero,
+    AlreadyMoved,
+    /// No path leads to the target.
+    Unreachable,
+    /// The target is further away than the hero's movement points allow.
+    OutOfRange,
+    /// Another hero stands on the target.
+    Occupied,
+    /// Every player slot is taken.
+    GameFull,
+    NotHost,
+    InvalidSetup,
+    /// The server only plays turns one after another.
+    UnsupportedSetup,
+    /// The map has fewer start positions than the setup has players.
+    TooFewStartPositions,
+    /// The new setup would take the seat of a connected player.
+    SeatTaken,
+    /// Game commands sent from the lobby.
+    GameNotStarted,
+    /// Lobby commands sent during the game.
+    GameAlreadyStarted,
+    GameOver,
+}
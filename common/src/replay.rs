@@ -0,0 +1,173 @@
+//! Logs of what players did during a game. Together with the setup, which
+//! holds the map seed, a log plays the same game again.
+
+use std::{fmt, fs, io, path::Path};
+
+use hexx::Hex;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    protocol::{HeroId, PlayerId},
+    setup::GameSetup,
+};
+
+/// Version written into new logs. Old logs are not migrated, a log only
+/// replays on the version that recorded it anyway.
+pub const LOG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandLog {
+    pub version: u32,
+    /// The setup the game started from, the map is generated again from it.
+    pub setup: GameSetup,
+    pub commands: Vec<LoggedCommand>,
+    /// `world_hash` of the game when the log was written.
+    pub final_hash: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoggedCommand {
+    pub turn: u32,
+    pub player: PlayerId,
+    pub action: LoggedAction,
+}
+
+/// An action as it is logged. Heroes are numbered in the order of the start
+/// positions, the same way the server numbers them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LoggedAction {
+    Move {
+        hero: HeroId,
+        to: Hex,
+    },
+    Attack {
+        attacker: HeroId,
+        target: HeroId,
+    },
+    Wait {
+        hero: HeroId,
+    },
+    /// Drops what the hero planned but didn't carry out yet.
+    Cancel {
+        hero: HeroId,
+    },
+    /// Sets the hero off towards `to`, further than it gets in one turn. The
+    /// way there is found from where the hero stands, the moves along it in
+    /// later turns follow from it and aren't logged.
+    Journey {
+        hero: HeroId,
+        to: Hex,
+    },
+    /// Makes the hero give up its journey.
+    EndJourney {
+        hero: HeroId,
+    },
+    EndTurn,
+    Undo,
+    Redo,
+}
+
+#[derive(Debug)]
+pub enum LogError {
+    Io(io::Error),
+    Serialize(ron::Error),
+    Deserialize(ron::error::SpannedError),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogError::Io(error) => write!(f, "could not access command log: {error}"),
+            LogError::Serialize(error) => write!(f, "could not write command log: {error}"),
+            LogError::Deserialize(error) => write!(f, "could not read command log: {error}"),
+            LogError::UnsupportedVersion(version) => write!(
+                f,
+                "command log version {version} is not supported, only {LOG_VERSION} is"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LogError {}
+
+impl From<io::Error> for LogError {
+    fn from(error: io::Error) -> Self {
+        LogError::Io(error)
+    }
+}
+
+impl From<ron::Error> for LogError {
+    fn from(error: ron::Error) -> Self {
+        LogError::Serialize(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for LogError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        LogError::Deserialize(error)
+    }
+}
+
+impl CommandLog {
+    pub fn new(setup: GameSetup) -> Self {
+        Self {
+            version: LOG_VERSION,
+            setup,
+            commands: Vec::new(),
+            final_hash: None,
+        }
+    }
+
+    pub fn record(&mut self, turn: u32, player: PlayerId, action: LoggedAction) {
+        self.commands.push(LoggedCommand {
+            turn,
+            player,
+            action,
+        });
+    }
+
+    pub fn to_ron(&self) -> Result<String, LogError> {
+        Ok(ron::ser::to_string_pretty(self, PrettyConfig::default())?)
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, LogError> {
+        let log: CommandLog = ron::from_str(text)?;
+        if log.version != LOG_VERSION {
+            return Err(LogError::UnsupportedVersion(log.version));
+        }
+
+        Ok(log)
+    }
+
+    /// Writes the log to `path`, creating its directory if needed.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), LogError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, LogError> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+}
+
+/// Hash of everything in `world`, the same on every machine and every run.
+/// Two games that ended up the same have the same hash, as long as `world`
+/// keeps its collections in a fixed order.
+pub fn world_hash(world: &impl Serialize) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+    let bytes =
+        bincode::serialize(world).expect("world_hash only takes types bincode can serialize");
+
+    bytes.iter().fold(FNV_OFFSET, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
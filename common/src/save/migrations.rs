@@ -0,0 +1,744 @@
+//! Older save formats and the steps that upgrade them. Each old version reads
+//! into its own copies of the structs it wrote, so changing the current ones
+//! never changes how an old save is read. When a saved struct changes, copy
+//! its previous shape into a module for the old version here and add a step
+//! from that version to the next.
+
+use serde::Deserialize;
+
+use super::{SaveError, SaveGame, SaveHeader, SavedHero, SavedTurn};
+use crate::{
+    protocol::PlayerId,
+    setup::{default_color, default_name},
+};
+
+/// Version 1 had no header, the version was a field of the save itself.
+mod v1 {
+    use serde::Deserialize;
+
+    use super::{v2::SavedHero, v3::SavedTurn, v4::SavedMap};
+
+    #[derive(Debug, Deserialize)]
+    pub struct SaveGame {
+        pub map: SavedMap,
+        pub heroes: Vec<SavedHero>,
+        pub turn: SavedTurn,
+    }
+}
+
+/// Version 2 had a single player, so heroes had no owner.
+mod v2 {
+    use hexx::Hex;
+    use serde::Deserialize;
+
+    use super::{
+        v3::SavedTurn,
+        v4::{SavedHealth, SavedMap, SavedUnit},
+    };
+
+    #[derive(Debug, Deserialize)]
+    pub struct SaveGame {
+        pub map: SavedMap,
+        pub heroes: Vec<SavedHero>,
+        pub turn: SavedTurn,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SavedHero {
+        pub hex: Hex,
+        pub experience: u32,
+        pub level: u32,
+        pub health: SavedHealth,
+        pub movement_points: u32,
+        pub has_moved: bool,
+        pub max_units: u32,
+        pub units: Vec<Option<SavedUnit>>,
+    }
+}
+
+/// Version 3 only knew whether a human or the AI played each seat, and had
+/// a gameplay state for each of the eight players.
+mod v3 {
+    use serde::Deserialize;
+
+    use super::v4::{SavedHero, SavedMap, SavedPlayerKind};
+
+    #[derive(Debug, Deserialize)]
+    pub struct SaveGame {
+        pub map: SavedMap,
+        pub players: Vec<SavedPlayerKind>,
+        pub heroes: Vec<SavedHero>,
+        pub turn: SavedTurn,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SavedTurn {
+        pub current_turn: u32,
+        pub max_turns: u32,
+        pub state: SavedGameplayState,
+    }
+
+    #[derive(Debug, Clone, Copy, Deserialize)]
+    pub enum SavedGameplayState {
+        Player1Turn,
+        Player2Turn,
+        Player3Turn,
+        Player4Turn,
+        Player5Turn,
+        Player6Turn,
+        Player7Turn,
+        Player8Turn,
+        TurnTransition,
+        GameOver,
+    }
+}
+
+/// Version 4 always played one turn after another. The map, heroes and
+/// players it saved are kept here as every version up to 5 wrote them.
+mod v4 {
+    use hexx::Hex;
+    use serde::Deserialize;
+
+    use crate::{
+        map::{components, generation, placement, rivers::HexEdge, shape},
+        save,
+    };
+
+    #[derive(Debug, Deserialize)]
+    pub struct SaveGame {
+        pub map: SavedMap,
+        pub players: Vec<SavedPlayer>,
+        pub heroes: Vec<SavedHero>,
+        pub turn: SavedTurn,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SavedTurn {
+        pub current_turn: u32,
+        pub max_turns: u32,
+        pub state: SavedGameplayState,
+    }
+
+    #[derive(Debug, Clone, Copy, Deserialize)]
+    pub enum SavedGameplayState {
+        PlayerTurn(u32),
+        TurnTransition,
+        GameOver,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SavedPlayer {
+        pub id: u32,
+        pub name: String,
+        pub color: [f32; 3],
+        pub team: u32,
+        pub kind: SavedPlayerKind,
+    }
+
+    #[derive(Debug, Clone, Copy, Deserialize)]
+    pub enum SavedPlayerKind {
+        Human,
+        Ai,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SavedHero {
+        pub owner: u32,
+        pub hex: Hex,
+        pub experience: u32,
+        pub level: u32,
+        pub health: SavedHealth,
+        pub movement_points: u32,
+        pub has_moved: bool,
+        pub max_units: u32,
+        pub units: Vec<Option<SavedUnit>>,
+    }
+
+    #[derive(Debug, Clone, Copy, Deserialize)]
+    pub struct SavedHealth {
+        pub current: u32,
+        pub max: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SavedUnit {
+        pub unit_type: SavedUnitType,
+        pub health: Option<SavedHealth>,
+        pub attack: Option<i32>,
+        pub defense: Option<i32>,
+        pub range: Option<u32>,
+        pub movement_points: Option<u32>,
+    }
+
+    #[derive(Debug, Clone, Copy, Deserialize)]
+    pub enum SavedUnitType {
+        Melee,
+        Ranged,
+        Support,
+        Naval,
+        Air,
+        Siege,
+        Cavalry,
+        Artillery,
+        Armor,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SavedMap {
+        pub generator: GeneratorSettings,
+        pub tiles: Vec<(Hex, Tile)>,
+        pub rivers: Vec<(Hex, Hex)>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct GeneratorSettings {
+        pub seed: u64,
+        pub shape: MapShape,
+        pub map_type: MapType,
+        pub land_ratio: f64,
+        pub elevation: NoiseSettings,
+        pub moisture: NoiseSettings,
+        pub river_count: u32,
+        pub resources: ResourceSettings,
+        pub player_count: u32,
+    }
+
+    #[derive(Debug, Clone, Copy, Deserialize)]
+    pub enum MapShape {
+        Hexagon {
+            radius: u32,
+        },
+        Rectangle {
+            width: u32,
+            height: u32,
+            wrap_x: bool,
+        },
+    }
+
+    #[derive(Debug, Clone, Copy, Deserialize)]
+    pub enum MapType {
+        Continents,
+        Pangaea,
+        Archipelago,
+        InlandSea,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct NoiseSettings {
+        pub frequency: f64,
+        pub octaves: usize,
+        pub lacunarity: f64,
+        pub persistence: f64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct ResourceSettings {
+        pub strategic_frequency: f64,
+        pub luxury_frequency: f64,
+        pub bonus_frequency: f64,
+        pub min_spacing: u32,
+        pub start_radius: u32,
+        pub strategic_near_start: u32,
+        pub luxury_near_start: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct Tile {
+        pub biome: Biome,
+        pub attributes: TileAttributes,
+        pub strategic_resource: Option<TileResource>,
+        pub trade_resource: Option<TileResource>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct TileAttributes {
+        pub production: i32,
+        pub science: i32,
+        pub attractiveness: i32,
+    }
+
+    #[derive(Debug, Clone, Copy, Deserialize)]
+    pub enum Biome {
+        Mountain,
+        Plains,
+        Forest,
+        Desert,
+        ShallowWater,
+        DeepWater,
+        Snow,
+    }
+
+    #[derive(Debug, Clone, Copy, Deserialize)]
+    pub enum TileResource {
+        Wood,
+        Stone,
+        Iron,
+        Nitre,
+        Coal,
+        Oil,
+        Uranium,
+        Tea,
+        Marble,
+        Salt,
+        Copper,
+        Diamond,
+        Ivory,
+        Banana,
+        Wheat,
+        Rice,
+        Sugar,
+        Spices,
+    }
+
+    impl From<SavedGameplayState> for save::SavedGameplayState {
+        fn from(state: SavedGameplayState) -> Self {
+            match state {
+                SavedGameplayState::PlayerTurn(player) => Self::PlayerTurn(player),
+                SavedGameplayState::TurnTransition => Self::TurnTransition,
+                SavedGameplayState::GameOver => Self::GameOver,
+            }
+        }
+    }
+
+    impl From<SavedPlayer> for save::SavedPlayer {
+        fn from(player: SavedPlayer) -> Self {
+            Self {
+                id: player.id,
+                name: player.name,
+                color: player.color,
+                team: player.team,
+                kind: player.kind.into(),
+            }
+        }
+    }
+
+    impl From<SavedPlayerKind> for save::SavedPlayerKind {
+        fn from(kind: SavedPlayerKind) -> Self {
+            match kind {
+                SavedPlayerKind::Human => Self::Human,
+                SavedPlayerKind::Ai => Self::Ai,
+            }
+        }
+    }
+
+    impl From<SavedHealth> for save::SavedHealth {
+        fn from(health: SavedHealth) -> Self {
+            Self {
+                current: health.current,
+                max: health.max,
+            }
+        }
+    }
+
+    impl From<SavedUnit> for save::SavedUnit {
+        fn from(unit: SavedUnit) -> Self {
+            Self {
+                unit_type: unit.unit_type.into(),
+                health: unit.health.map(Into::into),
+                attack: unit.attack,
+                defense: unit.defense,
+                range: unit.range,
+                movement_points: unit.movement_points,
+            }
+        }
+    }
+
+    impl From<SavedUnitType> for save::SavedUnitType {
+        fn from(unit_type: SavedUnitType) -> Self {
+            match unit_type {
+                SavedUnitType::Melee => Self::Melee,
+                SavedUnitType::Ranged => Self::Ranged,
+                SavedUnitType::Support => Self::Support,
+                SavedUnitType::Naval => Self::Naval,
+                SavedUnitType::Air => Self::Air,
+                SavedUnitType::Siege => Self::Siege,
+                SavedUnitType::Cavalry => Self::Cavalry,
+                SavedUnitType::Artillery => Self::Artillery,
+                SavedUnitType::Armor => Self::Armor,
+            }
+        }
+    }
+
+    impl From<SavedMap> for save::SavedMap {
+        fn from(map: SavedMap) -> Self {
+            Self {
+                generator: map.generator.into(),
+                tiles: map
+                    .tiles
+                    .into_iter()
+                    .map(|(hex, tile)| (hex, tile.into()))
+                    .collect(),
+                rivers: map
+                    .rivers
+                    .into_iter()
+                    .map(|(a, b)| HexEdge::new(a, b))
+                    .collect(),
+            }
+        }
+    }
+
+    impl From<GeneratorSettings> for generation::GeneratorSettings {
+        fn from(settings: GeneratorSettings) -> Self {
+            Self {
+                seed: settings.seed,
+                shape: settings.shape.into(),
+                map_type: settings.map_type.into(),
+                land_ratio: settings.land_ratio,
+                elevation: settings.elevation.into(),
+                moisture: settings.moisture.into(),
+                river_count: settings.river_count,
+                resources: settings.resources.into(),
+                player_count: settings.player_count,
+            }
+        }
+    }
+
+    impl From<MapShape> for shape::MapShape {
+        fn from(shape: MapShape) -> Self {
+            match shape {
+                MapShape::Hexagon { radius } => Self::Hexagon { radius },
+                MapShape::Rectangle {
+                    width,
+                    height,
+                    wrap_x,
+                } => Self::Rectangle {
+                    width,
+                    height,
+                    wrap_x,
+                },
+            }
+        }
+    }
+
+    impl From<MapType> for generation::MapType {
+        fn from(map_type: MapType) -> Self {
+            match map_type {
+                MapType::Continents => Self::Continents,
+                MapType::Pangaea => Self::Pangaea,
+                MapType::Archipelago => Self::Archipelago,
+                MapType::InlandSea => Self::InlandSea,
+            }
+        }
+    }
+
+    impl From<NoiseSettings> for generation::NoiseSettings {
+        fn from(noise: NoiseSettings) -> Self {
+            Self {
+                frequency: noise.frequency,
+                octaves: noise.octaves,
+                lacunarity: noise.lacunarity,
+                persistence: noise.persistence,
+            }
+        }
+    }
+
+    impl From<ResourceSettings> for placement::ResourceSettings {
+        fn from(resources: ResourceSettings) -> Self {
+            Self {
+                strategic_frequency: resources.strategic_frequency,
+                luxury_frequency: resources.luxury_frequency,
+                bonus_frequency: resources.bonus_frequency,
+                min_spacing: resources.min_spacing,
+                start_radius: resources.start_radius,
+                strategic_near_start: resources.strategic_near_start,
+                luxury_near_start: resources.luxury_near_start,
+            }
+        }
+    }
+
+    impl From<Tile> for components::Tile {
+        fn from(tile: Tile) -> Self {
+            Self {
+                biome: tile.biome.into(),
+                attributes: components::TileAttributes {
+                    production: tile.attributes.production,
+                    science: tile.attributes.science,
+                    attractiveness: tile.attributes.attractiveness,
+                },
+                strategic_resource: tile.strategic_resource.map(Into::into),
+                trade_resource: tile.trade_resource.map(Into::into),
+            }
+        }
+    }
+
+    impl From<Biome> for components::Biome {
+        fn from(biome: Biome) -> Self {
+            match biome {
+                Biome::Mountain => Self::Mountain,
+                Biome::Plains => Self::Plains,
+                Biome::Forest => Self::Forest,
+                Biome::Desert => Self::Desert,
+                Biome::ShallowWater => Self::ShallowWater,
+                Biome::DeepWater => Self::DeepWater,
+                Biome::Snow => Self::Snow,
+            }
+        }
+    }
+
+    impl From<TileResource> for components::TileResource {
+        fn from(resource: TileResource) -> Self {
+            match resource {
+                TileResource::Wood => Self::Wood,
+                TileResource::Stone => Self::Stone,
+                TileResource::Iron => Self::Iron,
+                TileResource::Nitre => Self::Nitre,
+                TileResource::Coal => Self::Coal,
+                TileResource::Oil => Self::Oil,
+                TileResource::Uranium => Self::Uranium,
+                TileResource::Tea => Self::Tea,
+                TileResource::Marble => Self::Marble,
+                TileResource::Salt => Self::Salt,
+                TileResource::Copper => Self::Copper,
+                TileResource::Diamond => Self::Diamond,
+                TileResource::Ivory => Self::Ivory,
+                TileResource::Banana => Self::Banana,
+                TileResource::Wheat => Self::Wheat,
+                TileResource::Rice => Self::Rice,
+                TileResource::Sugar => Self::Sugar,
+                TileResource::Spices => Self::Spices,
+            }
+        }
+    }
+}
+
+/// Version 5 didn't keep what heroes planned, where they were headed or the
+/// action points they had left.
+mod v5 {
+    use serde::Deserialize;
+
+    use super::v4::{SavedGameplayState, SavedHero, SavedMap, SavedPlayer};
+    use crate::save;
+
+    #[derive(Debug, Deserialize)]
+    pub struct SaveGame {
+        pub map: SavedMap,
+        pub players: Vec<SavedPlayer>,
+        pub heroes: Vec<SavedHero>,
+        pub turn: SavedTurn,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SavedTurn {
+        pub current_turn: u32,
+        pub max_turns: u32,
+        pub mode: SavedTurnMode,
+        pub state: SavedGameplayState,
+    }
+
+    #[derive(Debug, Clone, Copy, Deserialize)]
+    pub enum SavedTurnMode {
+        Sequential,
+        Simultaneous,
+    }
+
+    impl From<SavedTurnMode> for save::SavedTurnMode {
+        fn from(mode: SavedTurnMode) -> Self {
+            match mode {
+                SavedTurnMode::Sequential => Self::Sequential,
+                SavedTurnMode::Simultaneous => Self::Simultaneous,
+            }
+        }
+    }
+}
+
+/// Just enough of a save to tell which version wrote it.
+#[derive(Debug, Deserialize)]
+struct HeaderProbe {
+    header: SaveHeader,
+}
+
+/// The same for saves from before the header existed.
+#[derive(Debug, Deserialize)]
+struct LegacyProbe {
+    version: u32,
+}
+
+/// A save as some version wrote it, oldest first.
+enum VersionedSave {
+    V1(v1::SaveGame),
+    V2(v2::SaveGame),
+    V3(v3::SaveGame),
+    V4(v4::SaveGame),
+    V5(v5::SaveGame),
+    V6(SaveGame),
+}
+
+impl VersionedSave {
+    fn parse(text: &str, version: u32) -> Result<Self, SaveError> {
+        match version {
+            1 => Ok(VersionedSave::V1(ron::from_str(text)?)),
+            2 => Ok(VersionedSave::V2(ron::from_str(text)?)),
+            3 => Ok(VersionedSave::V3(ron::from_str(text)?)),
+            4 => Ok(VersionedSave::V4(ron::from_str(text)?)),
+            5 => Ok(VersionedSave::V5(ron::from_str(text)?)),
+            6 => Ok(VersionedSave::V6(ron::from_str(text)?)),
+            _ => Err(SaveError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Runs the migrations one version at a time until the save is current.
+    fn upgrade(self) -> SaveGame {
+        match self {
+            VersionedSave::V1(save) => VersionedSave::V2(v1_to_v2(save)).upgrade(),
+            VersionedSave::V2(save) => VersionedSave::V3(v2_to_v3(save)).upgrade(),
+            VersionedSave::V3(save) => VersionedSave::V4(v3_to_v4(save)).upgrade(),
+            VersionedSave::V4(save) => VersionedSave::V5(v4_to_v5(save)).upgrade(),
+            VersionedSave::V5(save) => VersionedSave::V6(v5_to_v6(save)).upgrade(),
+            VersionedSave::V6(save) => save,
+        }
+    }
+}
+
+/// Reads the version of a save and upgrades it to the current one.
+pub(super) fn load(text: &str) -> Result<SaveGame, SaveError> {
+    let version = read_version(text)?;
+
+    Ok(VersionedSave::parse(text, version)?.upgrade())
+}
+
+fn read_version(text: &str) -> Result<u32, SaveError> {
+    if let Ok(probe) = ron::from_str::<HeaderProbe>(text) {
+        return Ok(probe.header.version);
+    }
+
+    match ron::from_str::<LegacyProbe>(text) {
+        Ok(probe) => Ok(probe.version),
+        Err(error) if matches!(error.code, ron::Error::MissingStructField { .. }) => {
+            Err(SaveError::MissingVersion)
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn v1_to_v2(save: v1::SaveGame) -> v2::SaveGame {
+    v2::SaveGame {
+        map: save.map,
+        heroes: save.heroes,
+        turn: save.turn,
+    }
+}
+
+/// Every seat was played by a human, and all heroes belonged to player 1.
+fn v2_to_v3(save: v2::SaveGame) -> v3::SaveGame {
+    let players = save.map.generator.player_count.max(1) as usize;
+
+    v3::SaveGame {
+        players: vec![v4::SavedPlayerKind::Human; players],
+        heroes: save
+            .heroes
+            .into_iter()
+            .map(|hero| v4::SavedHero {
+                owner: 1,
+                hex: hero.hex,
+                experience: hero.experience,
+                level: hero.level,
+                health: hero.health,
+                movement_points: hero.movement_points,
+                has_moved: hero.has_moved,
+                max_units: hero.max_units,
+                units: hero.units,
+            })
+            .collect(),
+        map: save.map,
+        turn: save.turn,
+    }
+}
+
+/// Players get their default name and colour, each on their own team.
+fn v3_to_v4(save: v3::SaveGame) -> v4::SaveGame {
+    use v3::SavedGameplayState as Old;
+    use v4::SavedGameplayState;
+
+    let state = match save.turn.state {
+        Old::Player1Turn => SavedGameplayState::PlayerTurn(1),
+        Old::Player2Turn => SavedGameplayState::PlayerTurn(2),
+        Old::Player3Turn => SavedGameplayState::PlayerTurn(3),
+        Old::Player4Turn => SavedGameplayState::PlayerTurn(4),
+        Old::Player5Turn => SavedGameplayState::PlayerTurn(5),
+        Old::Player6Turn => SavedGameplayState::PlayerTurn(6),
+        Old::Player7Turn => SavedGameplayState::PlayerTurn(7),
+        Old::Player8Turn => SavedGameplayState::PlayerTurn(8),
+        Old::TurnTransition => SavedGameplayState::TurnTransition,
+        Old::GameOver => SavedGameplayState::GameOver,
+    };
+
+    v4::SaveGame {
+        map: save.map,
+        players: save
+            .players
+            .into_iter()
+            .enumerate()
+            .map(|(index, kind)| {
+                let id = PlayerId(index as u32 + 1);
+                v4::SavedPlayer {
+                    id: id.0,
+                    name: default_name(id),
+                    color: default_color(id),
+                    team: id.0,
+                    kind,
+                }
+            })
+            .collect(),
+        heroes: save.heroes,
+        turn: v4::SavedTurn {
+            current_turn: save.turn.current_turn,
+            max_turns: save.turn.max_turns,
+            state,
+        },
+    }
+}
+
+/// Turns were taken one after another.
+fn v4_to_v5(save: v4::SaveGame) -> v5::SaveGame {
+    v5::SaveGame {
+        map: save.map,
+        players: save.players,
+        heroes: save.heroes,
+        turn: v5::SavedTurn {
+            current_turn: save.turn.current_turn,
+            max_turns: save.turn.max_turns,
+            mode: v5::SavedTurnMode::Sequential,
+            state: save.turn.state,
+        },
+    }
+}
+
+/// Heroes had nothing planned and no journey. They get the action points
+/// every hero had in a turn back then.
+fn v5_to_v6(save: v5::SaveGame) -> SaveGame {
+    const HERO_ACTION_POINTS: u32 = 2;
+
+    SaveGame {
+        header: SaveHeader { version: 6 },
+        map: save.map.into(),
+        players: save.players.into_iter().map(Into::into).collect(),
+        heroes: save
+            .heroes
+            .into_iter()
+            .map(|hero| SavedHero {
+                owner: hero.owner,
+                hex: hero.hex,
+                experience: hero.experience,
+                level: hero.level,
+                health: hero.health.into(),
+                movement_points: hero.movement_points,
+                has_moved: hero.has_moved,
+                action_points: HERO_ACTION_POINTS,
+                journey: None,
+                plan: Vec::new(),
+                max_units: hero.max_units,
+                units: hero
+                    .units
+                    .into_iter()
+                    .map(|slot| slot.map(Into::into))
+                    .collect(),
+            })
+            .collect(),
+        turn: SavedTurn {
+            current_turn: save.turn.current_turn,
+            max_turns: save.turn.max_turns,
+            mode: save.turn.mode.into(),
+            state: save.turn.state.into(),
+        },
+    }
+}
@@ -0,0 +1,310 @@
+use std::{fmt, fs, io, path::Path};
+
+use bevy::utils::HashMap;
+use hexx::Hex;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    map::{
+        components::Tile,
+        generation::GeneratorSettings,
+        rivers::{HexEdge, Rivers},
+    },
+    setup::{SlotKind, TurnMode},
+};
+
+mod migrations;
+
+/// Version written into new saves. Bump it whenever a saved struct changes,
+/// and add a migration from the previous version to `migrations`.
+pub const SAVE_VERSION: u32 = 6;
+
+/// Comes first in every save so it can be read before the rest is understood.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveHeader {
+    pub version: u32,
+}
+
+/// A whole game as written to disk. The saved structs mirror the client's
+/// components instead of reusing them, so the format only changes on purpose.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub header: SaveHeader,
+    pub map: SavedMap,
+    /// Every player, in turn order.
+    pub players: Vec<SavedPlayer>,
+    pub heroes: Vec<SavedHero>,
+    pub turn: SavedTurn,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedMap {
+    /// Settings the map was generated with, including its seed.
+    pub generator: GeneratorSettings,
+    /// Every tile, sorted by hex. They are saved rather than regenerated from
+    /// the seed so changes made during the game are kept.
+    pub tiles: Vec<(Hex, Tile)>,
+    /// River edges, sorted.
+    pub rivers: Vec<HexEdge>,
+}
+
+impl SavedMap {
+    pub fn new(generator: GeneratorSettings, tiles: &HashMap<Hex, Tile>, rivers: &Rivers) -> Self {
+        let mut tiles: Vec<(Hex, Tile)> = tiles
+            .iter()
+            .map(|(&hex, tile)| (hex, tile.clone()))
+            .collect();
+        tiles.sort_by_key(|(hex, _)| (hex.x, hex.y));
+
+        let mut rivers: Vec<HexEdge> = rivers.edges.iter().copied().collect();
+        rivers.sort_by_key(|edge| edge.hexes().map(|hex| (hex.x, hex.y)));
+
+        Self {
+            generator,
+            tiles,
+            rivers,
+        }
+    }
+
+    pub fn tiles(&self) -> HashMap<Hex, Tile> {
+        self.tiles.iter().cloned().collect()
+    }
+
+    pub fn rivers(&self) -> Rivers {
+        Rivers {
+            edges: self.rivers.iter().copied().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedPlayer {
+    pub id: u32,
+    pub name: String,
+    /// sRGB.
+    pub color: [f32; 3],
+    /// Players on the same team are allies.
+    pub team: u32,
+    pub kind: SavedPlayerKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SavedPlayerKind {
+    Human,
+    Ai,
+}
+
+impl From<&SlotKind> for SavedPlayerKind {
+    fn from(kind: &SlotKind) -> Self {
+        match kind {
+            SlotKind::Human => SavedPlayerKind::Human,
+            SlotKind::Ai => SavedPlayerKind::Ai,
+        }
+    }
+}
+
+impl From<SavedPlayerKind> for SlotKind {
+    fn from(kind: SavedPlayerKind) -> Self {
+        match kind {
+            SavedPlayerKind::Human => SlotKind::Human,
+            SavedPlayerKind::Ai => SlotKind::Ai,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedHero {
+    /// Id of the player the hero belongs to.
+    pub owner: u32,
+    pub hex: Hex,
+    pub experience: u32,
+    pub level: u32,
+    pub health: SavedHealth,
+    pub movement_points: u32,
+    pub has_moved: bool,
+    /// Action points left this turn.
+    pub action_points: u32,
+    pub journey: Option<SavedJourney>,
+    /// What the hero planned but didn't carry out yet, in order.
+    pub plan: Vec<SavedAction>,
+    pub max_units: u32,
+    /// One entry per unit slot, empty slots included.
+    pub units: Vec<Option<SavedUnit>>,
+}
+
+/// Where a hero is headed beyond this turn, and the hexes it still has to
+/// cross.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedJourney {
+    pub to: Hex,
+    pub path: Vec<Hex>,
+}
+
+/// An action a hero planned. Other heroes are named by their place in
+/// `SaveGame::heroes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SavedAction {
+    Move { to: Hex },
+    Attack { target: u32 },
+    Wait,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedHealth {
+    pub current: u32,
+    pub max: u32,
+}
+
+/// A unit in a hero's army. Units only carry the components they were given.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedUnit {
+    pub unit_type: SavedUnitType,
+    pub health: Option<SavedHealth>,
+    pub attack: Option<i32>,
+    pub defense: Option<i32>,
+    pub range: Option<u32>,
+    pub movement_points: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SavedUnitType {
+    Melee,
+    Ranged,
+    Support,
+    Naval,
+    Air,
+    Siege,
+    Cavalry,
+    Artillery,
+    Armor,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedTurn {
+    pub current_turn: u32,
+    pub max_turns: u32,
+    pub mode: SavedTurnMode,
+    pub state: SavedGameplayState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SavedTurnMode {
+    Sequential,
+    Simultaneous,
+}
+
+impl From<&TurnMode> for SavedTurnMode {
+    fn from(mode: &TurnMode) -> Self {
+        match mode {
+            TurnMode::Sequential => SavedTurnMode::Sequential,
+            TurnMode::Simultaneous => SavedTurnMode::Simultaneous,
+        }
+    }
+}
+
+impl From<SavedTurnMode> for TurnMode {
+    fn from(mode: SavedTurnMode) -> Self {
+        match mode {
+            SavedTurnMode::Sequential => TurnMode::Sequential,
+            SavedTurnMode::Simultaneous => TurnMode::Simultaneous,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SavedGameplayState {
+    /// The turn of the player with this id.
+    PlayerTurn(u32),
+    /// Between turns, while simultaneous orders are carried out.
+    TurnTransition,
+    GameOver,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Serialize(ron::Error),
+    Deserialize(ron::error::SpannedError),
+    MissingVersion,
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(error) => write!(f, "could not access save file: {error}"),
+            SaveError::Serialize(error) => write!(f, "could not write save: {error}"),
+            SaveError::Deserialize(error) => write!(f, "could not read save: {error}"),
+            SaveError::MissingVersion => write!(f, "save has no version"),
+            SaveError::UnsupportedVersion(version) => write!(
+                f,
+                "save version {version} is not supported, the newest is {SAVE_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(error: io::Error) -> Self {
+        SaveError::Io(error)
+    }
+}
+
+impl From<ron::Error> for SaveError {
+    fn from(error: ron::Error) -> Self {
+        SaveError::Serialize(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for SaveError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        SaveError::Deserialize(error)
+    }
+}
+
+impl SaveGame {
+    pub fn new(
+        map: SavedMap,
+        players: Vec<SavedPlayer>,
+        heroes: Vec<SavedHero>,
+        turn: SavedTurn,
+    ) -> Self {
+        Self {
+            header: SaveHeader {
+                version: SAVE_VERSION,
+            },
+            map,
+            players,
+            heroes,
+            turn,
+        }
+    }
+
+    pub fn to_ron(&self) -> Result<String, SaveError> {
+        Ok(ron::ser::to_string_pretty(self, PrettyConfig::default())?)
+    }
+
+    /// Reads a save written by any supported version, upgrading it to the
+    /// current one.
+    pub fn from_ron(text: &str) -> Result<Self, SaveError> {
+        migrations::load(text)
+    }
+
+    /// Writes the save to `path`, creating its directory if needed.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, SaveError> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+}
@@ -0,0 +1,213 @@
+//! The choices made before a game starts, shared by the local lobby and the
+//! server's.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    map::{
+        generation::{GeneratorSettings, MapType},
+        shape::MapShape,
+    },
+    protocol::PlayerId,
+};
+
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = 8;
+
+/// Colours of the players in turn order, as sRGB.
+pub const PLAYER_COLORS: [[f32; 3]; MAX_PLAYERS] = [
+    [0.0, 0.0, 1.0],
+    [0.9, 0.1, 0.1],
+    [0.1, 0.7, 0.1],
+    [0.9, 0.8, 0.0],
+    [0.6, 0.1, 0.8],
+    [1.0, 0.5, 0.0],
+    [0.0, 0.8, 0.8],
+    [0.9, 0.4, 0.7],
+];
+
+/// Name `player` goes by until they pick one.
+pub fn default_name(player: PlayerId) -> String {
+    format!("Player {}", player.0)
+}
+
+/// Colour `player` gets until they pick one.
+pub fn default_color(player: PlayerId) -> [f32; 3] {
+    let index = player.0.saturating_sub(1) as usize;
+
+    PLAYER_COLORS.get(index).copied().unwrap_or([0.5, 0.5, 0.5])
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MapSize {
+    Tiny,
+    Small,
+    #[default]
+    Standard,
+    Large,
+    Huge,
+}
+
+impl MapSize {
+    pub const ALL: [MapSize; 5] = [
+        MapSize::Tiny,
+        MapSize::Small,
+        MapSize::Standard,
+        MapSize::Large,
+        MapSize::Huge,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MapSize::Tiny => "Tiny",
+            MapSize::Small => "Small",
+            MapSize::Standard => "Standard",
+            MapSize::Large => "Large",
+            MapSize::Huge => "Huge",
+        }
+    }
+
+    pub fn shape(&self) -> MapShape {
+        let radius = match self {
+            MapSize::Tiny => 30,
+            MapSize::Small => 50,
+            MapSize::Standard => 80,
+            MapSize::Large => 100,
+            MapSize::Huge => 120,
+        };
+
+        MapShape::Hexagon { radius }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaxTurns {
+    Quick,
+    #[default]
+    Normal,
+    Long,
+    LongLongLong,
+    Marathon,
+    Infinite,
+}
+
+impl MaxTurns {
+    pub const ALL: [MaxTurns; 6] = [
+        MaxTurns::Quick,
+        MaxTurns::Normal,
+        MaxTurns::Long,
+        MaxTurns::LongLongLong,
+        MaxTurns::Marathon,
+        MaxTurns::Infinite,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MaxTurns::Quick => "Quick",
+            MaxTurns::Normal => "Normal",
+            MaxTurns::Long => "Long",
+            MaxTurns::LongLongLong => "Very long",
+            MaxTurns::Marathon => "Marathon",
+            MaxTurns::Infinite => "Infinite",
+        }
+    }
+
+    pub fn get_max_turns(&self) -> u32 {
+        match self {
+            MaxTurns::Quick => 50,
+            MaxTurns::Normal => 100,
+            MaxTurns::Long => 150,
+            MaxTurns::LongLongLong => 175,
+            MaxTurns::Marathon => 200,
+            MaxTurns::Infinite => u32::MAX,
+        }
+    }
+}
+
+/// Who plays a slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlotKind {
+    Human,
+    Ai,
+}
+
+impl SlotKind {
+    pub const ALL: [SlotKind; 2] = [SlotKind::Human, SlotKind::Ai];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SlotKind::Human => "Human",
+            SlotKind::Ai => "AI",
+        }
+    }
+}
+
+/// How the players take their turns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TurnMode {
+    /// One player after the other.
+    #[default]
+    Sequential,
+    /// Everyone gives their orders, then they are carried out together.
+    Simultaneous,
+}
+
+impl TurnMode {
+    pub const ALL: [TurnMode; 2] = [TurnMode::Sequential, TurnMode::Simultaneous];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TurnMode::Sequential => "One after another",
+            TurnMode::Simultaneous => "Simultaneous",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameSetup {
+    pub map_size: MapSize,
+    pub map_type: MapType,
+    pub seed: u64,
+    pub max_turns: MaxTurns,
+    pub turn_mode: TurnMode,
+    /// One slot per player, in turn order. The first belongs to whoever hosts
+    /// the game and is always human.
+    pub slots: Vec<SlotKind>,
+}
+
+impl Default for GameSetup {
+    fn default() -> Self {
+        Self {
+            map_size: MapSize::default(),
+            map_type: MapType::default(),
+            seed: rand::random(),
+            max_turns: MaxTurns::default(),
+            turn_mode: TurnMode::default(),
+            slots: vec![SlotKind::Human, SlotKind::Human],
+        }
+    }
+}
+
+impl GameSetup {
+    pub fn is_valid(&self) -> bool {
+        (MIN_PLAYERS..=MAX_PLAYERS).contains(&self.slots.len()) && self.slots[0] == SlotKind::Human
+    }
+
+    /// Adds or removes slots at the end until there are `players`. New slots
+    /// are played by the AI.
+    pub fn set_player_count(&mut self, players: usize) {
+        self.slots
+            .resize(players.clamp(MIN_PLAYERS, MAX_PLAYERS), SlotKind::Ai);
+    }
+
+    /// Settings to generate the map with.
+    pub fn generator(&self) -> GeneratorSettings {
+        GeneratorSettings {
+            seed: self.seed,
+            shape: self.map_size.shape(),
+            map_type: self.map_type,
+            player_count: self.slots.len() as u32,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use common::map::components::Biome;
+
+#[test]
+fn classifies_every_elevation_and_moisture_band() {
+    let cases = [
+        (-0.5, 0.5, Biome::DeepWater),
+        (-0.1, 0.5, Biome::ShallowWater),
+        (0.1, 0.2, Biome::Desert),
+        (0.1, 0.5, Biome::Plains),
+        (0.1, 0.8, Biome::Forest),
+        (0.3, 0.3, Biome::Plains),
+        (0.3, 0.7, Biome::Forest),
+        (0.5, 0.2, Biome::Mountain),
+        (0.5, 0.8, Biome::Mountain),
+        (0.7, 0.2, Biome::Mountain),
+        (0.7, 0.6, Biome::Snow),
+    ];
+
+    for (elevation, moisture, biome) in cases {
+        assert_eq!(
+            Biome::from_elevation_and_moisture(elevation, moisture),
+            biome,
+            "elevation {elevation}, moisture {moisture}"
+        );
+    }
+}
+
+#[test]
+fn band_edges_belong_to_the_band_above() {
+    assert_eq!(
+        Biome::from_elevation_and_moisture(-0.2, 0.5),
+        Biome::ShallowWater
+    );
+    assert_eq!(Biome::from_elevation_and_moisture(0.0, 0.5), Biome::Plains);
+    assert_eq!(Biome::from_elevation_and_moisture(0.25, 0.5), Biome::Forest);
+    assert_eq!(
+        Biome::from_elevation_and_moisture(0.4, 0.2),
+        Biome::Mountain
+    );
+    assert_eq!(Biome::from_elevation_and_moisture(0.55, 0.4), Biome::Snow);
+}
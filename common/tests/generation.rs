@@ -0,0 +1,93 @@
+use bevy::utils::HashSet;
+use common::map::{
+    generation::{generate_map, GeneratorSettings, MapType},
+    shape::MapShape,
+};
+use hexx::Hex;
+
+fn settings(seed: u64) -> GeneratorSettings {
+    GeneratorSettings {
+        seed,
+        shape: MapShape::Hexagon { radius: 16 },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn same_seed_generates_the_same_map() {
+    let first = generate_map(&settings(42));
+    let second = generate_map(&settings(42));
+
+    assert_eq!(first.tiles, second.tiles);
+    assert_eq!(first.rivers.edges, second.rivers.edges);
+    assert_eq!(first.start_positions, second.start_positions);
+}
+
+#[test]
+fn different_seeds_generate_different_maps() {
+    let first = generate_map(&settings(42));
+    let second = generate_map(&settings(43));
+
+    assert_ne!(first.tiles, second.tiles);
+}
+
+#[test]
+fn every_map_type_hits_the_land_ratio() {
+    for map_type in MapType::ALL {
+        let map = generate_map(&GeneratorSettings {
+            map_type,
+            land_ratio: 0.35,
+            ..settings(7)
+        });
+
+        let land = map
+            .tiles
+            .values()
+            .filter(|tile| !tile.biome.is_water())
+            .count();
+        let ratio = land as f64 / map.tiles.len() as f64;
+        assert!(
+            (ratio - 0.35).abs() < 0.01,
+            "{} has a land ratio of {ratio}",
+            map_type.name()
+        );
+    }
+}
+
+#[test]
+fn continents_are_separate_landmasses() {
+    let shape = MapShape::Rectangle {
+        width: 48,
+        height: 30,
+        wrap_x: true,
+    };
+    let map = generate_map(&GeneratorSettings {
+        shape,
+        map_type: MapType::Continents,
+        ..settings(7)
+    });
+    let mut land: HashSet<Hex> = map
+        .tiles
+        .iter()
+        .filter(|(_, tile)| !tile.biome.is_water())
+        .map(|(&hex, _)| hex)
+        .collect();
+
+    let mut landmasses = 0;
+    while let Some(&start) = land.iter().next() {
+        land.remove(&start);
+        let mut open = vec![start];
+        while let Some(hex) = open.pop() {
+            for neighbor in hex.all_neighbors() {
+                if let Some(neighbor) = shape.normalize(neighbor) {
+                    if land.remove(&neighbor) {
+                        open.push(neighbor);
+                    }
+                }
+            }
+        }
+        landmasses += 1;
+    }
+
+    assert!(landmasses > 1);
+}
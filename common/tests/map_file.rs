@@ -0,0 +1,140 @@
+use common::map::{
+    components::{Biome, TileResource},
+    generation::{generate_map, GeneratorSettings},
+    map_file::{MapFile, MapFileError},
+    shape::MapShape,
+};
+use hexx::Hex;
+
+const SMALL: &str = include_str!("fixtures/map_small.ron");
+
+fn with_tile(tile: &str) -> String {
+    SMALL.replacen("    ],", &format!("        {tile},\n    ],"), 1)
+}
+
+#[test]
+fn reads_hand_written_maps() {
+    let tiles = MapFile::from_ron(SMALL).unwrap().tiles().unwrap();
+
+    assert_eq!(tiles.len(), 7);
+    let center = &tiles[&Hex::ZERO];
+    assert_eq!(center.biome, Biome::Plains);
+    assert_eq!(center.attributes.attractiveness, 55);
+    assert_eq!(center.strategic_resource, Some(TileResource::Iron));
+    assert_eq!(center.trade_resource, Some(TileResource::Wheat));
+    assert_eq!(tiles[&Hex::new(1, 0)].strategic_resource, None);
+}
+
+#[test]
+fn round_trips_generated_maps() {
+    let settings = GeneratorSettings {
+        seed: 3,
+        shape: MapShape::Rectangle {
+            width: 20,
+            height: 12,
+            wrap_x: true,
+        },
+        ..Default::default()
+    };
+    let map = generate_map(&settings);
+
+    let text = MapFile::new(settings.shape, &map.tiles).to_ron().unwrap();
+    let file = MapFile::from_ron(&text).unwrap();
+
+    assert_eq!(file.shape, settings.shape);
+    assert_eq!(file.tiles().unwrap(), map.tiles);
+    assert_eq!(file.to_ron().unwrap(), text);
+}
+
+#[test]
+fn places_start_positions_on_imported_maps() {
+    let map = MapFile::from_ron(SMALL).unwrap().to_map(1, 1).unwrap();
+
+    let [start] = map.start_positions.hexes()[..] else {
+        panic!("expected one start position");
+    };
+    assert!(!map.tiles[&start].biome.is_water());
+    assert!(map.rivers.edges.is_empty());
+}
+
+#[test]
+fn rejects_unknown_biomes() {
+    let text = SMALL.replace("\"Snow\"", "\"Tundra\"");
+
+    assert!(matches!(
+        MapFile::from_ron(&text).unwrap().tiles(),
+        Err(MapFileError::UnknownBiome { hex, biome }) if hex == Hex::new(0, -1) && biome == "Tundra"
+    ));
+}
+
+#[test]
+fn rejects_unknown_resources() {
+    let text = SMALL.replace("\"Copper\"", "\"Gold\"");
+
+    assert!(matches!(
+        MapFile::from_ron(&text).unwrap().tiles(),
+        Err(MapFileError::UnknownResource { resource, .. }) if resource == "Gold"
+    ));
+}
+
+#[test]
+fn rejects_resources_in_the_wrong_slot() {
+    let text = SMALL.replace(
+        "trade_resource: Some(\"Wheat\")",
+        "trade_resource: Some(\"Coal\")",
+    );
+
+    assert!(matches!(
+        MapFile::from_ron(&text).unwrap().tiles(),
+        Err(MapFileError::MisplacedResource {
+            resource: TileResource::Coal,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn rejects_duplicate_hexes() {
+    let text =
+        with_tile("(hex: (1, 0), biome: \"Plains\", production: 1, science: 1, attractiveness: 1)");
+
+    assert!(matches!(
+        MapFile::from_ron(&text).unwrap().tiles(),
+        Err(MapFileError::DuplicateHex(hex)) if hex == Hex::new(1, 0)
+    ));
+}
+
+#[test]
+fn rejects_hexes_outside_the_shape() {
+    let text =
+        with_tile("(hex: (2, 0), biome: \"Plains\", production: 1, science: 1, attractiveness: 1)");
+
+    assert!(matches!(
+        MapFile::from_ron(&text).unwrap().tiles(),
+        Err(MapFileError::OutsideShape(hex)) if hex == Hex::new(2, 0)
+    ));
+}
+
+#[test]
+fn rejects_out_of_range_attributes() {
+    let text = SMALL.replace("production: 80", "production: 180");
+
+    assert!(matches!(
+        MapFile::from_ron(&text).unwrap().tiles(),
+        Err(MapFileError::AttributeOutOfRange {
+            attribute: "production",
+            value: 180,
+            ..
+        })
+    ));
+
+    let text = SMALL.replace("science: 50", "science: -1");
+    assert!(matches!(
+        MapFile::from_ron(&text).unwrap().tiles(),
+        Err(MapFileError::AttributeOutOfRange {
+            attribute: "science",
+            value: -1,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,87 @@
+use bevy::utils::HashMap;
+use common::map::{
+    components::{Biome, Tile},
+    pathfinding::turn_stops,
+    rivers::{HexEdge, Rivers},
+    shape::MapShape,
+};
+use hexx::Hex;
+
+/// A row of tiles heading east from the center, the first one the start.
+fn row(biomes: &[Biome]) -> (HashMap<Hex, Tile>, Vec<Hex>) {
+    let hexes: Vec<Hex> = (0..biomes.len() as i32).map(|x| Hex::new(x, 0)).collect();
+    let tiles = hexes
+        .iter()
+        .zip(biomes)
+        .map(|(&hex, &biome)| {
+            (
+                hex,
+                Tile {
+                    biome,
+                    ..Default::default()
+                },
+            )
+        })
+        .collect();
+
+    (tiles, hexes)
+}
+
+fn stops(biomes: &[Biome], rivers: &Rivers, movement_points: u32) -> Vec<usize> {
+    let (tiles, hexes) = row(biomes);
+    let shape = MapShape::Hexagon { radius: 20 };
+
+    turn_stops(
+        &tiles,
+        &shape,
+        rivers,
+        hexes[0],
+        &hexes[1..],
+        movement_points,
+        5,
+    )
+}
+
+#[test]
+fn splits_long_paths_into_turns() {
+    let biomes = [Biome::Plains; 13];
+
+    assert_eq!(stops(&biomes, &Rivers::default(), 5), vec![5, 10, 12]);
+    assert_eq!(stops(&biomes[..4], &Rivers::default(), 5), vec![3]);
+    assert_eq!(
+        stops(&biomes[..1], &Rivers::default(), 5),
+        Vec::<usize>::new()
+    );
+}
+
+#[test]
+fn starts_with_what_is_left_of_this_turn() {
+    let biomes = [Biome::Plains, Biome::Forest, Biome::Plains, Biome::Plains];
+
+    // the forest needs a fresh turn
+    assert_eq!(stops(&biomes, &Rivers::default(), 4), vec![0, 1, 3]);
+    assert_eq!(stops(&biomes, &Rivers::default(), 5), vec![1, 3]);
+}
+
+#[test]
+fn counts_river_crossings() {
+    let biomes = [Biome::Plains; 6];
+    let mut rivers = Rivers::default();
+    rivers
+        .edges
+        .insert(HexEdge::new(Hex::new(1, 0), Hex::new(2, 0)));
+
+    // crossing takes 4 points, one more than is left after the first step
+    assert_eq!(stops(&biomes, &rivers, 4), vec![1, 3, 5]);
+}
+
+#[test]
+fn stops_where_no_turn_is_long_enough() {
+    let biomes = [Biome::Plains, Biome::Plains, Biome::Desert, Biome::Plains];
+
+    assert_eq!(stops(&biomes, &Rivers::default(), 5), vec![1]);
+    assert_eq!(
+        stops(&biomes[1..], &Rivers::default(), 5),
+        Vec::<usize>::new()
+    );
+}
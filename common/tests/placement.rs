@@ -0,0 +1,100 @@
+use bevy::utils::HashMap;
+use common::map::{
+    components::{Biome, Tile},
+    placement::{place_resources, spawn_table, ResourceSettings},
+    shape::MapShape,
+};
+use hexx::Hex;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+const SHAPE: MapShape = MapShape::Hexagon { radius: 12 };
+
+/// Every biome in stripes, so each spawn table gets used.
+fn striped_tiles() -> HashMap<Hex, Tile> {
+    SHAPE
+        .hexes()
+        .into_iter()
+        .map(|hex| {
+            let biome = Biome::ALL[hex.x.rem_euclid(Biome::ALL.len() as i32) as usize];
+            (
+                hex,
+                Tile {
+                    biome,
+                    ..Default::default()
+                },
+            )
+        })
+        .collect()
+}
+
+fn placed(seed: u64, settings: &ResourceSettings) -> HashMap<Hex, Tile> {
+    let mut tiles = striped_tiles();
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    place_resources(&mut tiles, &SHAPE.hexes(), &SHAPE, settings, &mut rng);
+
+    tiles
+}
+
+/// Frequent enough that spacing decides where resources go.
+fn crowded() -> ResourceSettings {
+    ResourceSettings {
+        strategic_frequency: 1.0,
+        luxury_frequency: 1.0,
+        bonus_frequency: 1.0,
+        min_spacing: 3,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn same_seed_places_the_same_resources() {
+    let settings = ResourceSettings::default();
+
+    assert_eq!(placed(5, &settings), placed(5, &settings));
+    assert_ne!(placed(5, &settings), placed(6, &settings));
+}
+
+#[test]
+fn resources_come_from_their_biome_spawn_table() {
+    let tiles = placed(5, &crowded());
+
+    for tile in tiles.values() {
+        for resource in [tile.strategic_resource, tile.trade_resource]
+            .into_iter()
+            .flatten()
+        {
+            assert!(
+                spawn_table(&tile.biome)
+                    .iter()
+                    .any(|(allowed, _)| *allowed == resource),
+                "{resource:?} on {:?}",
+                tile.biome
+            );
+        }
+    }
+}
+
+#[test]
+fn resources_of_a_category_keep_their_spacing() {
+    let settings = crowded();
+    let tiles = placed(5, &settings);
+    let resources: Vec<(Hex, _)> = tiles
+        .iter()
+        .flat_map(|(&hex, tile)| {
+            [tile.strategic_resource, tile.trade_resource]
+                .into_iter()
+                .flatten()
+                .map(move |resource| (hex, resource.category()))
+        })
+        .collect();
+
+    assert!(!resources.is_empty());
+    for (i, (hex, category)) in resources.iter().enumerate() {
+        for (other, other_category) in &resources[i + 1..] {
+            if category == other_category {
+                assert!(SHAPE.distance(*hex, *other) >= settings.min_spacing);
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::{fmt::Debug, io::Cursor};
+
+use common::{
+    map::{
+        generation::{generate_map, GeneratorSettings, MapType},
+        shape::MapShape,
+    },
+    protocol::{
+        codec::{encode, read_frame, write_frame, CodecError, MAX_FRAME_LEN},
+        ClientMessage, GameSnapshot, Hello, HeroId, HeroState, LobbyState, PlayerId, Rejection,
+        Seat, ServerMessage, PROTOCOL_VERSION,
+    },
+    save::SavedMap,
+    setup::{GameSetup, MapSize, MaxTurns, SlotKind, TurnMode},
+};
+use hexx::Hex;
+use serde::{de::DeserializeOwned, Serialize};
+
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(message: T) {
+    let mut buffer = Vec::new();
+    write_frame(&mut buffer, &message).unwrap();
+
+    let mut reader = Cursor::new(buffer);
+    assert_eq!(read_frame::<T>(&mut reader).unwrap(), message);
+    assert_eq!(reader.position() as usize, reader.get_ref().len());
+}
+
+fn snapshot() -> GameSnapshot {
+    let generator = GeneratorSettings {
+        seed: 5,
+        shape: MapShape::Rectangle {
+            width: 16,
+            height: 10,
+            wrap_x: true,
+        },
+        ..Default::default()
+    };
+    let map = generate_map(&generator);
+    let heroes = map
+        .start_positions
+        .hexes()
+        .into_iter()
+        .enumerate()
+        .map(|(index, hex)| HeroState {
+            id: HeroId(index as u32),
+            owner: PlayerId(index as u32 + 1),
+            hex,
+            movement_points: 5,
+            has_moved: index == 0,
+        })
+        .collect();
+
+    GameSnapshot {
+        map: SavedMap::new(generator, &map.tiles, &map.rivers),
+        heroes,
+        players: vec![PlayerId(1), PlayerId(2)],
+        active_player: PlayerId(2),
+        turn: 12,
+        max_turns: 100,
+    }
+}
+
+fn lobby() -> LobbyState {
+    let setup = GameSetup {
+        map_size: MapSize::Large,
+        map_type: MapType::Archipelago,
+        seed: 123_456_789,
+        max_turns: MaxTurns::Marathon,
+        turn_mode: TurnMode::Sequential,
+        slots: vec![SlotKind::Human, SlotKind::Ai, SlotKind::Human],
+    };
+    let seats = setup
+        .slots
+        .iter()
+        .enumerate()
+        .map(|(index, &kind)| Seat {
+            player: PlayerId(index as u32 + 1),
+            kind,
+            taken: index == 0,
+            ready: index == 0,
+        })
+        .collect();
+
+    LobbyState { setup, seats }
+}
+
+#[test]
+fn hello_round_trips() {
+    round_trip(Hello::default());
+    assert_eq!(Hello::default().protocol_version, PROTOCOL_VERSION);
+}
+
+#[test]
+fn client_messages_round_trip() {
+    round_trip(ClientMessage::SelectHero { hero: HeroId(3) });
+    round_trip(ClientMessage::DeselectHero);
+    round_trip(ClientMessage::MoveHero {
+        hero: HeroId(1),
+        to: Hex::new(-4, 7),
+    });
+    round_trip(ClientMessage::EndTurn);
+    round_trip(ClientMessage::ChangeSetup(lobby().setup));
+    round_trip(ClientMessage::SetReady(true));
+}
+
+#[test]
+fn server_messages_round_trip() {
+    round_trip(ServerMessage::VersionMismatch {
+        server_version: PROTOCOL_VERSION,
+    });
+    round_trip(ServerMessage::Welcome {
+        player: PlayerId(3),
+        lobby: lobby(),
+    });
+    round_trip(ServerMessage::LobbyChanged(lobby()));
+    round_trip(ServerMessage::GameStarted(Box::new(snapshot())));
+    round_trip(ServerMessage::HeroMoved {
+        hero: HeroId(0),
+        path: vec![Hex::new(1, 0), Hex::new(2, -1), Hex::new(2, 0)],
+    });
+    round_trip(ServerMessage::TurnStarted {
+        player: PlayerId(1),
+        turn: 4,
+    });
+    round_trip(ServerMessage::GameOver);
+    for rejection in [
+        Rejection::NotYourTurn,
+        Rejection::UnknownHero,
+        Rejection::NotYourHero,
+        Rejection::AlreadyMoved,
+        Rejection::Unreachable,
+        Rejection::OutOfRange,
+        Rejection::Occupied,
+        Rejection::GameFull,
+        Rejection::NotHost,
+        Rejection::InvalidSetup,
+        Rejection::UnsupportedSetup,
+        Rejection::TooFewStartPositions,
+        Rejection::SeatTaken,
+        Rejection::GameNotStarted,
+        Rejection::GameAlreadyStarted,
+        Rejection::GameOver,
+    ] {
+        round_trip(ServerMessage::Rejected(rejection));
+    }
+}
+
+#[test]
+fn frames_follow_each_other_on_a_stream() {
+    let messages = [
+        ClientMessage::SelectHero { hero: HeroId(0) },
+        ClientMessage::EndTurn,
+        ClientMessage::DeselectHero,
+    ];
+    let mut buffer = Vec::new();
+    for message in messages.iter() {
+        write_frame(&mut buffer, message).unwrap();
+    }
+
+    let mut reader = Cursor::new(buffer);
+    for message in messages.iter() {
+        assert_eq!(&read_frame::<ClientMessage>(&mut reader).unwrap(), message);
+    }
+    assert!(read_frame::<ClientMessage>(&mut reader)
+        .unwrap_err()
+        .is_disconnect());
+}
+
+#[test]
+fn frames_start_with_their_length() {
+    let frame = encode(&ClientMessage::EndTurn).unwrap();
+    let len = u32::from_le_bytes(frame[..4].try_into().unwrap());
+
+    assert_eq!(len as usize, frame.len() - 4);
+}
+
+#[test]
+fn rejects_oversized_and_truncated_frames() {
+    let oversized = (MAX_FRAME_LEN + 1).to_le_bytes();
+    assert!(matches!(
+        read_frame::<ClientMessage>(&mut Cursor::new(oversized)),
+        Err(CodecError::FrameTooLarge(len)) if len == MAX_FRAME_LEN + 1
+    ));
+
+    let mut truncated = encode(&ClientMessage::MoveHero {
+        hero: HeroId(1),
+        to: Hex::new(2, 3),
+    })
+    .unwrap();
+    truncated.pop();
+    assert!(read_frame::<ClientMessage>(&mut Cursor::new(truncated))
+        .unwrap_err()
+        .is_disconnect());
+}
+
+#[test]
+fn rejects_garbage() {
+    let mut frame = 4u32.to_le_bytes().to_vec();
+    frame.extend_from_slice(&[0xff; 4]);
+
+    assert!(matches!(
+        read_frame::<ClientMessage>(&mut Cursor::new(frame)),
+        Err(CodecError::Bincode(_))
+    ));
+}
@@ -0,0 +1,69 @@
+use common::{
+    protocol::{HeroId, PlayerId},
+    replay::{world_hash, CommandLog, LogError, LoggedAction, LOG_VERSION},
+    setup::{GameSetup, SlotKind, TurnMode},
+};
+use hexx::Hex;
+
+fn command_log() -> CommandLog {
+    let mut log = CommandLog::new(GameSetup {
+        seed: 1234,
+        turn_mode: TurnMode::Simultaneous,
+        slots: vec![SlotKind::Human, SlotKind::Ai, SlotKind::Human],
+        ..Default::default()
+    });
+    log.record(
+        1,
+        PlayerId(1),
+        LoggedAction::Move {
+            hero: HeroId(0),
+            to: Hex::new(2, -1),
+        },
+    );
+    log.record(1, PlayerId(1), LoggedAction::Undo);
+    log.record(1, PlayerId(1), LoggedAction::Redo);
+    log.record(1, PlayerId(1), LoggedAction::EndTurn);
+    log.record(
+        1,
+        PlayerId(3),
+        LoggedAction::Attack {
+            attacker: HeroId(2),
+            target: HeroId(0),
+        },
+    );
+    log.record(1, PlayerId(3), LoggedAction::Wait { hero: HeroId(2) });
+    log.record(1, PlayerId(3), LoggedAction::Cancel { hero: HeroId(2) });
+    log.final_hash = Some(world_hash(&log.commands));
+
+    log
+}
+
+#[test]
+fn round_trips_through_ron() {
+    let log = command_log();
+
+    let loaded = CommandLog::from_ron(&log.to_ron().unwrap()).unwrap();
+
+    assert_eq!(loaded, log);
+    assert_eq!(loaded.version, LOG_VERSION);
+}
+
+#[test]
+fn rejects_logs_of_other_versions() {
+    let mut log = command_log();
+    log.version = LOG_VERSION + 1;
+
+    let error = CommandLog::from_ron(&log.to_ron().unwrap()).unwrap_err();
+
+    assert!(matches!(error, LogError::UnsupportedVersion(version) if version == LOG_VERSION + 1));
+}
+
+#[test]
+fn world_hashes_only_match_for_equal_worlds() {
+    let log = command_log();
+    let mut other = log.commands.clone();
+    other.swap(1, 2);
+
+    assert_eq!(world_hash(&log.commands), world_hash(&log.commands.clone()));
+    assert_ne!(world_hash(&log.commands), world_hash(&other));
+}
@@ -0,0 +1,62 @@
+use bevy::utils::HashMap;
+use common::map::{rivers::trace_rivers, shape::MapShape};
+use hexx::Hex;
+
+const SHAPE: MapShape = MapShape::Hexagon { radius: 8 };
+
+/// Elevations that fall off with the distance from the center, which is land
+/// up to `coast` and sea beyond.
+fn cone(coast: u32) -> HashMap<Hex, f64> {
+    SHAPE
+        .hexes()
+        .into_iter()
+        .map(|hex| {
+            let distance = hex.unsigned_distance_to(Hex::ZERO) as f64;
+            (hex, 1.0 - distance / (coast as f64 + 0.5))
+        })
+        .collect()
+}
+
+#[test]
+fn rivers_run_downhill_into_the_sea() {
+    let elevations = cone(5);
+
+    let rivers = trace_rivers(&elevations, &SHAPE, [Hex::ZERO], 1);
+
+    assert!(!rivers.edges.is_empty());
+    let banks = rivers.banks();
+    // every bank is land, and the river gets all the way to the coast
+    assert!(banks.iter().all(|hex| elevations[hex] >= 0.0));
+    assert!(banks
+        .iter()
+        .any(|hex| hex.unsigned_distance_to(Hex::ZERO) <= 1));
+    assert!(banks.iter().any(|hex| {
+        hex.all_neighbors()
+            .iter()
+            .any(|neighbor| elevations.get(neighbor).is_some_and(|&e| e < 0.0))
+    }));
+}
+
+#[test]
+fn rivers_never_flow_uphill() {
+    // a bowl, the center is a pit no river can leave
+    let elevations: HashMap<Hex, f64> = cone(20)
+        .into_iter()
+        .map(|(hex, elevation)| (hex, 2.0 - elevation))
+        .collect();
+
+    let rivers = trace_rivers(&elevations, &SHAPE, [Hex::ZERO, Hex::new(1, 0)], 2);
+
+    assert!(rivers.edges.is_empty());
+}
+
+#[test]
+fn sources_on_a_bank_start_no_river() {
+    let elevations = cone(5);
+    let first = trace_rivers(&elevations, &SHAPE, [Hex::ZERO], 1);
+    let bank = *first.banks().iter().next().unwrap();
+
+    let rivers = trace_rivers(&elevations, &SHAPE, [Hex::ZERO, bank], 2);
+
+    assert_eq!(rivers.edges, first.edges);
+}
@@ -0,0 +1,151 @@
+use common::{
+    map::{
+        generation::{generate_map, GeneratorSettings},
+        shape::MapShape,
+    },
+    save::{
+        SaveError, SaveGame, SavedAction, SavedGameplayState, SavedHealth, SavedHero, SavedJourney,
+        SavedMap, SavedPlayer, SavedPlayerKind, SavedTurn, SavedTurnMode, SavedUnit, SavedUnitType,
+        SAVE_VERSION,
+    },
+};
+use hexx::Hex;
+
+fn save_game() -> SaveGame {
+    let generator = GeneratorSettings {
+        seed: 42,
+        shape: MapShape::Hexagon { radius: 12 },
+        ..Default::default()
+    };
+    let map = generate_map(&generator);
+    let start = map.start_positions.positions[0].hex;
+
+    let hero = SavedHero {
+        owner: 2,
+        hex: start,
+        experience: 120,
+        level: 3,
+        health: SavedHealth {
+            current: 64,
+            max: 100,
+        },
+        movement_points: 4,
+        has_moved: true,
+        action_points: 1,
+        journey: Some(SavedJourney {
+            to: start + Hex::new(3, 0),
+            path: (1..=3).map(|x| start + Hex::new(x, 0)).collect(),
+        }),
+        plan: vec![
+            SavedAction::Move {
+                to: start + Hex::new(1, 0),
+            },
+            SavedAction::Attack { target: 0 },
+            SavedAction::Wait,
+        ],
+        max_units: 3,
+        units: vec![
+            Some(SavedUnit {
+                unit_type: SavedUnitType::Cavalry,
+                health: Some(SavedHealth {
+                    current: 30,
+                    max: 40,
+                }),
+                attack: Some(7),
+                defense: Some(-2),
+                range: Some(1),
+                movement_points: Some(6),
+            }),
+            None,
+            Some(SavedUnit {
+                unit_type: SavedUnitType::Ranged,
+                health: None,
+                attack: None,
+                defense: None,
+                range: Some(3),
+                movement_points: None,
+            }),
+        ],
+    };
+
+    SaveGame::new(
+        SavedMap::new(generator, &map.tiles, &map.rivers),
+        vec![
+            SavedPlayer {
+                id: 1,
+                name: "Ada".to_string(),
+                color: [0.2, 0.4, 0.6],
+                team: 1,
+                kind: SavedPlayerKind::Human,
+            },
+            SavedPlayer {
+                id: 2,
+                name: "Player 2".to_string(),
+                color: [0.9, 0.1, 0.1],
+                team: 1,
+                kind: SavedPlayerKind::Ai,
+            },
+        ],
+        vec![hero],
+        SavedTurn {
+            current_turn: 17,
+            max_turns: 100,
+            mode: SavedTurnMode::Simultaneous,
+            state: SavedGameplayState::PlayerTurn(2),
+        },
+    )
+}
+
+#[test]
+fn round_trips_through_ron() {
+    let save = save_game();
+    let text = save.to_ron().unwrap();
+    let loaded = SaveGame::from_ron(&text).unwrap();
+
+    assert_eq!(loaded, save);
+    assert_eq!(loaded.to_ron().unwrap(), text);
+}
+
+#[test]
+fn round_trips_through_a_file() {
+    let save = save_game();
+    let path = std::env::temp_dir()
+        .join("project-strategy-tests")
+        .join("round_trip.ron");
+
+    save.write(&path).unwrap();
+    let loaded = SaveGame::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, save);
+}
+
+#[test]
+fn keeps_the_generated_map() {
+    let save = save_game();
+    let map = generate_map(&save.map.generator);
+
+    assert_eq!(save.map.tiles(), map.tiles);
+    assert_eq!(save.map.rivers().edges, map.rivers.edges);
+}
+
+#[test]
+fn rejects_unknown_versions() {
+    let mut save = save_game();
+    save.header.version = SAVE_VERSION + 1;
+    let text = save.to_ron().unwrap();
+
+    assert!(matches!(
+        SaveGame::from_ron(&text),
+        Err(SaveError::UnsupportedVersion(version)) if version == SAVE_VERSION + 1
+    ));
+}
+
+#[test]
+fn missing_file_is_an_io_error() {
+    let path = std::env::temp_dir()
+        .join("project-strategy-tests")
+        .join("does_not_exist.ron");
+
+    assert!(matches!(SaveGame::read(path), Err(SaveError::Io(_))));
+}
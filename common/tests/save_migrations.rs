@@ -0,0 +1,126 @@
+use common::{
+    map::components::{Biome, TileResource},
+    save::{
+        SaveError, SaveGame, SavedGameplayState, SavedHealth, SavedPlayer, SavedPlayerKind,
+        SavedTurnMode, SavedUnitType, SAVE_VERSION,
+    },
+};
+use hexx::Hex;
+
+const V1: &str = include_str!("fixtures/save_v1.ron");
+const V2: &str = include_str!("fixtures/save_v2.ron");
+const V3: &str = include_str!("fixtures/save_v3.ron");
+const V4: &str = include_str!("fixtures/save_v4.ron");
+const V5: &str = include_str!("fixtures/save_v5.ron");
+const V6: &str = include_str!("fixtures/save_v6.ron");
+
+/// All fixtures hold the same game, so every version must load into this.
+fn assert_fixture_world(save: &SaveGame) {
+    assert_eq!(save.header.version, SAVE_VERSION);
+
+    assert_eq!(save.map.generator.seed, 7);
+    let tiles = save.map.tiles();
+    assert_eq!(tiles.len(), 7);
+    let center = &tiles[&Hex::ZERO];
+    assert_eq!(center.biome, Biome::Plains);
+    assert_eq!(center.attributes.production, 40);
+    assert_eq!(center.strategic_resource, Some(TileResource::Iron));
+    assert_eq!(center.trade_resource, Some(TileResource::Wheat));
+    assert_eq!(tiles[&Hex::new(0, 1)].biome, Biome::DeepWater);
+    assert!(save.map.rivers().crosses(Hex::new(1, 0), Hex::ZERO));
+    assert_eq!(save.map.rivers.len(), 2);
+
+    assert_eq!(
+        save.players[1],
+        SavedPlayer {
+            id: 2,
+            name: "Player 2".to_string(),
+            color: [0.9, 0.1, 0.1],
+            team: 2,
+            kind: SavedPlayerKind::Human,
+        }
+    );
+    assert_eq!(save.players.len(), 2);
+
+    let [hero] = save.heroes.as_slice() else {
+        panic!("expected a single hero, got {}", save.heroes.len());
+    };
+    assert_eq!(hero.owner, 1);
+    assert_eq!(hero.hex, Hex::new(1, 0));
+    assert_eq!((hero.experience, hero.level), (250, 4));
+    assert_eq!(
+        hero.health,
+        SavedHealth {
+            current: 72,
+            max: 110
+        }
+    );
+    assert!(hero.has_moved);
+    assert_eq!(hero.action_points, 2);
+    assert_eq!(hero.journey, None);
+    assert!(hero.plan.is_empty());
+    assert_eq!(hero.units.len(), 4);
+    let melee = hero.units[0].as_ref().unwrap();
+    assert_eq!(melee.unit_type, SavedUnitType::Melee);
+    assert_eq!(melee.attack, Some(6));
+    assert!(hero.units[1].is_none());
+    let artillery = hero.units[2].as_ref().unwrap();
+    assert_eq!(artillery.unit_type, SavedUnitType::Artillery);
+    assert_eq!(artillery.health, None);
+
+    assert_eq!(save.turn.current_turn, 23);
+    assert_eq!(save.turn.max_turns, 150);
+    assert_eq!(save.turn.mode, SavedTurnMode::Sequential);
+    assert_eq!(save.turn.state, SavedGameplayState::PlayerTurn(2));
+}
+
+#[test]
+fn loads_version_1() {
+    assert_fixture_world(&SaveGame::from_ron(V1).unwrap());
+}
+
+#[test]
+fn loads_version_2() {
+    assert_fixture_world(&SaveGame::from_ron(V2).unwrap());
+}
+
+#[test]
+fn loads_version_3() {
+    assert_fixture_world(&SaveGame::from_ron(V3).unwrap());
+}
+
+#[test]
+fn loads_version_4() {
+    assert_fixture_world(&SaveGame::from_ron(V4).unwrap());
+}
+
+#[test]
+fn loads_version_5() {
+    assert_fixture_world(&SaveGame::from_ron(V5).unwrap());
+}
+
+#[test]
+fn loads_version_6() {
+    assert_fixture_world(&SaveGame::from_ron(V6).unwrap());
+}
+
+#[test]
+fn upgraded_saves_match_current_ones() {
+    let current = SaveGame::from_ron(V6).unwrap();
+
+    for old in [V1, V2, V3, V4, V5] {
+        let upgraded = SaveGame::from_ron(old).unwrap();
+        assert_eq!(upgraded, current);
+        assert_eq!(upgraded.to_ron().unwrap(), V6.trim_end());
+    }
+}
+
+#[test]
+fn rejects_saves_without_a_version() {
+    let text = V1.replacen("version: 1,", "", 1);
+
+    assert!(matches!(
+        SaveGame::from_ron(&text),
+        Err(SaveError::MissingVersion)
+    ));
+}
@@ -0,0 +1,54 @@
+use common::{
+    map::{generation::MapType, shape::MapShape},
+    setup::{GameSetup, MapSize, MaxTurns, SlotKind, TurnMode, MAX_PLAYERS, MIN_PLAYERS},
+};
+
+#[test]
+fn the_host_slot_is_human() {
+    let mut setup = GameSetup::default();
+    assert!(setup.is_valid());
+
+    setup.slots[0] = SlotKind::Ai;
+    assert!(!setup.is_valid());
+}
+
+#[test]
+fn player_counts_stay_in_range() {
+    let mut setup = GameSetup::default();
+
+    setup.set_player_count(5);
+    assert_eq!(
+        setup.slots,
+        vec![
+            SlotKind::Human,
+            SlotKind::Human,
+            SlotKind::Ai,
+            SlotKind::Ai,
+            SlotKind::Ai
+        ]
+    );
+
+    setup.set_player_count(1);
+    assert_eq!(setup.slots.len(), MIN_PLAYERS);
+    setup.set_player_count(100);
+    assert_eq!(setup.slots.len(), MAX_PLAYERS);
+    assert!(setup.is_valid());
+}
+
+#[test]
+fn generator_follows_the_setup() {
+    let setup = GameSetup {
+        map_size: MapSize::Small,
+        map_type: MapType::Pangaea,
+        seed: 17,
+        max_turns: MaxTurns::Long,
+        turn_mode: TurnMode::Simultaneous,
+        slots: vec![SlotKind::Human, SlotKind::Ai, SlotKind::Ai],
+    };
+    let generator = setup.generator();
+
+    assert_eq!(generator.seed, 17);
+    assert_eq!(generator.shape, MapShape::Hexagon { radius: 50 });
+    assert_eq!(generator.map_type, MapType::Pangaea);
+    assert_eq!(generator.player_count, 3);
+}
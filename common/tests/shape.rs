@@ -0,0 +1,72 @@
+use common::map::shape::MapShape;
+use hexx::Hex;
+
+const WRAPPING: MapShape = MapShape::Rectangle {
+    width: 10,
+    height: 6,
+    wrap_x: true,
+};
+
+#[test]
+fn neighbours_across_the_seam_are_adjacent() {
+    for hex in WRAPPING.hexes() {
+        for neighbor in hex.all_neighbors() {
+            let Some(wrapped) = WRAPPING.normalize(neighbor) else {
+                // past the top or bottom edge
+                assert!(!WRAPPING.contains(WRAPPING.wrap(neighbor)));
+                continue;
+            };
+
+            assert!(WRAPPING.contains(wrapped));
+            assert_eq!(WRAPPING.distance(hex, wrapped), 1, "{hex:?} {wrapped:?}");
+        }
+    }
+}
+
+#[test]
+fn wraps_the_east_edge_onto_the_west_edge() {
+    // the last column of an even and an odd row
+    assert_eq!(WRAPPING.normalize(Hex::new(5, 0)), Some(Hex::new(-5, 0)));
+    assert_eq!(WRAPPING.normalize(Hex::new(5, 1)), Some(Hex::new(-5, 1)));
+    assert_eq!(WRAPPING.wrap(Hex::new(4, 1)), Hex::new(4, 1));
+    assert_eq!(WRAPPING.normalize(Hex::new(0, 3)), None);
+}
+
+#[test]
+fn nearest_picks_the_copy_across_the_seam() {
+    assert_eq!(
+        WRAPPING.nearest(Hex::new(4, 0), Hex::new(-5, 0)),
+        Hex::new(5, 0)
+    );
+    assert_eq!(
+        WRAPPING.nearest(Hex::new(4, 1), Hex::new(-5, 1)),
+        Hex::new(5, 1)
+    );
+    assert_eq!(
+        WRAPPING.nearest(Hex::new(-5, 1), Hex::new(4, 1)),
+        Hex::new(-6, 1)
+    );
+    assert_eq!(
+        WRAPPING.nearest(Hex::new(0, 0), Hex::new(1, 0)),
+        Hex::new(1, 0)
+    );
+    assert_eq!(WRAPPING.distance(Hex::new(-5, 1), Hex::new(4, 1)), 1);
+}
+
+#[test]
+fn only_wrapping_maps_bound_searches() {
+    let flat = MapShape::Rectangle {
+        width: 10,
+        height: 6,
+        wrap_x: false,
+    };
+
+    assert_eq!(WRAPPING.search_limit(), Some(16));
+    assert_eq!(flat.search_limit(), None);
+    assert_eq!(MapShape::Hexagon { radius: 5 }.search_limit(), None);
+    assert_eq!(flat.normalize(Hex::new(5, 0)), None);
+    assert_eq!(
+        flat.nearest(Hex::new(4, 0), Hex::new(-5, 0)),
+        Hex::new(-5, 0)
+    );
+}
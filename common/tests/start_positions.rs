@@ -0,0 +1,96 @@
+use bevy::utils::HashMap;
+use common::map::{
+    components::{Biome, Tile},
+    generation::{generate_map, GeneratorSettings},
+    shape::MapShape,
+    start_positions::find_start_positions,
+};
+use hexx::Hex;
+
+const SHAPE: MapShape = MapShape::Hexagon { radius: 10 };
+
+fn tiles(biome: impl Fn(Hex) -> Biome) -> HashMap<Hex, Tile> {
+    SHAPE
+        .hexes()
+        .into_iter()
+        .map(|hex| {
+            (
+                hex,
+                Tile {
+                    biome: biome(hex),
+                    ..Default::default()
+                },
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn same_seed_gives_the_same_start_positions() {
+    let settings = GeneratorSettings {
+        seed: 11,
+        shape: MapShape::Hexagon { radius: 16 },
+        player_count: 4,
+        ..Default::default()
+    };
+
+    let first = generate_map(&settings).start_positions;
+    let second = generate_map(&settings).start_positions;
+
+    assert_eq!(first.positions.len(), 4);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn starts_are_walkable_land() {
+    // water and mountains everywhere but a few columns
+    let tiles = tiles(|hex| match hex.x.rem_euclid(4) {
+        0 => Biome::ShallowWater,
+        1 => Biome::Mountain,
+        2 => Biome::DeepWater,
+        _ => Biome::Plains,
+    });
+
+    let starts = find_start_positions(&tiles, &SHAPE, &SHAPE.hexes(), 6, 2);
+
+    assert_eq!(starts.len(), 6);
+    for start in starts {
+        assert_eq!(tiles[&start].biome, Biome::Plains);
+    }
+}
+
+#[test]
+fn starts_keep_their_distance() {
+    let tiles = tiles(|_| Biome::Plains);
+
+    for players in [2, 3, 6] {
+        let starts = find_start_positions(&tiles, &SHAPE, &SHAPE.hexes(), players, 2);
+        let closest = starts
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &a)| starts[i + 1..].iter().map(move |&b| SHAPE.distance(a, b)))
+            .min();
+
+        // the center and the corners are a whole radius apart
+        assert_eq!(starts.len(), players as usize);
+        assert!(
+            closest.is_some_and(|closest| closest >= 10),
+            "{players} players"
+        );
+    }
+}
+
+#[test]
+fn fewer_starts_only_when_land_runs_out() {
+    let tiles = tiles(|hex| {
+        if hex == Hex::ZERO || hex == Hex::new(3, 0) {
+            Biome::Plains
+        } else {
+            Biome::DeepWater
+        }
+    });
+
+    let starts = find_start_positions(&tiles, &SHAPE, &SHAPE.hexes(), 3, 2);
+
+    assert_eq!(starts.len(), 2);
+}
@@ -0,0 +1,229 @@
+use bevy::utils::HashMap;
+use common::{
+    map::{
+        components::Tile,
+        generation::{generate_map, GeneratorSettings},
+        pathfinding::find_path,
+        rivers::Rivers,
+    },
+    protocol::{
+        ClientMessage, GameSnapshot, HeroId, HeroState, PlayerId, Rejection, ServerMessage,
+    },
+    save::SavedMap,
+    setup::{GameSetup, SlotKind},
+};
+use hexx::Hex;
+
+/// Movement points every hero starts with, the same as on the client.
+pub const HERO_MOVEMENT_POINTS: u32 = 5;
+
+/// The authoritative state of one game. Commands are checked against it
+/// before anything changes.
+pub struct Game {
+    generator: GeneratorSettings,
+    tiles: HashMap<Hex, Tile>,
+    rivers: Rivers,
+    heroes: Vec<HeroState>,
+    /// The hero each player has selected, if any.
+    selected: HashMap<PlayerId, HeroId>,
+    players: Vec<PlayerId>,
+    ai_players: Vec<PlayerId>,
+    active: usize,
+    turn: u32,
+    max_turns: u32,
+    over: bool,
+}
+
+impl Game {
+    /// Generates the map and gives every player a hero on their start
+    /// position. Fails if the map has no room for every player.
+    pub fn new(setup: &GameSetup) -> Result<Self, Rejection> {
+        let generator = setup.generator();
+        let map = generate_map(&generator);
+
+        let players: Vec<PlayerId> = (1..=setup.slots.len() as u32).map(PlayerId).collect();
+        let ai_players = players
+            .iter()
+            .zip(setup.slots.iter())
+            .filter(|(_, &kind)| kind == SlotKind::Ai)
+            .map(|(&player, _)| player)
+            .collect();
+        let start_positions = map.start_positions.hexes();
+        if start_positions.len() < players.len() {
+            return Err(Rejection::TooFewStartPositions);
+        }
+        let heroes = start_positions
+            .into_iter()
+            .zip(players.iter())
+            .enumerate()
+            .map(|(index, (hex, &owner))| HeroState {
+                id: HeroId(index as u32),
+                owner,
+                hex,
+                movement_points: HERO_MOVEMENT_POINTS,
+                has_moved: false,
+            })
+            .collect();
+
+        Ok(Self {
+            generator,
+            tiles: map.tiles,
+            rivers: map.rivers,
+            heroes,
+            selected: HashMap::default(),
+            players,
+            ai_players,
+            active: 0,
+            turn: 1,
+            max_turns: setup.max_turns.get_max_turns(),
+            over: false,
+        })
+    }
+
+    pub fn players(&self) -> &[PlayerId] {
+        &self.players
+    }
+
+    pub fn active_player(&self) -> PlayerId {
+        self.players[self.active]
+    }
+
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    pub fn hero(&self, id: HeroId) -> Option<&HeroState> {
+        self.heroes.iter().find(|hero| hero.id == id)
+    }
+
+    pub fn selected_hero(&self, player: PlayerId) -> Option<HeroId> {
+        self.selected.get(&player).copied()
+    }
+
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            map: SavedMap::new(self.generator.clone(), &self.tiles, &self.rivers),
+            heroes: self.heroes.clone(),
+            players: self.players.clone(),
+            active_player: self.active_player(),
+            turn: self.turn,
+            max_turns: self.max_turns,
+        }
+    }
+
+    /// Applies a command from `player`, returning the changes every client
+    /// should hear about. Heroes can be selected out of turn, everything else
+    /// has to wait for the player's turn.
+    pub fn handle(
+        &mut self,
+        player: PlayerId,
+        message: &ClientMessage,
+    ) -> Result<Vec<ServerMessage>, Rejection> {
+        match *message {
+            ClientMessage::SelectHero { hero } => self.select_hero(player, hero),
+            ClientMessage::DeselectHero => {
+                self.selected.remove(&player);
+                Ok(Vec::new())
+            }
+            ClientMessage::ChangeSetup(_) | ClientMessage::SetReady(_) => {
+                Err(Rejection::GameAlreadyStarted)
+            }
+            _ if self.over => Err(Rejection::GameOver),
+            _ if player != self.active_player() => Err(Rejection::NotYourTurn),
+            ClientMessage::MoveHero { hero, to } => self.move_hero(player, hero, to),
+            ClientMessage::EndTurn => Ok(self.end_turn()),
+        }
+    }
+
+    fn select_hero(
+        &mut self,
+        player: PlayerId,
+        id: HeroId,
+    ) -> Result<Vec<ServerMessage>, Rejection> {
+        let hero = self.hero(id).ok_or(Rejection::UnknownHero)?;
+        if hero.owner != player {
+            return Err(Rejection::NotYourHero);
+        }
+
+        self.selected.insert(player, id);
+        Ok(Vec::new())
+    }
+
+    fn move_hero(
+        &mut self,
+        player: PlayerId,
+        id: HeroId,
+        to: Hex,
+    ) -> Result<Vec<ServerMessage>, Rejection> {
+        let shape = self.generator.shape;
+        let hero = self.hero(id).ok_or(Rejection::UnknownHero)?;
+        if hero.owner != player {
+            return Err(Rejection::NotYourHero);
+        }
+        if hero.has_moved {
+            return Err(Rejection::AlreadyMoved);
+        }
+
+        let to = shape.normalize(to).ok_or(Rejection::Unreachable)?;
+        let (path, cost) = find_path(&self.tiles, &shape, &self.rivers, hero.hex, to)
+            .ok_or(Rejection::Unreachable)?;
+        if path.is_empty() {
+            return Err(Rejection::Unreachable);
+        }
+        if cost > hero.movement_points {
+            return Err(Rejection::OutOfRange);
+        }
+        if self
+            .heroes
+            .iter()
+            .any(|other| other.id != id && other.hex == to)
+        {
+            return Err(Rejection::Occupied);
+        }
+
+        let hero = self
+            .heroes
+            .iter_mut()
+            .find(|hero| hero.id == id)
+            .expect("hero was found above");
+        hero.hex = to;
+        hero.has_moved = true;
+
+        Ok(vec![ServerMessage::HeroMoved { hero: id, path }])
+    }
+
+    /// Hands the turn to the next player, starting a new turn after the last.
+    /// The AI has nothing to decide yet, so its turns pass straight away, at
+    /// most once around the table if nobody else is playing.
+    fn end_turn(&mut self) -> Vec<ServerMessage> {
+        for _ in 0..self.players.len() {
+            self.active = (self.active + 1) % self.players.len();
+            if self.active == 0 {
+                self.turn += 1;
+            }
+            if !self.ai_players.contains(&self.active_player()) {
+                break;
+            }
+        }
+
+        if self.turn > self.max_turns {
+            self.over = true;
+            return vec![ServerMessage::GameOver];
+        }
+
+        let player = self.active_player();
+        for hero in self.heroes.iter_mut().filter(|hero| hero.owner == player) {
+            hero.movement_points = HERO_MOVEMENT_POINTS;
+            hero.has_moved = false;
+        }
+
+        vec![ServerMessage::TurnStarted {
+            player,
+            turn: self.turn,
+        }]
+    }
+}
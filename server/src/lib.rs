@@ -0,0 +1,4 @@
+pub mod game;
+pub mod lobby;
+pub mod net;
+pub mod replay;
@@ -0,0 +1,117 @@
+use common::{
+    protocol::{ClientMessage, LobbyState, PlayerId, Rejection, Seat, HOST},
+    setup::{GameSetup, SlotKind, TurnMode},
+};
+
+/// Seats players and collects the setup until everyone is ready. It keeps
+/// track of who is connected during the game as well.
+pub struct Lobby {
+    state: LobbyState,
+}
+
+impl Lobby {
+    pub fn new(setup: GameSetup) -> Self {
+        let seats = seats_for(&setup);
+
+        Self {
+            state: LobbyState { setup, seats },
+        }
+    }
+
+    pub fn state(&self) -> &LobbyState {
+        &self.state
+    }
+
+    pub fn setup(&self) -> &GameSetup {
+        &self.state.setup
+    }
+
+    /// Takes the first free human seat.
+    pub fn join(&mut self) -> Option<PlayerId> {
+        let seat = self
+            .state
+            .seats
+            .iter_mut()
+            .find(|seat| seat.kind == SlotKind::Human && !seat.taken)?;
+        seat.taken = true;
+        seat.ready = false;
+
+        Some(seat.player)
+    }
+
+    pub fn leave(&mut self, player: PlayerId) {
+        if let Some(seat) = self.seat_mut(player) {
+            seat.taken = false;
+            seat.ready = false;
+        }
+    }
+
+    /// Whether every human seat is taken by a player who is ready.
+    pub fn is_ready(&self) -> bool {
+        self.state
+            .seats
+            .iter()
+            .filter(|seat| seat.kind == SlotKind::Human)
+            .all(|seat| seat.taken && seat.ready)
+    }
+
+    pub fn handle(&mut self, player: PlayerId, message: &ClientMessage) -> Result<(), Rejection> {
+        match message {
+            ClientMessage::ChangeSetup(setup) => self.change_setup(player, setup),
+            ClientMessage::SetReady(ready) => {
+                if let Some(seat) = self.seat_mut(player) {
+                    seat.ready = *ready;
+                }
+                Ok(())
+            }
+            _ => Err(Rejection::GameNotStarted),
+        }
+    }
+
+    fn change_setup(&mut self, player: PlayerId, setup: &GameSetup) -> Result<(), Rejection> {
+        if player != HOST {
+            return Err(Rejection::NotHost);
+        }
+        if !setup.is_valid() {
+            return Err(Rejection::InvalidSetup);
+        }
+        if setup.turn_mode != TurnMode::Sequential {
+            return Err(Rejection::UnsupportedSetup);
+        }
+
+        let mut seats = seats_for(setup);
+        for taken in self.state.seats.iter().filter(|seat| seat.taken) {
+            match seats.iter_mut().find(|seat| seat.player == taken.player) {
+                Some(seat) if seat.kind == SlotKind::Human => seat.taken = true,
+                _ => return Err(Rejection::SeatTaken),
+            }
+        }
+
+        self.state = LobbyState {
+            setup: setup.clone(),
+            seats,
+        };
+        Ok(())
+    }
+
+    fn seat_mut(&mut self, player: PlayerId) -> Option<&mut Seat> {
+        self.state
+            .seats
+            .iter_mut()
+            .find(|seat| seat.player == player)
+    }
+}
+
+fn seats_for(setup: &GameSetup) -> Vec<Seat> {
+    setup
+        .slots
+        .iter()
+        .enumerate()
+        .map(|(index, &kind)| Seat {
+            player: PlayerId(index as u32 + 1),
+            kind,
+            taken: false,
+            ready: false,
+        })
+        .collect()
+}
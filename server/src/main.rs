@@ -1,3 +1,50 @@
+use std::{env, process};
+
+use common::setup::{GameSetup, SlotKind};
+use server::net::Server;
+
+const DEFAULT_BIND: &str = "127.0.0.1:7777";
+
 fn main() {
-    println!("Hello, world!");
+    let mut bind = DEFAULT_BIND.to_string();
+    let mut setup = GameSetup::default();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next();
+        let parsed = match (arg.as_str(), value) {
+            ("--bind", Some(value)) => {
+                bind = value;
+                true
+            }
+            ("--seed", Some(value)) => value.parse().map(|seed| setup.seed = seed).is_ok(),
+            ("--players", Some(value)) => value
+                .parse()
+                .map(|players| setup.slots = vec![SlotKind::Human; players])
+                .is_ok(),
+            _ => false,
+        };
+
+        if !parsed || !setup.is_valid() {
+            eprintln!("usage: server [--bind host:port] [--seed n] [--players 2-8]");
+            process::exit(2);
+        }
+    }
+
+    let server = match Server::bind(&bind, setup) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!("Could not listen on {bind}: {error}");
+            process::exit(1);
+        }
+    };
+
+    match server.local_addr() {
+        Ok(addr) => println!("Listening on {addr}, waiting in the lobby"),
+        Err(_) => println!("Listening on {bind}, waiting in the lobby"),
+    }
+    if let Err(error) = server.run() {
+        eprintln!("Server stopped: {error}");
+        process::exit(1);
+    }
 }
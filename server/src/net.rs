@@ -0,0 +1,248 @@
+use std::{
+    io,
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+use bevy::utils::HashMap;
+use common::{
+    protocol::{
+        codec::{read_frame, write_frame},
+        ClientMessage, Hello, PlayerId, Rejection, ServerMessage, PROTOCOL_VERSION,
+    },
+    setup::GameSetup,
+};
+
+use crate::{game::Game, lobby::Lobby};
+
+type ConnectionId = u32;
+
+/// What the connection threads report to the game loop.
+enum NetEvent {
+    Connected(ConnectionId, TcpStream),
+    Greeted(ConnectionId, Hello),
+    Received(ConnectionId, ClientMessage),
+    Disconnected(ConnectionId),
+}
+
+struct Client {
+    stream: TcpStream,
+    player: PlayerId,
+}
+
+/// Hosts a game over TCP. Clients are seated in the `Lobby` once their
+/// `Hello` is accepted, and the game starts when every human player is ready.
+/// Commands are validated by the `Game` and the resulting changes are sent to
+/// everyone.
+pub struct Server {
+    listener: TcpListener,
+    lobby: Lobby,
+    game: Option<Game>,
+}
+
+impl Server {
+    pub fn bind(addr: impl ToSocketAddrs, setup: GameSetup) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            lobby: Lobby::new(setup),
+            game: None,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serves clients until the listener fails.
+    pub fn run(mut self) -> io::Result<()> {
+        let (sender, receiver) = mpsc::channel();
+        let listener = self.listener.try_clone()?;
+        thread::spawn(move || accept_connections(listener, sender));
+
+        self.process(receiver);
+        Ok(())
+    }
+
+    fn process(&mut self, receiver: Receiver<NetEvent>) {
+        // connections that haven't sent their `Hello` yet
+        let mut pending: HashMap<ConnectionId, TcpStream> = HashMap::default();
+        let mut clients: HashMap<ConnectionId, Client> = HashMap::default();
+
+        for event in receiver {
+            match event {
+                NetEvent::Connected(id, stream) => {
+                    pending.insert(id, stream);
+                }
+                NetEvent::Greeted(id, hello) => {
+                    let Some(stream) = pending.remove(&id) else {
+                        continue;
+                    };
+                    self.greet(id, stream, hello, &mut clients);
+                }
+                NetEvent::Received(id, message) => {
+                    let Some(client) = clients.get_mut(&id) else {
+                        continue;
+                    };
+                    let player = client.player;
+
+                    match self.handle(player, &message) {
+                        Ok(changes) => {
+                            for change in changes.iter() {
+                                broadcast(&mut clients, change);
+                            }
+                        }
+                        Err(rejection) => {
+                            let _ = write_frame(
+                                &mut client.stream,
+                                &ServerMessage::Rejected(rejection),
+                            );
+                        }
+                    }
+                }
+                NetEvent::Disconnected(id) => {
+                    pending.remove(&id);
+                    if let Some(client) = clients.remove(&id) {
+                        println!("Player {} left", client.player.0);
+                        self.lobby.leave(client.player);
+                        broadcast(
+                            &mut clients,
+                            &ServerMessage::LobbyChanged(self.lobby.state().clone()),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Seats a client that said hello, or tells it why it can't join.
+    fn greet(
+        &mut self,
+        id: ConnectionId,
+        mut stream: TcpStream,
+        hello: Hello,
+        clients: &mut HashMap<ConnectionId, Client>,
+    ) {
+        if hello.protocol_version != PROTOCOL_VERSION {
+            let mismatch = ServerMessage::VersionMismatch {
+                server_version: PROTOCOL_VERSION,
+            };
+            let _ = write_frame(&mut stream, &mismatch);
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+
+        let Some(player) = self.lobby.join() else {
+            let _ = write_frame(&mut stream, &ServerMessage::Rejected(Rejection::GameFull));
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        };
+
+        let welcome = ServerMessage::Welcome {
+            player,
+            lobby: self.lobby.state().clone(),
+        };
+        let mut joined = write_frame(&mut stream, &welcome).is_ok();
+        if let Some(game) = self.game.as_ref() {
+            let started = ServerMessage::GameStarted(Box::new(game.snapshot()));
+            joined = joined && write_frame(&mut stream, &started).is_ok();
+        }
+        if !joined {
+            self.lobby.leave(player);
+            return;
+        }
+
+        println!("Player {} joined", player.0);
+        broadcast(
+            clients,
+            &ServerMessage::LobbyChanged(self.lobby.state().clone()),
+        );
+        clients.insert(id, Client { stream, player });
+    }
+
+    /// Passes a command to the lobby or, once it started, the game.
+    fn handle(
+        &mut self,
+        player: PlayerId,
+        message: &ClientMessage,
+    ) -> Result<Vec<ServerMessage>, Rejection> {
+        if let Some(game) = self.game.as_mut() {
+            return game.handle(player, message);
+        }
+
+        self.lobby.handle(player, message)?;
+        let mut changes = vec![ServerMessage::LobbyChanged(self.lobby.state().clone())];
+
+        if self.lobby.is_ready() {
+            let game = match Game::new(self.lobby.setup()) {
+                Ok(game) => game,
+                Err(rejection) => {
+                    // keep the lobby waiting until the host picks another setup
+                    self.lobby.handle(player, &ClientMessage::SetReady(false))?;
+                    return Err(rejection);
+                }
+            };
+            println!("Starting the game, map seed {}", self.lobby.setup().seed);
+            changes.push(ServerMessage::GameStarted(Box::new(game.snapshot())));
+            self.game = Some(game);
+        }
+
+        Ok(changes)
+    }
+}
+
+/// Sends `message` to every client. Clients that can't be reached are left
+/// to their reader threads, which report the disconnect.
+fn broadcast(clients: &mut HashMap<ConnectionId, Client>, message: &ServerMessage) {
+    for client in clients.values_mut() {
+        let _ = write_frame(&mut client.stream, message);
+    }
+}
+
+fn accept_connections(listener: TcpListener, sender: Sender<NetEvent>) {
+    for (id, stream) in listener.incoming().enumerate() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let id = id as ConnectionId;
+        let _ = stream.set_nodelay(true);
+        let Ok(reader) = stream.try_clone() else {
+            continue;
+        };
+
+        if sender.send(NetEvent::Connected(id, stream)).is_err() {
+            return;
+        }
+
+        let sender = sender.clone();
+        thread::spawn(move || read_messages(id, reader, sender));
+    }
+}
+
+/// Reads the client's `Hello`, then its commands until it disconnects.
+fn read_messages(id: ConnectionId, mut stream: TcpStream, sender: Sender<NetEvent>) {
+    let mut greeted = false;
+    loop {
+        let event = if greeted {
+            read_frame(&mut stream).map(|message| NetEvent::Received(id, message))
+        } else {
+            read_frame(&mut stream).map(|hello| NetEvent::Greeted(id, hello))
+        };
+
+        match event {
+            Ok(event) => {
+                greeted = true;
+                if sender.send(event).is_err() {
+                    return;
+                }
+            }
+            Err(error) => {
+                if !error.is_disconnect() {
+                    eprintln!("Dropping connection {id}: {error}");
+                }
+                let _ = sender.send(NetEvent::Disconnected(id));
+                return;
+            }
+        }
+    }
+}
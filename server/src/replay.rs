@@ -0,0 +1,85 @@
+//! Plays command logs recorded by clients without a window, to reproduce
+//! bug reports.
+
+use std::fmt;
+
+use common::{
+    protocol::{ClientMessage, Rejection},
+    replay::{CommandLog, LoggedAction, LoggedCommand},
+};
+
+use crate::game::Game;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The log's setup can't start a game.
+    Setup(Rejection),
+    /// The command at `index` was logged for a different turn than the one
+    /// the replay reached.
+    WrongTurn {
+        index: usize,
+        turn: u32,
+    },
+    /// The server doesn't play the command at `index` yet.
+    Unsupported {
+        index: usize,
+    },
+    Rejected {
+        index: usize,
+        rejection: Rejection,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Setup(rejection) => write!(f, "the game could not start: {rejection:?}"),
+            ReplayError::WrongTurn { index, turn } => {
+                write!(f, "command {index} was logged in turn {turn}")
+            }
+            ReplayError::Unsupported { index } => {
+                write!(f, "command {index} can only be replayed by the client")
+            }
+            ReplayError::Rejected { index, rejection } => {
+                write!(f, "command {index} was rejected: {rejection:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Starts a game from the log's setup and carries out every logged command
+/// in order, stopping at the first one that doesn't play out as logged.
+pub fn replay(log: &CommandLog) -> Result<Game, ReplayError> {
+    let mut game = Game::new(&log.setup).map_err(ReplayError::Setup)?;
+
+    for (index, command) in log.commands.iter().enumerate() {
+        if command.turn != game.turn() {
+            return Err(ReplayError::WrongTurn {
+                index,
+                turn: command.turn,
+            });
+        }
+
+        let message = message(command).ok_or(ReplayError::Unsupported { index })?;
+        game.handle(command.player, &message)
+            .map_err(|rejection| ReplayError::Rejected { index, rejection })?;
+    }
+
+    Ok(game)
+}
+
+fn message(command: &LoggedCommand) -> Option<ClientMessage> {
+    match command.action {
+        LoggedAction::Move { hero, to } => Some(ClientMessage::MoveHero { hero, to }),
+        LoggedAction::EndTurn => Some(ClientMessage::EndTurn),
+        LoggedAction::Attack { .. }
+        | LoggedAction::Wait { .. }
+        | LoggedAction::Cancel { .. }
+        | LoggedAction::Journey { .. }
+        | LoggedAction::EndJourney { .. }
+        | LoggedAction::Undo
+        | LoggedAction::Redo => None,
+    }
+}
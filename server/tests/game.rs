@@ -0,0 +1,306 @@
+use common::{
+    map::{generation::MapType, pathfinding::find_path},
+    protocol::{ClientMessage, GameSnapshot, HeroState, PlayerId, Rejection, ServerMessage},
+    setup::{GameSetup, MapSize, MaxTurns, SlotKind},
+};
+use hexx::Hex;
+use server::game::{Game, HERO_MOVEMENT_POINTS};
+
+fn setup(slots: Vec<SlotKind>) -> GameSetup {
+    GameSetup {
+        map_size: MapSize::Tiny,
+        seed: 42,
+        slots,
+        ..Default::default()
+    }
+}
+
+fn game() -> Game {
+    Game::new(&setup(vec![SlotKind::Human, SlotKind::Human])).unwrap()
+}
+
+fn hero_of(snapshot: &GameSnapshot, player: PlayerId) -> HeroState {
+    snapshot
+        .heroes
+        .iter()
+        .find(|hero| hero.owner == player)
+        .cloned()
+        .unwrap()
+}
+
+/// A hex next to the hero it can walk to, and one it can't reach this turn.
+fn targets(snapshot: &GameSnapshot, hero: &HeroState) -> (Hex, Hex) {
+    let tiles = snapshot.map.tiles();
+    let rivers = snapshot.map.rivers();
+    let shape = snapshot.map.generator.shape;
+    let cost = |hex| find_path(&tiles, &shape, &rivers, hero.hex, hex).map(|(_, cost)| cost);
+
+    let near = hero
+        .hex
+        .all_neighbors()
+        .into_iter()
+        .find(|&hex| cost(hex).is_some_and(|cost| cost <= hero.movement_points))
+        .unwrap();
+    let far = shape
+        .hexes()
+        .into_iter()
+        .find(|&hex| cost(hex).is_some_and(|cost| cost > hero.movement_points))
+        .unwrap();
+
+    (near, far)
+}
+
+#[test]
+fn every_player_starts_with_a_hero() {
+    let game = game();
+    let snapshot = game.snapshot();
+
+    assert_eq!(snapshot.players, vec![PlayerId(1), PlayerId(2)]);
+    assert_eq!(snapshot.active_player, PlayerId(1));
+    assert_eq!(snapshot.turn, 1);
+    for player in snapshot.players.iter() {
+        let hero = hero_of(&snapshot, *player);
+        assert_eq!(hero.movement_points, HERO_MOVEMENT_POINTS);
+        assert!(!hero.has_moved);
+    }
+}
+
+#[test]
+fn moves_heroes_within_range() {
+    let mut game = game();
+    let snapshot = game.snapshot();
+    let hero = hero_of(&snapshot, PlayerId(1));
+    let (near, _) = targets(&snapshot, &hero);
+
+    let changes = game
+        .handle(
+            PlayerId(1),
+            &ClientMessage::MoveHero {
+                hero: hero.id,
+                to: near,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(
+        changes,
+        vec![ServerMessage::HeroMoved {
+            hero: hero.id,
+            path: vec![near]
+        }]
+    );
+    let moved = game.hero(hero.id).unwrap();
+    assert_eq!(moved.hex, near);
+    assert!(moved.has_moved);
+}
+
+#[test]
+fn rejects_invalid_moves() {
+    let mut game = game();
+    let snapshot = game.snapshot();
+    let hero = hero_of(&snapshot, PlayerId(1));
+    let other = hero_of(&snapshot, PlayerId(2));
+    let (near, far) = targets(&snapshot, &hero);
+
+    let mut move_to = |player, hero, to| game.handle(player, &ClientMessage::MoveHero { hero, to });
+
+    assert_eq!(
+        move_to(PlayerId(2), other.id, near),
+        Err(Rejection::NotYourTurn)
+    );
+    assert_eq!(
+        move_to(PlayerId(1), other.id, near),
+        Err(Rejection::NotYourHero)
+    );
+    assert_eq!(
+        move_to(PlayerId(1), hero.id, far),
+        Err(Rejection::OutOfRange)
+    );
+    assert_eq!(
+        move_to(PlayerId(1), hero.id, Hex::new(1000, 0)),
+        Err(Rejection::Unreachable)
+    );
+    assert!(move_to(PlayerId(1), hero.id, near).is_ok());
+    assert_eq!(
+        move_to(PlayerId(1), hero.id, hero.hex),
+        Err(Rejection::AlreadyMoved)
+    );
+    assert_eq!(game.hero(hero.id).unwrap().hex, near);
+}
+
+#[test]
+fn heroes_cannot_share_a_hex() {
+    // a single landmass, so the heroes can walk up to each other
+    let mut game = Game::new(&GameSetup {
+        map_type: MapType::Pangaea,
+        ..setup(vec![SlotKind::Human, SlotKind::Human])
+    })
+    .unwrap();
+    let snapshot = game.snapshot();
+    let hero = hero_of(&snapshot, PlayerId(1));
+    let other = hero_of(&snapshot, PlayerId(2));
+    let (path, _) = find_path(
+        &snapshot.map.tiles(),
+        &snapshot.map.generator.shape,
+        &snapshot.map.rivers(),
+        hero.hex,
+        other.hex,
+    )
+    .unwrap();
+
+    // one step a turn, so every step is in range
+    for &to in &path[..path.len() - 1] {
+        game.handle(PlayerId(1), &ClientMessage::MoveHero { hero: hero.id, to })
+            .unwrap();
+        game.handle(PlayerId(1), &ClientMessage::EndTurn).unwrap();
+        game.handle(PlayerId(2), &ClientMessage::EndTurn).unwrap();
+    }
+
+    assert_eq!(
+        game.handle(
+            PlayerId(1),
+            &ClientMessage::MoveHero {
+                hero: hero.id,
+                to: other.hex
+            }
+        ),
+        Err(Rejection::Occupied)
+    );
+    assert_eq!(game.hero(hero.id).unwrap().hex, path[path.len() - 2]);
+}
+
+#[test]
+fn ending_turns_cycles_players_and_resets_heroes() {
+    let mut game = game();
+    let snapshot = game.snapshot();
+    let hero = hero_of(&snapshot, PlayerId(1));
+    let (near, _) = targets(&snapshot, &hero);
+
+    game.handle(
+        PlayerId(1),
+        &ClientMessage::MoveHero {
+            hero: hero.id,
+            to: near,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        game.handle(PlayerId(1), &ClientMessage::EndTurn),
+        Ok(vec![ServerMessage::TurnStarted {
+            player: PlayerId(2),
+            turn: 1
+        }])
+    );
+    assert_eq!(
+        game.handle(PlayerId(1), &ClientMessage::EndTurn),
+        Err(Rejection::NotYourTurn)
+    );
+    assert_eq!(
+        game.handle(PlayerId(2), &ClientMessage::EndTurn),
+        Ok(vec![ServerMessage::TurnStarted {
+            player: PlayerId(1),
+            turn: 2
+        }])
+    );
+    assert!(!game.hero(hero.id).unwrap().has_moved);
+}
+
+#[test]
+fn players_select_their_own_heroes_at_any_time() {
+    let mut game = game();
+    let snapshot = game.snapshot();
+    let hero = hero_of(&snapshot, PlayerId(2));
+    let other = hero_of(&snapshot, PlayerId(1));
+
+    assert_eq!(
+        game.handle(PlayerId(2), &ClientMessage::SelectHero { hero: other.id }),
+        Err(Rejection::NotYourHero)
+    );
+    assert_eq!(
+        game.handle(PlayerId(2), &ClientMessage::SelectHero { hero: hero.id }),
+        Ok(Vec::new())
+    );
+    assert_eq!(game.selected_hero(PlayerId(2)), Some(hero.id));
+
+    game.handle(PlayerId(2), &ClientMessage::DeselectHero)
+        .unwrap();
+    assert_eq!(game.selected_hero(PlayerId(2)), None);
+}
+
+#[test]
+fn ai_turns_pass_straight_away() {
+    let mut game = Game::new(&setup(vec![SlotKind::Human, SlotKind::Ai, SlotKind::Human])).unwrap();
+
+    assert_eq!(
+        game.handle(PlayerId(1), &ClientMessage::EndTurn),
+        Ok(vec![ServerMessage::TurnStarted {
+            player: PlayerId(3),
+            turn: 1
+        }])
+    );
+    assert_eq!(
+        game.handle(PlayerId(3), &ClientMessage::EndTurn),
+        Ok(vec![ServerMessage::TurnStarted {
+            player: PlayerId(1),
+            turn: 2
+        }])
+    );
+}
+
+#[test]
+fn ai_only_games_still_end_turns() {
+    let mut game = Game::new(&setup(vec![SlotKind::Ai, SlotKind::Ai])).unwrap();
+
+    assert_eq!(
+        game.handle(PlayerId(1), &ClientMessage::EndTurn),
+        Ok(vec![ServerMessage::TurnStarted {
+            player: PlayerId(1),
+            turn: 2
+        }])
+    );
+}
+
+#[test]
+fn needs_a_start_position_for_every_player() {
+    assert_eq!(
+        Game::new(&setup(vec![SlotKind::Human; 5000])).err(),
+        Some(Rejection::TooFewStartPositions)
+    );
+}
+
+#[test]
+fn the_game_ends_at_the_turn_limit() {
+    let mut game = Game::new(&GameSetup {
+        max_turns: MaxTurns::Quick,
+        ..setup(vec![SlotKind::Human, SlotKind::Ai])
+    })
+    .unwrap();
+    let max_turns = MaxTurns::Quick.get_max_turns();
+
+    for _ in 1..max_turns {
+        game.handle(PlayerId(1), &ClientMessage::EndTurn).unwrap();
+    }
+    assert_eq!(game.turn(), max_turns);
+    assert!(!game.is_over());
+
+    assert_eq!(
+        game.handle(PlayerId(1), &ClientMessage::EndTurn),
+        Ok(vec![ServerMessage::GameOver])
+    );
+    assert!(game.is_over());
+    assert_eq!(
+        game.handle(PlayerId(1), &ClientMessage::EndTurn),
+        Err(Rejection::GameOver)
+    );
+}
+
+#[test]
+fn the_setup_is_fixed_once_the_game_runs() {
+    let mut game = game();
+
+    assert_eq!(
+        game.handle(PlayerId(1), &ClientMessage::SetReady(false)),
+        Err(Rejection::GameAlreadyStarted)
+    );
+}
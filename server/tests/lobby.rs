@@ -0,0 +1,119 @@
+use common::{
+    protocol::{ClientMessage, PlayerId, Rejection, HOST},
+    setup::{GameSetup, SlotKind, TurnMode},
+};
+use server::lobby::Lobby;
+
+fn lobby(slots: Vec<SlotKind>) -> Lobby {
+    Lobby::new(GameSetup {
+        seed: 3,
+        slots,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn players_take_the_human_seats_in_order() {
+    let mut lobby = lobby(vec![SlotKind::Human, SlotKind::Ai, SlotKind::Human]);
+
+    assert_eq!(lobby.join(), Some(PlayerId(1)));
+    assert_eq!(lobby.join(), Some(PlayerId(3)));
+    assert_eq!(lobby.join(), None);
+
+    lobby.leave(PlayerId(1));
+    assert_eq!(lobby.join(), Some(PlayerId(1)));
+}
+
+#[test]
+fn starts_once_every_human_is_ready() {
+    let mut lobby = lobby(vec![SlotKind::Human, SlotKind::Ai, SlotKind::Human]);
+    let first = lobby.join().unwrap();
+    lobby.handle(first, &ClientMessage::SetReady(true)).unwrap();
+    assert!(!lobby.is_ready());
+
+    let second = lobby.join().unwrap();
+    assert!(!lobby.is_ready());
+    lobby
+        .handle(second, &ClientMessage::SetReady(true))
+        .unwrap();
+    assert!(lobby.is_ready());
+
+    lobby.leave(second);
+    assert!(!lobby.is_ready());
+}
+
+#[test]
+fn only_the_host_changes_valid_setups() {
+    let mut lobby = lobby(vec![SlotKind::Human, SlotKind::Human]);
+    let host = lobby.join().unwrap();
+    let guest = lobby.join().unwrap();
+    assert_eq!(host, HOST);
+    lobby.handle(guest, &ClientMessage::SetReady(true)).unwrap();
+
+    let mut setup = lobby.setup().clone();
+    setup.seed = 99;
+    setup.slots.push(SlotKind::Ai);
+
+    assert_eq!(
+        lobby.handle(guest, &ClientMessage::ChangeSetup(setup.clone())),
+        Err(Rejection::NotHost)
+    );
+    let mut invalid = setup.clone();
+    invalid.slots[0] = SlotKind::Ai;
+    assert_eq!(
+        lobby.handle(host, &ClientMessage::ChangeSetup(invalid)),
+        Err(Rejection::InvalidSetup)
+    );
+
+    lobby
+        .handle(host, &ClientMessage::ChangeSetup(setup.clone()))
+        .unwrap();
+    assert_eq!(lobby.setup(), &setup);
+    let seats = &lobby.state().seats;
+    assert_eq!(seats.len(), 3);
+    assert!(seats[1].taken);
+    assert!(!seats[1].ready);
+    assert_eq!(seats[2].kind, SlotKind::Ai);
+}
+
+#[test]
+fn setups_may_not_unseat_connected_players() {
+    let mut lobby = lobby(vec![SlotKind::Human, SlotKind::Human]);
+    let host = lobby.join().unwrap();
+    lobby.join().unwrap();
+
+    let mut setup = lobby.setup().clone();
+    setup.slots[1] = SlotKind::Ai;
+
+    assert_eq!(
+        lobby.handle(host, &ClientMessage::ChangeSetup(setup)),
+        Err(Rejection::SeatTaken)
+    );
+}
+
+#[test]
+fn simultaneous_turns_are_not_played_online() {
+    let mut lobby = lobby(vec![SlotKind::Human, SlotKind::Human]);
+    let host = lobby.join().unwrap();
+
+    let setup = GameSetup {
+        turn_mode: TurnMode::Simultaneous,
+        ..lobby.setup().clone()
+    };
+    assert_eq!(
+        lobby.handle(host, &ClientMessage::ChangeSetup(setup)),
+        Err(Rejection::UnsupportedSetup)
+    );
+    assert_eq!(lobby.setup().turn_mode, TurnMode::Sequential);
+}
+
+#[test]
+fn game_commands_wait_for_the_game() {
+    let mut lobby = lobby(vec![SlotKind::Human, SlotKind::Human]);
+    let host = lobby.join().unwrap();
+
+    assert_eq!(
+        lobby.handle(host, &ClientMessage::EndTurn),
+        Err(Rejection::GameNotStarted)
+    );
+}
@@ -0,0 +1,182 @@
+use std::{net::TcpStream, thread, time::Duration};
+
+use common::{
+    protocol::{
+        codec::{read_frame, write_frame},
+        ClientMessage, Hello, LobbyState, PlayerId, Rejection, ServerMessage, PROTOCOL_VERSION,
+    },
+    setup::{GameSetup, MapSize, SlotKind},
+};
+use server::net::Server;
+
+/// Starts a server for two human players on a free local port.
+fn start_server() -> String {
+    let setup = GameSetup {
+        map_size: MapSize::Tiny,
+        seed: 7,
+        slots: vec![SlotKind::Human, SlotKind::Human],
+        ..Default::default()
+    };
+    let server = Server::bind("127.0.0.1:0", setup).unwrap();
+    let addr = server.local_addr().unwrap().to_string();
+    thread::spawn(move || server.run());
+
+    addr
+}
+
+/// Opens a connection and says hello with `protocol_version`.
+fn connect(addr: &str, protocol_version: u32) -> TcpStream {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    write_frame(&mut stream, &Hello { protocol_version }).unwrap();
+    stream
+}
+
+fn receive(stream: &mut TcpStream) -> ServerMessage {
+    read_frame(stream).unwrap()
+}
+
+/// The next message that isn't a lobby update.
+fn receive_skipping_lobby(stream: &mut TcpStream) -> ServerMessage {
+    loop {
+        match receive(stream) {
+            ServerMessage::LobbyChanged(_) => continue,
+            message => return message,
+        }
+    }
+}
+
+fn join(addr: &str) -> (TcpStream, PlayerId, LobbyState) {
+    let mut stream = connect(addr, PROTOCOL_VERSION);
+    match receive(&mut stream) {
+        ServerMessage::Welcome { player, lobby } => (stream, player, lobby),
+        message => panic!("expected a welcome, got {message:?}"),
+    }
+}
+
+/// Reads lobby updates until one carries `setup`.
+fn receive_setup(stream: &mut TcpStream, setup: &GameSetup) -> Option<LobbyState> {
+    for _ in 0..4 {
+        if let ServerMessage::LobbyChanged(lobby) = receive(stream) {
+            if &lobby.setup == setup {
+                return Some(lobby);
+            }
+        }
+    }
+
+    None
+}
+
+/// Joins both players and readies them up.
+fn start_game(addr: &str) -> (TcpStream, TcpStream) {
+    let (mut first, _, _) = join(addr);
+    let (mut second, _, _) = join(addr);
+
+    for stream in [&mut first, &mut second] {
+        write_frame(stream, &ClientMessage::SetReady(true)).unwrap();
+    }
+    for stream in [&mut first, &mut second] {
+        match receive_skipping_lobby(stream) {
+            ServerMessage::GameStarted(snapshot) => {
+                assert_eq!(snapshot.players, vec![PlayerId(1), PlayerId(2)]);
+            }
+            message => panic!("expected the game to start, got {message:?}"),
+        }
+    }
+
+    (first, second)
+}
+
+#[test]
+fn clients_get_their_own_seat() {
+    let addr = start_server();
+
+    let (mut first, first_player, _) = join(&addr);
+    let (_second, second_player, lobby) = join(&addr);
+    assert_eq!(first_player, PlayerId(1));
+    assert_eq!(second_player, PlayerId(2));
+    assert!(lobby.seats.iter().all(|seat| seat.taken));
+
+    match receive(&mut first) {
+        ServerMessage::LobbyChanged(changed) => assert_eq!(changed, lobby),
+        message => panic!("expected a lobby update, got {message:?}"),
+    }
+
+    let mut third = connect(&addr, PROTOCOL_VERSION);
+    assert_eq!(
+        receive(&mut third),
+        ServerMessage::Rejected(Rejection::GameFull)
+    );
+}
+
+#[test]
+fn clients_of_another_version_are_turned_away() {
+    let addr = start_server();
+
+    let mut stream = connect(&addr, PROTOCOL_VERSION + 1);
+    assert_eq!(
+        receive(&mut stream),
+        ServerMessage::VersionMismatch {
+            server_version: PROTOCOL_VERSION
+        }
+    );
+
+    let (_first, player, _) = join(&addr);
+    assert_eq!(player, PlayerId(1));
+}
+
+#[test]
+fn only_the_host_changes_the_setup() {
+    let addr = start_server();
+    let (mut first, _, lobby) = join(&addr);
+    let (mut second, _, _) = join(&addr);
+
+    let mut setup = lobby.setup.clone();
+    setup.seed = 8;
+    write_frame(&mut second, &ClientMessage::ChangeSetup(setup.clone())).unwrap();
+    assert_eq!(
+        receive(&mut second),
+        ServerMessage::Rejected(Rejection::NotHost)
+    );
+
+    write_frame(&mut first, &ClientMessage::ChangeSetup(setup.clone())).unwrap();
+    match receive_setup(&mut second, &setup) {
+        Some(lobby) => assert_eq!(lobby.setup.seed, 8),
+        None => panic!("the setup change never arrived"),
+    }
+}
+
+#[test]
+fn commands_are_validated_and_broadcast() {
+    let addr = start_server();
+    let (mut first, mut second) = start_game(&addr);
+
+    write_frame(&mut second, &ClientMessage::EndTurn).unwrap();
+    assert_eq!(
+        receive(&mut second),
+        ServerMessage::Rejected(Rejection::NotYourTurn)
+    );
+
+    write_frame(&mut first, &ClientMessage::EndTurn).unwrap();
+    let started = ServerMessage::TurnStarted {
+        player: PlayerId(2),
+        turn: 1,
+    };
+    assert_eq!(receive(&mut first), started);
+    assert_eq!(receive(&mut second), started);
+}
+
+#[test]
+fn players_rejoin_a_running_game() {
+    let addr = start_server();
+    let (first, _second) = start_game(&addr);
+
+    drop(first);
+    thread::sleep(Duration::from_millis(200));
+
+    let (mut third, player, _) = join(&addr);
+    assert_eq!(player, PlayerId(1));
+    assert!(matches!(receive(&mut third), ServerMessage::GameStarted(_)));
+}
@@ -0,0 +1,118 @@
+use common::{
+    map::pathfinding::find_path,
+    protocol::{ClientMessage, HeroId, PlayerId, Rejection},
+    replay::{world_hash, CommandLog, LoggedAction},
+    setup::{GameSetup, MapSize, SlotKind},
+};
+use hexx::Hex;
+use server::{
+    game::Game,
+    replay::{replay, ReplayError},
+};
+
+fn setup() -> GameSetup {
+    GameSetup {
+        map_size: MapSize::Tiny,
+        seed: 7,
+        slots: vec![SlotKind::Human, SlotKind::Human],
+        ..Default::default()
+    }
+}
+
+/// A hex next to `hero` it can walk to this turn.
+fn near(game: &Game, hero: HeroId) -> Hex {
+    let snapshot = game.snapshot();
+    let tiles = snapshot.map.tiles();
+    let rivers = snapshot.map.rivers();
+    let shape = snapshot.map.generator.shape;
+    let hero = game.hero(hero).unwrap();
+
+    hero.hex
+        .all_neighbors()
+        .into_iter()
+        .find(|&hex| {
+            find_path(&tiles, &shape, &rivers, hero.hex, hex)
+                .is_some_and(|(_, cost)| cost <= hero.movement_points)
+        })
+        .unwrap()
+}
+
+/// Carries out `action` and logs it the way the client does.
+fn play(game: &mut Game, log: &mut CommandLog, player: PlayerId, action: LoggedAction) {
+    let message = match action {
+        LoggedAction::Move { hero, to } => ClientMessage::MoveHero { hero, to },
+        LoggedAction::EndTurn => ClientMessage::EndTurn,
+        _ => unreachable!("the server only moves heroes and ends turns"),
+    };
+
+    log.record(game.turn(), player, action);
+    game.handle(player, &message).unwrap();
+}
+
+fn played_log() -> (Game, CommandLog) {
+    let mut game = Game::new(&setup()).unwrap();
+    let mut log = CommandLog::new(setup());
+
+    for _ in 0..2 {
+        for (player, hero) in [(PlayerId(1), HeroId(0)), (PlayerId(2), HeroId(1))] {
+            let to = near(&game, hero);
+            play(&mut game, &mut log, player, LoggedAction::Move { hero, to });
+            play(&mut game, &mut log, player, LoggedAction::EndTurn);
+        }
+    }
+    log.final_hash = Some(world_hash(&game.snapshot()));
+
+    (game, log)
+}
+
+#[test]
+fn replaying_a_log_ends_in_the_same_world() {
+    let (_, log) = played_log();
+    let log = CommandLog::from_ron(&log.to_ron().unwrap()).unwrap();
+
+    let replayed = replay(&log).unwrap();
+
+    assert_eq!(replayed.turn(), 3);
+    assert_eq!(Some(world_hash(&replayed.snapshot())), log.final_hash);
+    assert_ne!(
+        Some(world_hash(&Game::new(&setup()).unwrap().snapshot())),
+        log.final_hash
+    );
+}
+
+#[test]
+fn replays_stop_at_commands_that_play_out_differently() {
+    let (game, log) = played_log();
+    let moved_to = game.hero(HeroId(0)).unwrap().hex;
+
+    let mut wrong_turn = CommandLog::new(setup());
+    wrong_turn.record(2, PlayerId(1), LoggedAction::EndTurn);
+    assert_eq!(
+        replay(&wrong_turn).err(),
+        Some(ReplayError::WrongTurn { index: 0, turn: 2 })
+    );
+
+    let mut rejected = log.clone();
+    rejected.record(
+        3,
+        PlayerId(2),
+        LoggedAction::Move {
+            hero: HeroId(1),
+            to: moved_to,
+        },
+    );
+    assert_eq!(
+        replay(&rejected).err(),
+        Some(ReplayError::Rejected {
+            index: log.commands.len(),
+            rejection: Rejection::NotYourTurn
+        })
+    );
+
+    let mut unsupported = CommandLog::new(setup());
+    unsupported.record(1, PlayerId(1), LoggedAction::Wait { hero: HeroId(0) });
+    assert_eq!(
+        replay(&unsupported).err(),
+        Some(ReplayError::Unsupported { index: 0 })
+    );
+}